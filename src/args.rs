@@ -1,10 +1,16 @@
 use clap; // an argument parser
 
+use crate::editor::quadratic::DEFAULT_QUADRATIC_TOLERANCE;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Args {
     pub filename: Option<String>,
     pub headless_mode: HeadlessMode,
     pub no_contour_ops: bool,
+    /// When set, flatten and export write quadratic contours within this tolerance.
+    pub quadratic: Option<f64>,
+    /// Recipe file for HeadlessMode::BuildComposites.
+    pub recipes: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -13,6 +19,7 @@ pub enum HeadlessMode {
     Flatten,
     Export,
     Save,
+    BuildComposites,
     RunScript, // unused until scripting support added
 }
 
@@ -32,7 +39,7 @@ pub fn parse_args() -> Args {
                 .long("flatten")
                 .short('f')
                 .takes_value(false)
-                .conflicts_with_all(&["save", "export"])
+                .conflicts_with_all(&["save", "export", "build-composites"])
                 .help(r#"Flatten glyph to current name (you should make a copy if not glifjson format)"#)
         )
         .arg(
@@ -56,6 +63,30 @@ pub fn parse_args() -> Args {
                 .takes_value(false)
                 .help(r#"For either the save or the export operation, remove all contour operations, don't apply them."#)
         )
+        .arg(
+            clap::Arg::new("quadratic")
+                .long("quadratic")
+                .short('q')
+                .takes_value(true)
+                .min_values(0)
+                // Otherwise `-q font.ufo/glyphs/a.glif` would take the .glif for the tolerance.
+                .require_equals(true)
+                .validator(|t| match t.parse::<f64>() {
+                    Ok(t) if t > 0. => Ok(()),
+                    _ => Err("TOLERANCE must be a positive number"),
+                })
+                .value_name("TOLERANCE")
+                .help(r#"For either the flatten or the export operation, write quadratic (TrueType) contours, approximating cubics within TOLERANCE units (default 1). Give it as --quadratic=TOLERANCE."#)
+        )
+        .arg(
+            clap::Arg::new("build-composites")
+                .long("build-composites")
+                .short('c')
+                .takes_value(true)
+                .value_name("RECIPES")
+                .conflicts_with_all(&["save", "export"])
+                .help(r#"Build the composite glyphs in RECIPES, one recipe like `Aacute = A + acutecomb@top` per line, in the glyphs directory GLIF is in (or is)."#)
+        )
         .get_matches();
 
     let headless_mode = if matches.is_present("export") {
@@ -64,16 +95,31 @@ pub fn parse_args() -> Args {
         HeadlessMode::Flatten
     } else if matches.is_present("save") {
         HeadlessMode::Save
+    } else if matches.is_present("build-composites") {
+        HeadlessMode::BuildComposites
     } else {
         HeadlessMode::None
     };
 
     let no_contour_ops = matches.is_present("no-contour-ops");
 
+    let quadratic = if matches.is_present("quadratic") {
+        // The validator has already checked any tolerance given.
+        Some(
+            matches
+                .value_of_t("quadratic")
+                .unwrap_or(DEFAULT_QUADRATIC_TOLERANCE),
+        )
+    } else {
+        None
+    };
+
     let args = Args {
         filename: matches.value_of("GLIF").map(|s| s.to_string()),
         headless_mode,
         no_contour_ops,
+        quadratic,
+        recipes: matches.value_of("build-composites").map(|s| s.to_string()),
     };
 
     args
@@ -55,9 +55,11 @@ pub enum Command {
     // tools
     ToolPan,
     ToolPen,
+    ToolPencil,
     ToolSelect,
     ToolZoom,
     ToolDash,
+    ToolNib,
     ToolPAP,
     ToolVWS,
     ToolMeasure,
@@ -90,6 +92,7 @@ pub enum Command {
     // view modes
     TogglePointLabels,
     TogglePreviewMode,
+    ToggleSnapping,
 
     // console
     ToggleConsole,
@@ -97,6 +100,11 @@ pub enum Command {
     // misc
     Quit,
     ReverseContour,
+    HarmonizeSelection,
+    SetStartPoint,
+    ToggleContourOpen,
+    SplitContour,
+    BuildComposite,
 
     // debug
     SkiaDump,
@@ -110,8 +118,8 @@ impl Command {
             NudgeUp | NudgeBigUp | NudgeTinyUp | NudgeDown | NudgeBigDown | NudgeTinyDown
             | NudgeLeft | NudgeBigLeft | NudgeTinyLeft | NudgeRight | NudgeBigRight
             | NudgeTinyRight => CommandType::Nudge,
-            ToolPan | ToolPen | ToolSelect | ToolZoom | ToolDash | ToolPAP | ToolVWS
-            | ToolMeasure | ToolAnchors | ToolShapes | ToolGuidelines | ToolImages => {
+            ToolPan | ToolPen | ToolPencil | ToolSelect | ToolZoom | ToolDash | ToolNib | ToolPAP
+            | ToolVWS | ToolMeasure | ToolAnchors | ToolShapes | ToolGuidelines | ToolImages => {
                 CommandType::ToolSelect
             }
             DeleteSelection
@@ -122,10 +130,15 @@ impl Command {
             | CutSelection => CommandType::Selection,
             HistoryUndo | HistoryRedo => CommandType::History,
             IOOpen | IOSave | IOSaveAs | IOFlatten | IOFlattenAs | IOExport => CommandType::IO,
-            ToolGrid | TogglePointLabels | TogglePreviewMode => CommandType::ViewMode,
+            ToolGrid | TogglePointLabels | TogglePreviewMode | ToggleSnapping => {
+                CommandType::ViewMode
+            }
             ToggleConsole => CommandType::ToggleConsole,
             Quit => CommandType::ExecState,
-            ReverseContour => CommandType::PathOp,
+            ReverseContour | HarmonizeSelection | SetStartPoint | ToggleContourOpen | SplitContour
+            | BuildComposite => {
+                CommandType::PathOp
+            },
             SkiaDump => CommandType::Debug,
         }
     }
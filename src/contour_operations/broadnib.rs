@@ -0,0 +1,425 @@
+// Sweeps a convex nib along a skeleton contour, as a broad-edged pen would. Along any stretch of the
+// skeleton the edge of the stroke on either side is traced by a single corner of the nib, the one
+// furthest out in that direction, so each side is made of exact translated copies of the skeleton.
+// The skeleton is split wherever its tangent is parallel to a side of the nib, as that's where the
+// tracing corner changes; corners of the skeleton and the ends of open strokes are joined by
+// walking around the nib. Overlaps, e.g. on the inside of tight curves, are removed by Skia.
+
+use flo_curves::BezierCurveFactory;
+use glifparser::glif::contour::MFEKContourCommon;
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::outline::skia::FromSkiaPath as _;
+use glifparser::{MFEKPointData, Outline};
+use skia_safe::Path;
+use MFEKmath::mfek::ResolveCubic;
+use MFEKmath::subdivide::Subdivide as _;
+use MFEKmath::{vec2, Bezier, Evaluate as _, Piecewise, Vector};
+
+use super::ContourOperationBuild;
+
+// Nibs thinner than this are thickened to it, so they always have an inside.
+const MIN_NIB_THICKNESS: f64 = 0.5;
+const HULL_SAMPLES: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NibShape {
+    /// A rectangular broad nib, `width` across and `height` thick, turned `angle` degrees.
+    Broad { width: f64, height: f64, angle: f64 },
+    /// Any convex polygon, around the nib's center.
+    Polygon(Vec<(f64, f64)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BroadNib {
+    pub shape: NibShape,
+}
+
+impl Default for BroadNib {
+    fn default() -> Self {
+        BroadNib {
+            shape: NibShape::Broad {
+                width: 60.,
+                height: 6.,
+                angle: 30.,
+            },
+        }
+    }
+}
+
+fn cross(a: Vector, b: Vector) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+/// The convex hull of `points`, counterclockwise.
+pub fn convex_hull(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let turn = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    // Andrew's monotone chain: the lower half left to right, then the upper half back.
+    let mut hull: Vec<(f64, f64)> = vec![];
+    for half in [points.clone(), points.iter().rev().copied().collect()] {
+        let start = hull.len();
+        for p in half {
+            while hull.len() >= start + 2
+                && turn(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point of each half is the first of the other.
+        hull.pop();
+    }
+    hull
+}
+
+impl BroadNib {
+    /// A nib the shape of `contour`, or rather of its convex hull, centered on the origin.
+    pub fn from_contour(contour: &MFEKContour<MFEKPointData>) -> Option<Self> {
+        let cubic = contour.to_cubic();
+        let segs = Piecewise::<Bezier>::from(cubic.cubic()?).segs;
+        let samples: Vec<(f64, f64)> = segs
+            .iter()
+            .flat_map(|b| {
+                (0..HULL_SAMPLES).map(move |k| {
+                    let p = b.at(k as f64 / HULL_SAMPLES as f64);
+                    (p.x, p.y)
+                })
+            })
+            .collect();
+        let hull = convex_hull(&samples);
+        if hull.len() < 3 {
+            return None;
+        }
+        let (min_x, max_x, min_y, max_y) = hull.iter().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(a, b, c, d), p| (a.min(p.0), b.max(p.0), c.min(p.1), d.max(p.1)),
+        );
+        let center = ((min_x + max_x) / 2., (min_y + max_y) / 2.);
+        Some(BroadNib {
+            shape: NibShape::Polygon(
+                hull.iter()
+                    .map(|p| (p.0 - center.0, p.1 - center.1))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// The corners of the nib, counterclockwise.
+    pub fn polygon(&self) -> Vec<Vector> {
+        let corners: Vec<(f64, f64)> = match &self.shape {
+            NibShape::Broad {
+                width,
+                height,
+                angle,
+            } => {
+                let (w, h) = (
+                    width.max(MIN_NIB_THICKNESS) / 2.,
+                    height.max(MIN_NIB_THICKNESS) / 2.,
+                );
+                let (sin, cos) = angle.to_radians().sin_cos();
+                [(-w, -h), (w, -h), (w, h), (-w, h)]
+                    .iter()
+                    .map(|(x, y)| (x * cos - y * sin, x * sin + y * cos))
+                    .collect()
+            }
+            NibShape::Polygon(points) => convex_hull(points),
+        };
+        corners.into_iter().map(|(x, y)| vec2!(x, y)).collect()
+    }
+}
+
+fn translate(b: &Bezier, by: Vector) -> Bezier {
+    <Bezier as BezierCurveFactory>::from_points(b.w1 + by, (b.w2 + by, b.w3 + by), b.w4 + by)
+}
+
+fn reverse(b: &Bezier) -> Bezier {
+    <Bezier as BezierCurveFactory>::from_points(b.w4, (b.w3, b.w2), b.w1)
+}
+
+fn line(from: Vector, to: Vector) -> Bezier {
+    let third = (to - from) * (1. / 3.);
+    <Bezier as BezierCurveFactory>::from_points(from, (from + third, to - third), to)
+}
+
+fn tangent_at(b: &Bezier, t: f64) -> Vector {
+    let mt = 1. - t;
+    (b.w2 - b.w1) * (mt * mt) + (b.w3 - b.w2) * (2. * mt * t) + (b.w4 - b.w3) * (t * t)
+}
+
+// Tangents at the ends, falling back on the next control point where a handle is retracted.
+fn start_tangent(b: &Bezier) -> Vector {
+    [b.w2, b.w3, b.w4]
+        .iter()
+        .map(|p| *p - b.w1)
+        .find(|d| d.x.hypot(d.y) > f64::EPSILON)
+        .unwrap_or(vec2!(0., 0.))
+}
+
+fn end_tangent(b: &Bezier) -> Vector {
+    [b.w3, b.w2, b.w1]
+        .iter()
+        .map(|p| b.w4 - *p)
+        .find(|d| d.x.hypot(d.y) > f64::EPSILON)
+        .unwrap_or(vec2!(0., 0.))
+}
+
+/// Times in (0, 1) where the tangent of `b` is parallel to `direction`.
+fn parallel_times(b: &Bezier, direction: Vector) -> Vec<f64> {
+    // The tangent's cross product with `direction` is a quadratic in Bernstein form.
+    let (a, m, c) = (
+        cross(b.w2 - b.w1, direction),
+        cross(b.w3 - b.w2, direction),
+        cross(b.w4 - b.w3, direction),
+    );
+    let (qa, qb, qc) = (a - 2. * m + c, 2. * (m - a), a);
+    let roots = if qa.abs() < 1e-12 {
+        if qb.abs() < 1e-12 {
+            vec![]
+        } else {
+            vec![-qc / qb]
+        }
+    } else {
+        let disc = qb * qb - 4. * qa * qc;
+        if disc < 0. {
+            vec![]
+        } else {
+            let sq = disc.sqrt();
+            vec![(-qb - sq) / (2. * qa), (-qb + sq) / (2. * qa)]
+        }
+    };
+    roots
+        .into_iter()
+        .filter(|t| *t > 1e-6 && *t < 1. - 1e-6)
+        .collect()
+}
+
+/// Which corner of the nib lies furthest in `direction`.
+fn support(nib: &[Vector], direction: Vector) -> usize {
+    (0..nib.len())
+        .max_by(|&a, &b| {
+            dot(nib[a], direction)
+                .partial_cmp(&dot(nib[b], direction))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap()
+}
+
+/// Lines from corner `from` of the nib around to corner `to`, with the nib centered on `at`,
+/// walking counterclockwise if `ccw`.
+fn walk(nib: &[Vector], at: Vector, from: usize, to: usize, ccw: bool) -> Vec<Bezier> {
+    let n = nib.len();
+    let mut ret = vec![];
+    let mut k = from;
+    while k != to {
+        let next = if ccw { (k + 1) % n } else { (k + n - 1) % n };
+        ret.push(line(at + nib[k], at + nib[next]));
+        k = next;
+    }
+    ret
+}
+
+// Between two pieces of skeleton the corners turn the way the skeleton does; where it's smooth,
+// the tracing corner only moves to a neighbour.
+fn turns_ccw(nib: &[Vector], before: &Bezier, after: &Bezier, from: usize, to: usize) -> bool {
+    let (t0, t1) = (end_tangent(before), start_tangent(after));
+    let turn = cross(t0, t1) / (t0.x.hypot(t0.y) * t1.x.hypot(t1.y)).max(f64::EPSILON);
+    if turn.abs() > 1e-6 {
+        return turn > 0.;
+    }
+    let n = nib.len();
+    (to + n - from) % n <= (from + n - to) % n
+}
+
+/// The outline of `nib` swept along `segs`, as closed loops of cubics.
+pub fn sweep(segs: &[Bezier], closed: bool, nib: &[Vector]) -> Vec<Vec<Bezier>> {
+    let n = nib.len();
+    let edges: Vec<Vector> = (0..n).map(|k| nib[(k + 1) % n] - nib[k]).collect();
+
+    let mut pieces: Vec<Bezier> = vec![];
+    for seg in segs {
+        let mut times: Vec<f64> = edges.iter().flat_map(|e| parallel_times(seg, *e)).collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        times.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+        let split = if times.is_empty() {
+            vec![seg.clone()]
+        } else {
+            seg.split_at_multiple_t(times)
+        };
+        pieces.extend(split.into_iter().filter(|b| {
+            let t = start_tangent(b);
+            t.x.hypot(t.y) > f64::EPSILON
+        }));
+    }
+    if pieces.is_empty() {
+        return vec![];
+    }
+
+    // The corners tracing the left and right side of each piece.
+    let corners: Vec<(usize, usize)> = pieces
+        .iter()
+        .map(|b| {
+            let t = tangent_at(b, 0.5);
+            let left = vec2!(-t.y, t.x);
+            (support(nib, left), support(nib, left * -1.))
+        })
+        .collect();
+
+    let mut left: Vec<Bezier> = vec![];
+    let mut right: Vec<Bezier> = vec![];
+    for (idx, piece) in pieces.iter().enumerate() {
+        if idx > 0 || closed {
+            let prev = (idx + pieces.len() - 1) % pieces.len();
+            let ccw_l = turns_ccw(nib, &pieces[prev], piece, corners[prev].0, corners[idx].0);
+            let ccw_r = turns_ccw(nib, &pieces[prev], piece, corners[prev].1, corners[idx].1);
+            left.extend(walk(nib, piece.w1, corners[prev].0, corners[idx].0, ccw_l));
+            right.extend(walk(nib, piece.w1, corners[prev].1, corners[idx].1, ccw_r));
+        }
+        left.push(translate(piece, nib[corners[idx].0]));
+        right.push(translate(piece, nib[corners[idx].1]));
+    }
+    let right_reversed: Vec<Bezier> = right.iter().rev().map(reverse).collect();
+
+    if closed {
+        return vec![left, right_reversed];
+    }
+
+    // Caps go around the front of the nib at the end, and around its back at the start, both
+    // clockwise from the left side to the right.
+    let (first, last) = (&pieces[0], &pieces[pieces.len() - 1]);
+    let (first_corners, last_corners) = (corners[0], corners[corners.len() - 1]);
+    let mut outline = left;
+    outline.extend(walk(nib, last.w4, last_corners.0, last_corners.1, false));
+    outline.extend(right_reversed);
+    outline.extend(walk(nib, first.w1, first_corners.1, first_corners.0, false));
+    vec![outline]
+}
+
+fn to_skia_path(loops: &[Vec<Bezier>]) -> Path {
+    let mut path = Path::new();
+    for beziers in loops.iter().filter(|l| !l.is_empty()) {
+        let p = |v: Vector| (v.x as f32, v.y as f32);
+        path.move_to(p(beziers[0].w1));
+        for b in beziers {
+            path.cubic_to(p(b.w2), p(b.w3), p(b.w4));
+        }
+        path.close();
+    }
+    path
+}
+
+impl ContourOperationBuild for BroadNib {
+    fn build(&self, contour: &MFEKContour<MFEKPointData>) -> MFEKOutline<MFEKPointData> {
+        let cubic = contour.to_cubic();
+        let segs = Piecewise::<Bezier>::from(cubic.cubic().unwrap()).segs;
+        let nib = self.polygon();
+        if nib.len() < 3 {
+            return vec![contour.clone()];
+        }
+
+        let path = to_skia_path(&sweep(&segs, contour.is_closed(), &nib));
+        let path = match path.simplify() {
+            Some(simplified) => simplified,
+            None => path,
+        };
+        let outline: Outline<MFEKPointData> = Outline::from_skia_path(&path);
+        outline.iter().map(|c| c.into()).collect()
+    }
+}
+
+#[cfg(test)]
+fn square_nib(half: f64) -> Vec<Vector> {
+    BroadNib {
+        shape: NibShape::Polygon(vec![
+            (-half, -half),
+            (half, -half),
+            (half, half),
+            (-half, half),
+        ]),
+    }
+    .polygon()
+}
+
+#[test]
+fn convex_hull_drops_inner_and_collinear_points() {
+    let points = [
+        (0., 0.),
+        (2., 0.),
+        (1., 0.),
+        (2., 2.),
+        (0., 2.),
+        (1., 1.),
+        (0., 0.),
+    ];
+    assert_eq!(
+        convex_hull(&points),
+        vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]
+    );
+    assert_eq!(convex_hull(&[(1., 1.), (1., 1.)]), vec![(1., 1.)]);
+}
+
+#[test]
+fn parallel_times_of_an_arch() {
+    let arch = <Bezier as BezierCurveFactory>::from_points(
+        vec2!(0., 0.),
+        (vec2!(0., 1.), vec2!(1., 1.)),
+        vec2!(1., 0.),
+    );
+    let times = parallel_times(&arch, vec2!(1., 0.));
+    assert_eq!(times.len(), 1);
+    assert!((times[0] - 0.5).abs() < 1e-9);
+    // Vertical only at its ends, which aren't counted.
+    assert!(parallel_times(&arch, vec2!(0., 1.)).is_empty());
+    // A straight line is parallel to its own direction everywhere, and split nowhere.
+    assert!(parallel_times(&line(vec2!(0., 0.), vec2!(1., 0.)), vec2!(1., 0.)).is_empty());
+}
+
+#[test]
+fn sweep_square_nib_along_lines() {
+    let nib = square_nib(5.);
+    let joined = |beziers: &[Bezier]| {
+        beziers
+            .iter()
+            .zip(beziers.iter().cycle().skip(1))
+            .all(|(a, b)| (a.w4.x - b.w1.x).hypot(a.w4.y - b.w1.y) < 1e-9)
+    };
+
+    // An open stroke is one loop, capped at both ends by the nib.
+    let stroke = sweep(&[line(vec2!(0., 0.), vec2!(100., 0.))], false, &nib);
+    assert_eq!(stroke.len(), 1);
+    assert!(joined(&stroke[0]));
+    let (xs, ys): (Vec<f64>, Vec<f64>) = stroke[0].iter().map(|b| (b.w1.x, b.w1.y)).unzip();
+    let min = |v: &[f64]| v.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = |v: &[f64]| v.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    assert_eq!((min(&xs), max(&xs)), (-5., 105.));
+    assert_eq!((min(&ys), max(&ys)), (-5., 5.));
+
+    // A closed skeleton has an outside and an inside edge.
+    let corners = [
+        vec2!(0., 0.),
+        vec2!(100., 0.),
+        vec2!(100., 100.),
+        vec2!(0., 100.),
+    ];
+    let segs: Vec<Bezier> = (0..4)
+        .map(|k| line(corners[k], corners[(k + 1) % 4]))
+        .collect();
+    let ring = sweep(&segs, true, &nib);
+    assert_eq!(ring.len(), 2);
+    assert!(ring.iter().all(|edge| joined(edge)));
+}
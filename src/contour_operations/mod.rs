@@ -1,3 +1,4 @@
+pub mod broadnib;
 pub mod dashalongpath;
 pub mod patternalongpath;
 pub mod variablewidthstroke;
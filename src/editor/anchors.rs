@@ -0,0 +1,118 @@
+// Glyph-level anchor edits, and the checks the anchor panel warns about. Anchors are kept on whole
+// units, like `tools::anchors` does, as OpenType anchors can't be fractional.
+
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::{Anchor, MFEKPointData};
+use skia_safe::Rect;
+
+use std::collections::{HashMap, HashSet};
+
+use super::Editor;
+
+/// Names offered for quick adding, in the order they're shown.
+pub const ANCHOR_PRESETS: &[&str] = &["top", "bottom", "ogonek", "_top", "_bottom", "_ogonek"];
+
+impl Editor {
+    /// Bounds of the active layer's outline together with the glyph's components.
+    pub fn outline_bounds(&self) -> Option<Rect> {
+        let outline = &self.get_active_layer_ref().outline;
+        let mut bounds = if outline.is_empty() {
+            None
+        } else {
+            Some(outline.to_skia_paths(None).combined().compute_tight_bounds())
+        };
+        let components = self.with_glyph(|g| g.components.vec.len());
+        for idx in 0..components {
+            if let Some(rect) = self.component_rect(idx) {
+                bounds = Some(bounds.map_or(rect, |b| {
+                    Rect::new(
+                        b.left.min(rect.left),
+                        b.top.min(rect.top),
+                        b.right.max(rect.right),
+                        b.bottom.max(rect.bottom),
+                    )
+                }));
+            }
+        }
+        bounds
+    }
+
+    /// Where a preset anchor goes on this glyph: base anchors at the edges marks attach to, mark
+    /// anchors at the opposite edge of the mark.
+    pub fn preset_anchor_position(&self, name: &str) -> (f32, f32) {
+        let bounds = self.outline_bounds().unwrap_or_default();
+        // Skia rects are y-down, so `bottom` is the top of the glyph.
+        let cx = bounds.center_x();
+        let position = match name {
+            "top" | "_bottom" => (cx, bounds.bottom),
+            "bottom" => (cx, 0.),
+            "_top" => (cx, bounds.top),
+            "ogonek" => (bounds.right, 0.),
+            "_ogonek" => (cx, bounds.bottom),
+            _ => (cx, bounds.center_y()),
+        };
+        (position.0.floor(), position.1.floor())
+    }
+
+    pub fn add_anchor(&mut self, name: &str, position: (f32, f32)) {
+        self.begin_modification("Add anchor.", false);
+        self.with_glyph_mut(|glif| {
+            let mut anchor = Anchor::default();
+            anchor.x = position.0.floor();
+            anchor.y = position.1.floor();
+            anchor.class = Some(name.to_string());
+            glif.anchors.push(anchor);
+        });
+        self.end_modification();
+    }
+
+    pub fn set_anchor(&mut self, idx: usize, anchor: Anchor<MFEKPointData>) {
+        self.begin_modification("Edit anchor.", true);
+        self.with_glyph_mut(|glif| {
+            glif.anchors[idx] = anchor.clone();
+            glif.anchors[idx].x = anchor.x.floor();
+            glif.anchors[idx].y = anchor.y.floor();
+        });
+        self.end_modification();
+    }
+
+    pub fn remove_anchor(&mut self, idx: usize) {
+        self.begin_modification("Delete anchor.", false);
+        self.with_glyph_mut(|glif| {
+            glif.anchors.remove(idx);
+        });
+        self.end_modification();
+    }
+}
+
+/// Problems with `anchors`: repeated names, and mark anchors (`_top`) for which no glyph in
+/// `base_names` has the base anchor (`top`) they'd attach to.
+pub fn anchor_warnings(
+    anchors: &[Anchor<MFEKPointData>],
+    base_names: &HashSet<String>,
+) -> Vec<String> {
+    let mut warnings = vec![];
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for anchor in anchors {
+        match &anchor.class {
+            Some(name) => *counts.entry(name.as_str()).or_default() += 1,
+            None => warnings.push("An anchor has no name".to_string()),
+        }
+    }
+    let mut duplicates: Vec<_> = counts.iter().filter(|(_, n)| **n > 1).collect();
+    duplicates.sort();
+    for (name, n) in duplicates {
+        warnings.push(format!("{} anchors are named {}", n, name));
+    }
+
+    let mut marks: Vec<_> = counts.keys().filter(|n| n.starts_with('_')).collect();
+    marks.sort();
+    for mark in marks {
+        if !base_names.contains(&mark[1..]) {
+            warnings.push(format!("No glyph has a {} anchor for {} to attach to", &mark[1..], mark));
+        }
+    }
+
+    warnings
+}
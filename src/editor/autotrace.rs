@@ -0,0 +1,359 @@
+// Tracing placed bitmaps into contours. The image is thresholded into ink and paper, the boundaries
+// between them are followed along pixel edges into closed polygons, and those are smoothed, split at
+// corners and fit with cubic Béziers. Everything is done in pixel space and mapped through the
+// image's matrix at the end, as Béziers map exactly under an affine transform.
+
+use flo_curves::bezier::fit_curve_cubic;
+use flo_curves::BezierCurveFactory;
+use glifparser::matrix::ToSkiaMatrix;
+use glifparser::{glif::MFEKContour, MFEKPointData};
+use skia_safe::{EncodedOrigin, Matrix};
+use MFEKmath::{vec2, Bezier, Piecewise, Vector};
+
+use std::collections::HashMap;
+
+use super::Editor;
+
+#[derive(Clone, Debug)]
+pub struct AutotraceSettings {
+    /// Pixels darker than this (0–255, composited over white) are ink.
+    pub threshold: u8,
+    /// The outline has a corner wherever it turns by more than this many degrees.
+    pub corner_angle: f64,
+    /// How far, in pixels, fitted curves may stray from the traced boundary.
+    pub tolerance: f64,
+    /// Boundaries shorter than this many pixel edges are dust, and dropped.
+    pub despeckle: usize,
+}
+
+impl Default for AutotraceSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 128,
+            corner_angle: 60.,
+            tolerance: 1.,
+            despeckle: 8,
+        }
+    }
+}
+
+type Lattice = (i32, i32);
+
+// `pixels` is RGBA8888 with premultiplied alpha, as decoded for the editor.
+fn is_ink(pixels: &[u8], width: usize, height: usize, x: i32, y: i32, threshold: u8) -> bool {
+    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+        return false;
+    }
+    let i = (y as usize * width + x as usize) * 4;
+    let paper = 255 - pixels[i + 3] as u32;
+    let (r, g, b) = (
+        pixels[i] as u32 + paper,
+        pixels[i + 1] as u32 + paper,
+        pixels[i + 2] as u32 + paper,
+    );
+    let luminance = (299 * r + 587 * g + 114 * b) / 1000;
+    luminance < threshold as u32
+}
+
+/// Follows the edges between ink and paper pixels into closed loops of pixel corners, with ink
+/// always on the same side, so holes wind opposite to the shapes around them.
+fn trace_boundaries(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    threshold: u8,
+) -> Vec<Vec<Lattice>> {
+    let ink = |x: i32, y: i32| is_ink(pixels, width, height, x, y, threshold);
+
+    let mut edges: HashMap<Lattice, Vec<Lattice>> = HashMap::new();
+    let mut add = |from: Lattice, to: Lattice| edges.entry(from).or_default().push(to);
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            if !ink(x, y) {
+                continue;
+            }
+            if !ink(x, y - 1) {
+                add((x, y), (x + 1, y));
+            }
+            if !ink(x + 1, y) {
+                add((x + 1, y), (x + 1, y + 1));
+            }
+            if !ink(x, y + 1) {
+                add((x + 1, y + 1), (x, y + 1));
+            }
+            if !ink(x - 1, y) {
+                add((x, y + 1), (x, y));
+            }
+        }
+    }
+
+    let mut starts: Vec<Lattice> = edges.keys().copied().collect();
+    starts.sort();
+
+    let mut loops = vec![];
+    for start in starts {
+        while edges.get(&start).map_or(false, |e| !e.is_empty()) {
+            let mut boundary = vec![start];
+            let mut at = start;
+            let mut direction = (0, 0);
+            loop {
+                let outgoing = match edges.get_mut(&at) {
+                    Some(outgoing) if !outgoing.is_empty() => outgoing,
+                    _ => break,
+                };
+                // Where two ink pixels touch only at a corner, two boundaries pass through it.
+                // Turning the same way every time keeps those pixels apart.
+                let pick = (0..outgoing.len())
+                    .max_by_key(|&i| {
+                        let d = (outgoing[i].0 - at.0, outgoing[i].1 - at.1);
+                        direction.0 * d.1 - direction.1 * d.0
+                    })
+                    .unwrap();
+                let next = outgoing.swap_remove(pick);
+                direction = (next.0 - at.0, next.1 - at.1);
+                at = next;
+                if at == start {
+                    break;
+                }
+                boundary.push(at);
+            }
+            loops.push(boundary);
+        }
+    }
+    loops
+}
+
+fn turn_angle(before: Vector, at: Vector, after: Vector) -> f64 {
+    let (a, b) = (at - before, after - at);
+    let cross = a.x * b.y - a.y * b.x;
+    let dot = a.x * b.x + a.y * b.y;
+    cross.atan2(dot).abs().to_degrees()
+}
+
+fn line(from: Vector, to: Vector) -> Bezier {
+    let third = (to - from) * (1. / 3.);
+    <Bezier as BezierCurveFactory>::from_points(from, (from + third, to - third), to)
+}
+
+// The direction from `from` to the first of `towards` that isn't on top of it.
+fn direction<'a>(from: Vector, mut towards: impl Iterator<Item = &'a Vector>) -> Option<Vector> {
+    towards
+        .find(|p| p.distance(from) > f64::EPSILON)
+        .map(|p| (*p - from).normalize())
+}
+
+fn fit_run(run: &[Vector], tolerance: f64) -> Vec<Bezier> {
+    // Samples on top of each other have no direction between them, and would fit to NaNs.
+    let mut run = run.to_vec();
+    run.dedup_by(|a, b| a.distance(*b) <= f64::EPSILON);
+    let (first, last) = (run[0], run[run.len() - 1]);
+    if run.len() < 4 {
+        return vec![line(first, last)];
+    }
+    let reach = 3;
+    let tangents = (
+        direction(first, run[reach..].iter()),
+        direction(last, run[..run.len() - reach].iter().rev()),
+    );
+    match tangents {
+        (Some(start_tangent), Some(end_tangent)) => {
+            fit_curve_cubic(&run, &start_tangent, &end_tangent, tolerance)
+        }
+        _ => vec![line(first, last)],
+    }
+}
+
+/// Fits a traced boundary with cubics, in pixel space.
+fn fit_boundary(boundary: &[Lattice], settings: &AutotraceSettings) -> Vec<Bezier> {
+    let n = boundary.len();
+    let raw: Vec<Vector> = boundary
+        .iter()
+        .map(|(x, y)| vec2!(*x as f64, *y as f64))
+        .collect();
+
+    // Average away the pixel stairs.
+    let smoothed: Vec<Vector> = (0..n)
+        .map(|i| {
+            let sum = (0..5).fold(vec2!(0., 0.), |sum, j| sum + raw[(i + n + j - 2) % n]);
+            sum * (1. / 5.)
+        })
+        .collect();
+
+    let k = 3.min(n / 4).max(1);
+    let turns: Vec<f64> = (0..n)
+        .map(|i| turn_angle(smoothed[(i + n - k) % n], smoothed[i], smoothed[(i + k) % n]))
+        .collect();
+    let corners: Vec<usize> = (0..n)
+        .filter(|&i| {
+            turns[i] > settings.corner_angle
+                && (1..=k).all(|j| turns[i] >= turns[(i + j) % n] && turns[i] > turns[(i + n - j) % n])
+        })
+        .collect();
+
+    let mut points = smoothed;
+    for &corner in corners.iter() {
+        points[corner] = raw[corner];
+    }
+
+    // Without corners the loop is cut anywhere; the fit starts and ends on the same tangent there.
+    let splits = if corners.is_empty() { vec![0] } else { corners };
+    let mut beziers = vec![];
+    for (idx, &from) in splits.iter().enumerate() {
+        let to = splits[(idx + 1) % splits.len()];
+        let len = (to + n - from) % n;
+        let len = if len == 0 { n } else { len };
+        let run: Vec<Vector> = (0..=len).map(|j| points[(from + j) % n]).collect();
+        beziers.extend(fit_run(&run, settings.tolerance));
+    }
+    beziers
+}
+
+/// Traces the ink of an RGBA8888 bitmap into closed cubic contours, in pixel coordinates.
+pub fn autotrace(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    settings: &AutotraceSettings,
+) -> Vec<Vec<Bezier>> {
+    trace_boundaries(pixels, width, height, settings.threshold)
+        .into_iter()
+        .filter(|boundary| boundary.len() >= settings.despeckle.max(4))
+        .map(|boundary| fit_boundary(&boundary, settings))
+        .collect()
+}
+
+fn map_bezier(matrix: &Matrix, bezier: &Bezier) -> Bezier {
+    let map = |v: Vector| {
+        let p = matrix.map_xy(v.x as f32, v.y as f32);
+        vec2!(p.x as f64, p.y as f64)
+    };
+    <Bezier as BezierCurveFactory>::from_points(
+        map(bezier.w1),
+        (map(bezier.w2), map(bezier.w3)),
+        map(bezier.w4),
+    )
+}
+
+impl Editor {
+    /// Traces the active layer's image `idx` and adds the result to the active layer. Returns the
+    /// number of contours added.
+    pub fn autotrace_image(&mut self, idx: usize, settings: &AutotraceSettings) -> usize {
+        let (image, i_matrix) = self.get_active_layer_ref().images[idx].clone();
+        let editor_image = match self.images.get(&image.filename) {
+            Some(editor_image) => editor_image,
+            None => {
+                log::error!("Image {:?} isn't loaded, can't trace it", image.filename);
+                return 0;
+            }
+        };
+        let (width, height) = (editor_image.img.width(), editor_image.img.height());
+
+        // Same transform the image is drawn with in `render::render_frame`.
+        let matrix = i_matrix.to_skia_matrix()
+            * EncodedOrigin::to_matrix(EncodedOrigin::BottomLeft, (width, height));
+
+        let traced = autotrace(
+            &editor_image.data,
+            width as usize,
+            height as usize,
+            settings,
+        );
+        let contours: Vec<MFEKContour<MFEKPointData>> = traced
+            .iter()
+            .filter(|beziers| !beziers.is_empty())
+            .map(|beziers| {
+                let beziers = beziers.iter().map(|b| map_bezier(&matrix, b)).collect();
+                Piecewise::new(beziers, None).to_contour().into()
+            })
+            .collect();
+
+        let count = contours.len();
+        if count == 0 {
+            log::warn!("Found nothing to trace in {:?}", image.filename);
+            return 0;
+        }
+        self.begin_modification("Autotrace image.", false);
+        self.get_active_layer_mut().outline.extend(contours);
+        self.end_modification();
+        count
+    }
+}
+
+#[cfg(test)]
+fn bitmap(width: usize, height: usize, ink: impl Fn(usize, usize) -> bool) -> Vec<u8> {
+    (0..width * height)
+        .flat_map(|i| match ink(i % width, i / width) {
+            true => [0, 0, 0, 255],
+            false => [0, 0, 0, 0],
+        })
+        .collect()
+}
+
+#[test]
+fn trace_square_and_ring() {
+    let square = bitmap(8, 8, |x, y| (2..6).contains(&x) && (2..6).contains(&y));
+    let loops = trace_boundaries(&square, 8, 8, 128);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 16);
+
+    // A ring's hole winds the other way from its outside.
+    let ring = bitmap(12, 12, |x, y| {
+        let inside = |lo, hi| (lo..hi).contains(&x) && (lo..hi).contains(&y);
+        inside(2, 10) && !inside(4, 8)
+    });
+    let loops = trace_boundaries(&ring, 12, 12, 128);
+    assert_eq!(loops.len(), 2);
+    let area = |boundary: &Vec<Lattice>| {
+        (0..boundary.len())
+            .map(|i| {
+                let ((x0, y0), (x1, y1)) = (boundary[i], boundary[(i + 1) % boundary.len()]);
+                x0 * y1 - x1 * y0
+            })
+            .sum::<i32>()
+    };
+    let mut areas: Vec<i32> = loops.iter().map(area).collect();
+    areas.sort_by_key(|a| a.abs());
+    assert_eq!(areas[0].abs(), 2 * 16);
+    assert_eq!(areas[1].abs(), 2 * 64);
+    assert_ne!(areas[0].signum(), areas[1].signum());
+}
+
+#[test]
+fn fit_square_boundary() {
+    let square = bitmap(8, 8, |x, y| (2..6).contains(&x) && (2..6).contains(&y));
+    let boundary = &trace_boundaries(&square, 8, 8, 128)[0];
+    let beziers = fit_boundary(boundary, &AutotraceSettings::default());
+    assert!(!beziers.is_empty());
+
+    // The fit is closed, and keeps to within a pixel of the square's edge.
+    let off_edge = |p: Vector| {
+        let inside = (p.x - 2.).min(6. - p.x).min(p.y - 2.).min(6. - p.y);
+        let outside = (2. - p.x).max(p.x - 6.).max(2. - p.y).max(p.y - 6.);
+        if outside > 0. {
+            outside
+        } else {
+            inside
+        }
+    };
+    for (idx, bezier) in beziers.iter().enumerate() {
+        for p in [bezier.w1, bezier.w2, bezier.w3, bezier.w4] {
+            assert!(p.x.is_finite() && p.y.is_finite());
+        }
+        assert!(off_edge(bezier.w1) <= 1.);
+        let next = &beziers[(idx + 1) % beziers.len()];
+        assert!(bezier.w4.distance(next.w1) < 1e-9);
+    }
+}
+
+#[test]
+fn fit_run_with_repeated_samples() {
+    let run: Vec<Vector> = [(0., 0.), (0., 0.), (1., 0.), (1., 0.), (2., 1.), (3., 3.)]
+        .iter()
+        .map(|(x, y)| vec2!(*x, *y))
+        .collect();
+    for bezier in fit_run(&run, 0.5) {
+        for p in [bezier.w1, bezier.w2, bezier.w3, bezier.w4] {
+            assert!(p.x.is_finite() && p.y.is_finite());
+        }
+    }
+}
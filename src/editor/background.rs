@@ -0,0 +1,112 @@
+// The background glyph: another glyph drawn faintly under the outline to draw over, e.g. `h` under
+// `n`. It is remembered in the glyph lib under BACKGROUND_LIB_KEY.
+
+use glifparser::glif::Lib;
+use plist::{Dictionary, Value};
+
+use super::util::glyph_lib_mut;
+use super::Editor;
+
+pub const BACKGROUND_LIB_KEY: &str = "org.MFEK.background";
+
+/// Where the background glyph comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackgroundSource {
+    /// A glyph of this glyph's UFO, by name.
+    Ufo(String),
+    /// The glyph for `character` in a system font, found by family name.
+    System { family: String, character: char },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Background {
+    pub source: BackgroundSource,
+    pub opacity: f32,
+    pub offset: (f32, f32),
+}
+
+impl Background {
+    pub fn new(source: BackgroundSource) -> Self {
+        Background {
+            source,
+            opacity: 0.25,
+            offset: (0., 0.),
+        }
+    }
+
+    fn from_dict(dict: &Dictionary) -> Option<Self> {
+        let real = |key: &str| {
+            let value = dict.get(key)?;
+            let real = value.as_real().or_else(|| value.as_signed_integer().map(|i| i as f64));
+            real.map(|r| r as f32)
+        };
+        let source = match (dict.get("glyph"), dict.get("family"), dict.get("character")) {
+            (Some(glyph), _, _) => BackgroundSource::Ufo(glyph.as_string()?.to_string()),
+            (None, Some(family), Some(character)) => BackgroundSource::System {
+                family: family.as_string()?.to_string(),
+                character: character.as_string()?.chars().next()?,
+            },
+            _ => return None,
+        };
+        Some(Background {
+            source,
+            opacity: real("opacity").unwrap_or(0.25).clamp(0., 1.),
+            offset: (real("x").unwrap_or(0.), real("y").unwrap_or(0.)),
+        })
+    }
+
+    fn to_dict(&self) -> Dictionary {
+        let mut dict = Dictionary::new();
+        match &self.source {
+            BackgroundSource::Ufo(glyph) => {
+                dict.insert("glyph".to_string(), Value::String(glyph.clone()));
+            }
+            BackgroundSource::System { family, character } => {
+                dict.insert("family".to_string(), Value::String(family.clone()));
+                dict.insert("character".to_string(), Value::String(character.to_string()));
+            }
+        }
+        dict.insert("opacity".to_string(), Value::Real(self.opacity as f64));
+        dict.insert("x".to_string(), Value::Real(self.offset.0 as f64));
+        dict.insert("y".to_string(), Value::Real(self.offset.1 as f64));
+        dict
+    }
+}
+
+impl Editor {
+    pub fn background(&self) -> Option<Background> {
+        self.with_glyph(|glyph| match &glyph.lib {
+            Lib::Plist(lib) => lib
+                .get(BACKGROUND_LIB_KEY)
+                .and_then(Value::as_dictionary)
+                .and_then(Background::from_dict),
+            _ => None,
+        })
+    }
+
+    /// Sets or, with `None`, removes the background glyph.
+    pub fn set_background(&mut self, background: Option<Background>) {
+        if background == self.background() {
+            return;
+        }
+        self.begin_modification("Set background glyph.", true);
+        self.with_glyph_mut(|glyph| {
+            let lib = match glyph_lib_mut(glyph) {
+                Some(lib) => lib,
+                None => return,
+            };
+            match &background {
+                Some(background) => {
+                    lib.insert(
+                        BACKGROUND_LIB_KEY.to_string(),
+                        Value::Dictionary(background.to_dict()),
+                    );
+                }
+                None => {
+                    lib.remove(BACKGROUND_LIB_KEY);
+                }
+            }
+        });
+        self.end_modification();
+    }
+}
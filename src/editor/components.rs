@@ -0,0 +1,234 @@
+// Editing of a glyph's components: inserting them by glyph name, changing their transformation,
+// decomposing them into contours and finding the .glif file they refer to.
+
+use glifparser::{
+    glif::contour::MFEKContourCommon, FlattenedGlif as _, Glif, GlifComponent, Handle,
+    IntegerOrFloat, MFEKPointData, Point,
+};
+use kurbo::Affine;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{Editor, SkPoint, SkRect};
+use skia_safe::Contains as _;
+
+pub(crate) fn transform_point(point: &mut Point<MFEKPointData>, affine: Affine) {
+    let p = affine * kurbo::Point::new(point.x as f64, point.y as f64);
+    point.x = p.x as f32;
+    point.y = p.y as f32;
+    for handle in [&mut point.a, &mut point.b] {
+        if let Handle::At(hx, hy) = *handle {
+            let h = affine * kurbo::Point::new(hx as f64, hy as f64);
+            *handle = Handle::At(h.x as f32, h.y as f32);
+        }
+    }
+}
+
+/// Splits a component matrix into offset, scale, rotation (in degrees) and skew, such that
+/// `compose_matrix` gives the same matrix back.
+pub fn decompose_matrix(affine: Affine) -> ((f64, f64), (f64, f64), f64, f64) {
+    let [a, b, c, d, e, f] = affine.as_coeffs();
+    let sx = a.hypot(b);
+    let angle = b.atan2(a);
+    let (sin, cos) = angle.sin_cos();
+    let skew = cos * c + sin * d;
+    let sy = cos * d - sin * c;
+    ((e, f), (sx, sy), angle.to_degrees(), skew)
+}
+
+pub fn compose_matrix(offset: (f64, f64), scale: (f64, f64), rotation: f64, skew: f64) -> Affine {
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (sx, sy) = scale;
+    Affine::new([
+        cos * sx,
+        sin * sx,
+        cos * skew - sin * sy,
+        sin * skew + cos * sy,
+        offset.0,
+        offset.1,
+    ])
+}
+
+/// Finds the .glif file of the glyph named `name` in the glyphs directory `dir`, looking first in
+/// its contents.plist and then for a file with the same name as the glyph.
+pub fn glyph_filename_in(dir: &Path, name: &str) -> Option<PathBuf> {
+    let contents: Option<HashMap<String, String>> =
+        plist::from_file(dir.join("contents.plist")).ok();
+    if let Some(filename) = contents.as_ref().and_then(|c| c.get(name)) {
+        return Some(dir.join(filename));
+    }
+
+    let fallback = dir.join(format!("{}.glif", name));
+    fallback.exists().then(|| fallback)
+}
+
+pub fn set_matrix(component: &mut GlifComponent, affine: Affine) {
+    let [a, b, c, d, e, f] = affine.as_coeffs().map(|n| IntegerOrFloat::Float(n as f32));
+    component.xScale = a;
+    component.xyScale = b;
+    component.yxScale = c;
+    component.yScale = d;
+    component.xOffset = e;
+    component.yOffset = f;
+}
+
+impl Editor {
+    /// The directory the current glyph was loaded from; in a UFO, its glyphs directory.
+    pub fn glyphs_dir(&self) -> Option<PathBuf> {
+        self.with_glyph(|g| g.filename.as_ref()?.parent().map(|p| p.to_path_buf()))
+    }
+
+    /// Finds the .glif file of the glyph named `name` beside the current glyph.
+    pub fn glyph_filename(&self, name: &str) -> Option<PathBuf> {
+        glyph_filename_in(&self.glyphs_dir()?, name)
+    }
+
+    /// The bounds of component `idx` as last cached by `Editor::rebuild`.
+    pub fn component_rect(&self, idx: usize) -> Option<SkRect> {
+        self.with_glyph(|glyph| {
+            let rects = glyph.component_rects.as_ref()?;
+            let base = &glyph.components.vec.get(idx)?.base;
+            // Rects are cached in component order, so this is the n-th one with our base name.
+            let nth = glyph.components.vec[..idx].iter().filter(|c| &c.base == base).count();
+            rects
+                .iter()
+                .filter(|r| &r.name == base)
+                .nth(nth)
+                .map(|r| SkRect::new(r.minx, r.miny, r.maxx, r.maxy))
+        })
+    }
+
+    /// Returns the topmost component whose bounds contain `position`.
+    pub fn component_at(&self, position: (f32, f32)) -> Option<usize> {
+        let len = self.with_glyph(|g| g.components.vec.len());
+        (0..len)
+            .rev()
+            .find(|idx| self.component_rect(*idx).map_or(false, |r| r.contains(SkPoint::from(position))))
+    }
+
+    pub fn component_matrix(&self, idx: usize) -> Affine {
+        self.with_glyph(|glyph| {
+            let c = &glyph.components.vec[idx];
+            Affine::new(
+                [c.xScale, c.xyScale, c.yxScale, c.yScale, c.xOffset, c.yOffset]
+                    .map(|n| f32::from(n) as f64),
+            )
+        })
+    }
+
+    /// This function must be called between begin_modification and end_modification.
+    pub fn set_component_matrix_no_history(&mut self, idx: usize, affine: Affine) {
+        self.with_glyph_mut(|glyph| set_matrix(&mut glyph.components.vec[idx], affine));
+    }
+
+    pub fn set_component_matrix(&mut self, idx: usize, affine: Affine) {
+        self.begin_modification("Transformed component.", true);
+        self.set_component_matrix_no_history(idx, affine);
+        self.end_modification();
+    }
+
+    /// Adds a component referring to the glyph `name`. Returns false if there's no such glyph.
+    pub fn add_component(&mut self, name: &str) -> bool {
+        let filename = match self.glyph_filename(name) {
+            Some(f) => f,
+            None => {
+                log::error!("Can't add component: no glyph named {} beside this one", name);
+                return false;
+            }
+        };
+        if self.with_glyph(|g| g.name == name) {
+            log::error!("A glyph can't be a component of itself");
+            return false;
+        }
+
+        self.begin_modification("Added component.", false);
+        let idx = self.with_glyph_mut(|glyph| {
+            let mut component = GlifComponent::new();
+            component.base = name.to_string();
+            component.filename = Some(filename.clone());
+            set_matrix(&mut component, Affine::IDENTITY);
+            glyph.components.vec.push(component);
+            glyph.components.vec.len() - 1
+        });
+        self.end_modification();
+        self.component_idx = Some(idx);
+        true
+    }
+
+    pub fn delete_component(&mut self, idx: usize) {
+        self.begin_modification("Deleted component.", false);
+        self.with_glyph_mut(|glyph| {
+            glyph.components.vec.remove(idx);
+        });
+        self.end_modification();
+        self.component_idx = None;
+    }
+
+    // The base glyph's outline, with its own components flattened, in this glyph's coordinates.
+    fn component_outline(&self, idx: usize) -> Result<Vec<Vec<Point<MFEKPointData>>>, String> {
+        let base = self.with_glyph(|g| g.components.vec[idx].base.clone());
+        let filename = self
+            .glyph_filename(&base)
+            .ok_or_else(|| format!("no glyph named {} beside this one", base))?;
+        let glif: Glif<MFEKPointData> = glifparser::read_from_filename(&filename)
+            .map_err(|e| format!("failed to read {:?}: {:?}", filename, e))?;
+        let glif = glif
+            .flattened(&mut None)
+            .map_err(|e| format!("failed to flatten {}: {:?}", base, e))?;
+
+        let affine = self.component_matrix(idx);
+        let mut outline = glif.outline.unwrap_or_default();
+        for contour in outline.iter_mut() {
+            for point in contour.iter_mut() {
+                transform_point(point, affine);
+            }
+        }
+        Ok(outline)
+    }
+
+    /// Replaces the components at `indices` with their contours, in the active layer.
+    pub fn decompose_components(&mut self, indices: &[usize]) {
+        let mut outlines = vec![];
+        for idx in indices {
+            match self.component_outline(*idx) {
+                Ok(outline) => outlines.push(outline),
+                Err(e) => {
+                    log::error!("Can't decompose component: {}", e);
+                    return;
+                }
+            }
+        }
+        // A mirroring matrix reverses the direction of every contour.
+        let mirrored: Vec<bool> = indices
+            .iter()
+            .map(|idx| self.component_matrix(*idx).determinant() < 0.)
+            .collect();
+
+        self.begin_modification("Decomposed components.", false);
+        let layer = self.get_active_layer_mut();
+        for (outline, mirrored) in outlines.iter().zip(mirrored) {
+            for contour in outline {
+                let mut contour: glifparser::glif::MFEKContour<MFEKPointData> = contour.into();
+                if mirrored {
+                    contour.reverse_points();
+                }
+                layer.outline.push(contour);
+            }
+        }
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        self.with_glyph_mut(|glyph| {
+            for idx in indices.iter().rev() {
+                glyph.components.vec.remove(*idx);
+            }
+        });
+        self.end_modification();
+        self.component_idx = None;
+    }
+
+    pub fn decompose_all_components(&mut self) {
+        let len = self.with_glyph(|g| g.components.vec.len());
+        self.decompose_components(&(0..len).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,257 @@
+// Building composite glyphs from recipes such as `Aacute = A + acutecomb@top`. Each mark is placed
+// so that its `_top` anchor sits on the `top` anchor of the glyph built so far, which lets marks
+// stack: `A + acutecomb@top + dotaccentcomb@top` puts the dot on the acute's own `top` anchor.
+
+use glifparser::{Glif, GlifComponent, MFEKPointData};
+use kurbo::Affine;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use super::components::{glyph_filename_in, set_matrix};
+use super::Editor;
+use crate::user_interface::{InputPrompt, Interface};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recipe {
+    /// The glyph being built. Optional when building the glyph that's open in the editor.
+    pub name: Option<String>,
+    pub base: String,
+    /// Each mark, with the name of the anchor it attaches to.
+    pub marks: Vec<(String, String)>,
+}
+
+impl FromStr for Recipe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rhs) = match s.split_once('=') {
+            Some((name, _)) if name.trim().is_empty() => {
+                return Err(format!("missing glyph name before = in {:?}", s))
+            }
+            Some((name, rhs)) => (Some(name.trim().to_string()), rhs),
+            None => (None, s),
+        };
+
+        let mut parts = rhs.split('+').map(str::trim);
+        let base = match parts.next() {
+            Some(base) if !base.is_empty() && !base.contains('@') => base.to_string(),
+            _ => return Err(format!("missing base glyph in {:?}", s)),
+        };
+
+        let marks = parts
+            .map(|part| match part.split_once('@') {
+                Some((mark, anchor)) if !mark.trim().is_empty() && !anchor.trim().is_empty() => {
+                    Ok((mark.trim().to_string(), anchor.trim().to_string()))
+                }
+                _ => Err(format!("expected mark@anchor, got {:?}", part)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Recipe { name, base, marks })
+    }
+}
+
+pub fn read_glyph(dir: &Path, name: &str) -> Result<Glif<MFEKPointData>, String> {
+    let filename =
+        glyph_filename_in(dir, name).ok_or_else(|| format!("no glyph named {}", name))?;
+    glifparser::read_from_filename(&filename)
+        .map_err(|e| format!("failed to read {:?}: {:?}", filename, e))
+}
+
+fn anchors_of(glif: &Glif<MFEKPointData>) -> HashMap<String, (f32, f32)> {
+    glif.anchors
+        .iter()
+        .filter_map(|a| Some((a.class.clone()?, (a.x, a.y))))
+        .collect()
+}
+
+/// Places the components of `recipe`, reading its glyphs from the glyphs directory `dir`. Also
+/// returns the base glyph, whose advance width the composite takes.
+pub fn build_composite(
+    dir: &Path,
+    recipe: &Recipe,
+) -> Result<(Vec<GlifComponent>, Glif<MFEKPointData>), String> {
+    let base = read_glyph(dir, &recipe.base)?;
+    let mut anchors = anchors_of(&base);
+
+    let mut base_component = GlifComponent::new();
+    base_component.base = recipe.base.clone();
+    base_component.filename = glyph_filename_in(dir, &recipe.base);
+    set_matrix(&mut base_component, Affine::IDENTITY);
+    let mut components = vec![base_component];
+
+    for (mark_name, anchor) in &recipe.marks {
+        let mark = read_glyph(dir, mark_name)?;
+        let mark_anchors = anchors_of(&mark);
+
+        let to = anchors
+            .get(anchor)
+            .ok_or_else(|| format!("no anchor {} to attach {} to", anchor, mark_name))?;
+        let from = mark_anchors
+            .get(&format!("_{}", anchor))
+            .ok_or_else(|| format!("{} has no anchor _{}", mark_name, anchor))?;
+        let offset = (to.0 - from.0, to.1 - from.1);
+
+        let mut component = GlifComponent::new();
+        component.base = mark_name.clone();
+        component.filename = glyph_filename_in(dir, mark_name);
+        set_matrix(
+            &mut component,
+            Affine::translate((offset.0 as f64, offset.1 as f64)),
+        );
+        components.push(component);
+
+        // The mark's own anchors take over, so following marks stack on top of it.
+        for (name, (x, y)) in mark_anchors {
+            if !name.starts_with('_') {
+                anchors.insert(name, (x + offset.0, y + offset.1));
+            }
+        }
+    }
+
+    Ok((components, base))
+}
+
+// A file name for a new glyph, following the UFO 3 user name to file name convention.
+fn new_glyph_filename(name: &str, taken: &[String]) -> String {
+    const ILLEGAL: &str = "\"*+/:<>?[\\]|";
+    let mut stem = String::new();
+    for (idx, c) in name.chars().enumerate() {
+        if (idx == 0 && c == '.') || c.is_control() || ILLEGAL.contains(c) {
+            stem.push('_');
+        } else {
+            stem.push(c);
+            if c.is_uppercase() {
+                stem.push('_');
+            }
+        }
+    }
+
+    let is_taken = |f: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(f));
+    let mut filename = format!("{}.glif", stem);
+    let mut counter = 1;
+    while is_taken(&filename) {
+        filename = format!("{}{:015}.glif", stem, counter);
+        counter += 1;
+    }
+    filename
+}
+
+/// Builds or rebuilds the glyph `recipe` names in the glyphs directory `dir`, adding it to the
+/// directory's contents.plist if it's new.
+pub fn write_composite(dir: &Path, recipe: &Recipe) -> Result<(), String> {
+    let name = recipe
+        .name
+        .as_ref()
+        .ok_or_else(|| "recipe doesn't name the glyph to build".to_string())?;
+    let (components, base) = build_composite(dir, recipe)?;
+
+    let (filename, mut glif) = match glyph_filename_in(dir, name) {
+        Some(filename) => {
+            let glif = read_glyph(dir, name)?;
+            (filename, glif)
+        }
+        None => {
+            let contents_fn = dir.join("contents.plist");
+            let mut contents: BTreeMap<String, String> =
+                plist::from_file(&contents_fn).unwrap_or_default();
+            let taken: Vec<String> = contents.values().cloned().collect();
+            let filename = new_glyph_filename(name, &taken);
+            contents.insert(name.clone(), filename.clone());
+            plist::to_file_xml(&contents_fn, &contents)
+                .map_err(|e| format!("failed to write {:?}: {:?}", contents_fn, e))?;
+
+            let mut glif = Glif::new();
+            glif.name = name.clone();
+            (dir.join(filename), glif)
+        }
+    };
+
+    glif.components.vec = components;
+    glif.width = base.width;
+    glifparser::write_to_filename(&glif, &filename)
+        .map_err(|e| format!("failed to write {:?}: {:?}", filename, e))
+}
+
+/// Runs every recipe in `recipes`, one per line, against the glyphs directory `dir`. Blank lines
+/// and lines starting with # are skipped. Returns how many recipes failed.
+pub fn write_composites_from_file(dir: &Path, recipes: &Path) -> usize {
+    let text = match fs::read_to_string(recipes) {
+        Ok(text) => text,
+        Err(e) => {
+            log::error!("Failed to read recipe file {:?}: {:?}", recipes, e);
+            return 1;
+        }
+    };
+
+    let mut failures = 0;
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.parse::<Recipe>().and_then(|recipe| write_composite(dir, &recipe)) {
+            Ok(()) => log::info!("Built {}", line),
+            Err(e) => {
+                log::error!("{:?} line {}: {}", recipes, lineno + 1, e);
+                failures += 1;
+            }
+        }
+    }
+    failures
+}
+
+impl Editor {
+    /// Asks for a recipe and builds the current glyph from it.
+    pub fn build_composite_prompt(&self, i: &mut Interface) {
+        let name = self.with_glyph(|g| g.name.clone());
+        i.push_prompt(InputPrompt::Text {
+            label: "Recipe:".to_string(),
+            default: format!("{} = ", name),
+            func: Rc::new(|v, recipe| {
+                if let Err(e) = v.build_composite(&recipe) {
+                    log::error!("Failed to build composite: {}", e);
+                }
+            }),
+        });
+    }
+
+    /// Replaces the current glyph's components with those built from `recipe`.
+    pub fn build_composite(&mut self, recipe: &str) -> Result<(), String> {
+        let recipe: Recipe = recipe.parse()?;
+        let name = self.with_glyph(|g| g.name.clone());
+        if let Some(recipe_name) = &recipe.name {
+            if *recipe_name != name {
+                return Err(format!("recipe builds {}, but this is {}", recipe_name, name));
+            }
+        }
+        let dir = self
+            .glyphs_dir()
+            .ok_or_else(|| "this glyph wasn't loaded from a file".to_string())?;
+        let (components, base) = build_composite(&dir, &recipe)?;
+
+        self.begin_modification("Built composite.", false);
+        self.with_glyph_mut(|glyph| {
+            glyph.components.vec = components.clone();
+            glyph.width = base.width;
+        });
+        self.end_modification();
+        self.component_idx = None;
+        Ok(())
+    }
+}
+
+#[test]
+fn parse_recipe() {
+    let recipe: Recipe = "Aacute = A + acutecomb@top".parse().unwrap();
+    assert_eq!(recipe.name.as_deref(), Some("Aacute"));
+    assert_eq!(recipe.base, "A");
+    assert_eq!(recipe.marks, vec![("acutecomb".to_string(), "top".to_string())]);
+
+    assert!("A + acutecomb".parse::<Recipe>().is_err());
+    assert!(" = A".parse::<Recipe>().is_err());
+}
@@ -1,3 +1,148 @@
+// Structural edits on a single contour: changing its start point, opening and closing it, and
+// splitting it at a node. All of these keep the contour operation's per-point data in step with
+// the points, like the pen modes do with `operation_mut().insert_op`.
+
+use glifparser::glif::{
+    contour::MFEKContourCommon, contour_operations::ContourOperation, inner::MFEKContourInnerType,
+    point::MFEKPointCommon, MFEKContour,
+};
+use glifparser::{Handle, MFEKPointData, WhichHandle};
+
+use super::Editor;
+
+// End points closer than this are considered the same point when closing a contour.
+const JOIN_DISTANCE: f32 = 0.5;
+
+/// Returns a copy of the closed contour `contour` whose first point is `pi`.
+fn rotated(contour: &MFEKContour<MFEKPointData>, pi: usize) -> MFEKContour<MFEKPointData> {
+    let mut front = contour.sub(pi, contour.len());
+    let mut back = contour.sub(0, pi);
+    // Both halves come from the same contour, so .append can't fail on a type mismatch.
+    let _ = front.append(&mut back);
+    front.set_closed();
+    front
+}
+
 impl Editor {
-    
-}
\ No newline at end of file
+    /// Makes `pi` the first point of contour `ci`. On an open contour only the last point can
+    /// become the start, which reverses the contour.
+    pub fn set_start_point(&mut self, ci: usize, pi: usize) {
+        let contour = &self.get_active_layer_ref().outline[ci];
+        if pi == 0 {
+            return;
+        }
+        if contour.is_open() && pi != contour.len() - 1 {
+            log::warn!("Only an end point can become the start point of an open contour");
+            return;
+        }
+
+        self.begin_modification("Set start point.", false);
+        let layer = self.get_active_layer_mut();
+        if layer.outline[ci].is_open() {
+            layer.outline[ci].reverse_points();
+        } else {
+            layer.outline[ci] = rotated(&layer.outline[ci], pi);
+        }
+        self.selected.clear();
+        self.set_selected(ci, 0);
+        self.end_modification();
+    }
+
+    /// Closes an open contour. If its end points coincide they are joined into one point,
+    /// otherwise a closing segment is added.
+    pub fn close_contour(&mut self, ci: usize) {
+        let contour = &self.get_active_layer_ref().outline[ci];
+        if contour.is_closed() {
+            return;
+        }
+
+        let last = contour.len() - 1;
+        let (fx, fy) = contour.get_point(0).unwrap().get_position();
+        let (lx, ly) = contour.get_point(last).unwrap().get_position();
+        let join = last > 0 && (fx - lx).hypot(fy - ly) < JOIN_DISTANCE;
+
+        self.begin_modification("Close contour.", false);
+        let layer = self.get_active_layer_mut();
+        let contour = &mut layer.outline[ci];
+        if join {
+            let last_b = contour.get_point(last).unwrap().get_handle(WhichHandle::B);
+            contour.delete(last);
+            contour.operation_mut().remove_op(last);
+            if let Some(handle) = last_b {
+                contour.get_point_mut(0).unwrap().set_handle(WhichHandle::B, handle);
+            }
+        }
+        contour.set_closed();
+
+        self.selected.clear();
+        if let Some(pi) = self.point_idx {
+            if join && pi == last {
+                self.point_idx = Some(0);
+            }
+        }
+        self.end_modification();
+    }
+
+    /// Opens a closed contour at `pi`, removing the segment that ends there. `pi` becomes the
+    /// start point.
+    pub fn open_contour_at(&mut self, ci: usize, pi: usize) {
+        if self.get_active_layer_ref().outline[ci].is_open() {
+            return;
+        }
+
+        self.begin_modification("Open contour.", false);
+        let layer = self.get_active_layer_mut();
+        let mut contour = rotated(&layer.outline[ci], pi);
+        contour.set_open();
+        if let Some(point) = contour.get_point_mut(0) {
+            point.colocate_handle(WhichHandle::B);
+        }
+        let last = contour.len() - 1;
+        if let Some(point) = contour.get_point_mut(last) {
+            point.colocate_handle(WhichHandle::A);
+        }
+        layer.outline[ci] = contour;
+
+        self.selected.clear();
+        self.set_selected(ci, 0);
+        self.end_modification();
+    }
+
+    /// Splits a closed contour at node `pi` into an open one which starts and ends there, without
+    /// changing its shape.
+    pub fn split_contour_at(&mut self, ci: usize, pi: usize) {
+        let contour = &self.get_active_layer_ref().outline[ci];
+        if contour.is_open() {
+            return;
+        }
+        if contour.get_type() == MFEKContourInnerType::Hyper {
+            // Hyperbeziers are solved globally, so a duplicated node gives no exact split.
+            log::warn!("Splitting hyperbezier contours isn't supported");
+            return;
+        }
+
+        self.begin_modification("Split contour.", false);
+        let layer = self.get_active_layer_mut();
+        let mut contour = rotated(&layer.outline[ci], pi);
+        let len = contour.len();
+
+        // The closing segment now ends in a copy of the first point.
+        if let Some(points) = contour.cubic_mut() {
+            let mut end = points[0].clone();
+            end.a = Handle::Colocated;
+            points[0].b = Handle::Colocated;
+            points.push(end);
+        } else if let Some(points) = contour.quad_mut() {
+            let mut end = points[0].clone();
+            end.a = Handle::Colocated;
+            points.push(end);
+        }
+        contour.operation_mut().insert_op(len);
+        contour.set_open();
+        layer.outline[ci] = contour;
+
+        self.selected.clear();
+        self.set_selected(ci, 0);
+        self.end_modification();
+    }
+}
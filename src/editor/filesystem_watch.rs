@@ -27,6 +27,11 @@ impl Editor {
                             },
                         );
                         log::info!("Reloaded UFO-sourced metadata, fontinfo.plist changed");
+                    } else if p.file_name() == Some(oss("kerning.plist"))
+                        || p.file_name() == Some(oss("groups.plist"))
+                    {
+                        i.text_preview.invalidate_kerning();
+                        log::info!("Reloaded kerning, {:?} changed", p.file_name().unwrap());
                     } else if p.extension() == Some(oss("glif"))
                         || p.extension() == Some(oss("glifjson"))
                     {
@@ -49,6 +54,9 @@ impl Editor {
                                 log::debug!("Got filesystem event from our own recent write");
                             }
                         } else {
+                            self.invalidate_ufo_index();
+                            i.text_preview.invalidate();
+                            i.background.invalidate();
                             let ufo_or_dir = if p.ufo().is_some() {
                                 "UFO"
                             } else {
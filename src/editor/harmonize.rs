@@ -0,0 +1,118 @@
+use glifparser::{
+    glif::{contour::MFEKContourCommon, point::MFEKPointCommon},
+    Handle, MFEKPointData, Point,
+};
+use MFEKmath::{vec2, Vector};
+
+use super::Editor;
+
+fn handle_or_point(point: &Point<MFEKPointData>, handle: Handle) -> Vector {
+    match handle {
+        Handle::At(x, y) => vec2![x, y],
+        Handle::Colocated => vec2![point.x, point.y],
+    }
+}
+
+/// Moves `point` to where it has to sit on the line between its own handles for the curvature of
+/// the incoming and outgoing segments to be equal (G2). The handles themselves are left alone, so
+/// they stay collinear with the new position by construction.
+pub fn harmonize_point(
+    prev: &Point<MFEKPointData>,
+    point: &Point<MFEKPointData>,
+    next: &Point<MFEKPointData>,
+) -> Option<Point<MFEKPointData>> {
+    let (c2, c3) = match (point.b, point.a) {
+        (Handle::At(bx, by), Handle::At(ax, ay)) => (vec2![bx, by], vec2![ax, ay]),
+        _ => return None,
+    };
+    let c1 = handle_or_point(prev, prev.a);
+    let c4 = handle_or_point(next, next.b);
+
+    let tangent = c3 - c2;
+    let length = tangent.distance(vec2![0., 0.]);
+    if length < f64::EPSILON {
+        return None;
+    }
+
+    // Curvature at the end of a cubic is 2/3 · h / d², where h is the distance of the outer
+    // handle from the tangent line and d the length of the inner handle. Setting both sides
+    // equal gives d_in / d_out = √(h_in / h_out).
+    let h_in = (tangent.cross(c1 - c2) / length).abs().sqrt();
+    let h_out = (tangent.cross(c4 - c2) / length).abs().sqrt();
+    if h_in + h_out < f64::EPSILON {
+        return None;
+    }
+
+    let t = h_in / (h_in + h_out);
+    let new = c2 + tangent * t;
+    let mut harmonized = point.clone();
+    harmonized.set_position_no_handles(new.x as f32, new.y as f32);
+    Some(harmonized)
+}
+
+impl Editor {
+    /// Harmonizes every selected smooth point of a cubic contour.
+    pub fn harmonize_selection(&mut self) {
+        let mut selected = self.selected.clone();
+        if let Some(sp) = self.selected_point() {
+            selected.insert(sp);
+        }
+
+        let layer = self.get_active_layer_ref();
+        let mut moves = vec![];
+        for (ci, pi) in selected {
+            let mfek_contour = &layer.outline[ci];
+            let contour = match mfek_contour.cubic() {
+                Some(c) => c,
+                None => continue,
+            };
+            let len = contour.len();
+            if len < 2 || contour[pi].get_smooth() != Some(true) {
+                continue;
+            }
+            if mfek_contour.is_open() && (pi == 0 || pi == len - 1) {
+                continue;
+            }
+
+            let prev = &contour[(pi + len - 1) % len];
+            let next = &contour[(pi + 1) % len];
+            if let Some(point) = harmonize_point(prev, &contour[pi], next) {
+                moves.push((ci, pi, point));
+            }
+        }
+
+        if moves.is_empty() {
+            log::debug!("No smooth cubic points in selection to harmonize");
+            return;
+        }
+
+        self.begin_modification("Harmonize selection.", false);
+        for (ci, pi, point) in moves {
+            self.get_active_layer_mut().outline[ci].cubic_mut().unwrap()[pi] = point;
+        }
+        self.end_modification();
+    }
+}
+
+#[test]
+fn harmonize_moves_only_the_point() {
+    use glifparser::PointType;
+
+    let point_with = |(x, y), a: Handle, b: Handle| {
+        let mut point: Point<MFEKPointData> = Point::from_x_y_type((x, y), PointType::Curve);
+        point.a = a;
+        point.b = b;
+        point
+    };
+    // The incoming outer handle is 40 units off the tangent and the outgoing one 10 units.
+    let prev = point_with((0., 0.), Handle::At(50., 40.), Handle::Colocated);
+    let point = point_with((200., 0.), Handle::At(300., 0.), Handle::At(100., 0.));
+    let next = point_with((400., 0.), Handle::Colocated, Handle::At(350., -10.));
+
+    let harmonized = harmonize_point(&prev, &point, &next).unwrap();
+    let t = 40f32.sqrt() / (40f32.sqrt() + 10f32.sqrt());
+    assert!((harmonized.x - (100. + 200. * t)).abs() < 1e-3);
+    assert!(harmonized.y.abs() < 1e-3);
+    assert_eq!(harmonized.a, Handle::At(300., 0.));
+    assert_eq!(harmonized.b, Handle::At(100., 0.));
+}
@@ -1,7 +1,8 @@
-use super::Editor;
+use super::{composite, Editor};
 use crate::args::HeadlessMode;
 
 use std::cell::RefCell;
+use std::path::Path;
 use std::process;
 
 thread_local!(pub static IS_HEADLESS: RefCell<bool> = RefCell::new(false));
@@ -18,6 +19,15 @@ impl Editor {
             Some(filename) => filename.clone(),
             None => panic!("Cannot go headless without a .glif file to work on"),
         };
+
+        if self.args.headless_mode == HeadlessMode::BuildComposites {
+            let path = Path::new(&filename);
+            let dir = if path.is_dir() { path } else { path.parent().unwrap() };
+            let recipes = self.args.recipes.as_ref().unwrap();
+            let failures = composite::write_composites_from_file(dir, Path::new(recipes));
+            process::exit(if failures == 0 { 0 } else { 1 })
+        }
+
         self.load_glif_impl(filename);
 
         if self.args.no_contour_ops {
@@ -40,6 +50,7 @@ impl Editor {
             HeadlessMode::Flatten => {
                 self.flatten_glif(None, false).unwrap();
             }
+            HeadlessMode::BuildComposites => unreachable!(),
             HeadlessMode::RunScript => unimplemented!(),
         }
         process::exit(0)
@@ -10,11 +10,21 @@ use skia_safe::{Data as SkData, Image as SkImage, Matrix};
 
 use glifparser::matrix::ToSkiaMatrix;
 
+use glifparser::glif::Lib;
+use glifparser::{MFEKGlif, MFEKPointData};
+use image::{codecs::png::PngEncoder, ColorType};
+use kurbo::Affine;
+use plist::{Dictionary, Value};
+
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
+use super::util::glyph_lib_mut;
 use super::Editor;
+use crate::user_interface::gui;
 
 #[derive(Clone)]
 pub struct EditorImage {
@@ -95,22 +105,344 @@ impl Editor {
         });
     }
 
-    /// This function must be called between begin_layer_modification and end_layer_modification
-    pub fn add_image_to_active_layer(&mut self, path: PathBuf) {
-        // okay so first we've got to take our pathbuf and we've got to convert it into a glyph image
-        let image_result = GlifImage::from_filename(path);
+    /// Where images added to this glyph are kept: its UFO's images directory, if it has a UFO.
+    pub fn images_dir(&self) -> Option<PathBuf> {
+        self.font_dir().map(|ufo| ufo.join(IMAGES_DIR))
+    }
 
-        let image = match image_result {
-            Ok(image) => image,
-            Err(_) => todo!(), // need to add some way to tell the user we failed to load the image
-                               // a pop up box or something should do but I'm not super concerned about this at the moment
+    /// Adds the PNG at `path` to the active layer, centered on `position` if given. If the glyph is
+    /// in a UFO the image is copied into its images directory first, as UFO images can't live
+    /// elsewhere.
+    pub fn add_image_to_active_layer(
+        &mut self,
+        path: PathBuf,
+        position: Option<(f32, f32)>,
+    ) -> Result<(), ()> {
+        let path = match self.images_dir() {
+            Some(dir) => copy_into_images_dir(&path, &dir).map_err(|e| {
+                gui::error!("Failed to copy image {:?} into the UFO: {}", path, e);
+            })?,
+            None => path,
         };
 
+        let mut image = GlifImage::from_filename(path.clone()).map_err(|e| {
+            gui::error!("Failed to load image {:?}: {:?}", path, e);
+        })?;
+        if self.images_dir().is_some() {
+            // Images in a UFO are referred to by file name alone.
+            image.filename = PathBuf::from(path.file_name().unwrap());
+        }
+
+        self.begin_modification("Add image to layer.", false);
         let matrix = image.matrix();
+        let filename = image.filename.clone();
         self.get_active_layer_mut()
             .images
             .push((image, matrix.into()));
+        self.recache_images();
 
+        if let (Some((x, y)), Some(loaded)) = (position, self.images.get(&filename)) {
+            let (w, h) = (loaded.img.width() as f64, loaded.img.height() as f64);
+            let layer = self.get_active_layer_mut();
+            let matrix = &mut layer.images.last_mut().unwrap().1;
+            let center = *matrix * kurbo::Point::new(w / 2., h / 2.);
+            *matrix = Affine::translate((x as f64 - center.x, y as f64 - center.y)) * *matrix;
+        }
+        self.end_modification();
+        Ok(())
+    }
+
+    /// Adds image data from the clipboard to the active layer as a new UFO image. Returns false if
+    /// there's no image on the clipboard, or the glyph isn't in a UFO to keep one in.
+    pub fn paste_image(&mut self, position: Option<(f32, f32)>) -> bool {
+        let dir = match self.images_dir() {
+            Some(dir) => dir,
+            None => return false,
+        };
+        let image = self
+            .clipboard
+            .with(|c| {
+                c.get_image()
+                    .map(|i| (i.width as u32, i.height as u32, i.bytes.into_owned()))
+                    .ok()
+            })
+            .flatten();
+        let (width, height, rgba) = match image {
+            Some(image) => image,
+            None => return false,
+        };
+
+        let mut png = vec![];
+        if let Err(e) = PngEncoder::new(&mut png).encode(&rgba, width, height, ColorType::Rgba8) {
+            gui::error!("Failed to encode pasted image! {:?}", e);
+            return true;
+        }
+        match store_in_images_dir(&png, "pasted", &dir) {
+            Ok(path) => drop(self.add_image_to_active_layer(path, position)),
+            Err(e) => gui::error!("Failed to store pasted image in the UFO: {}", e),
+        }
+        true
+    }
+
+    /// The settings of image `idx` of the active layer.
+    pub fn image_settings(&self, idx: usize) -> ImageSettings {
+        let layer_idx = self.layer_idx.unwrap();
+        self.with_glyph(|glyph| {
+            image_settings_in(&glyph.lib)
+                .remove(&(layer_idx, idx))
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn set_image_settings(&mut self, idx: usize, settings: ImageSettings) {
+        if self.image_settings(idx) == settings {
+            return;
+        }
+        let layer_idx = self.layer_idx.unwrap();
+        self.begin_modification("Changed image settings.", true);
+        self.with_glyph_mut(|glyph| {
+            let mut all = image_settings_in(&glyph.lib);
+            if settings == ImageSettings::default() {
+                all.remove(&(layer_idx, idx));
+            } else {
+                all.insert((layer_idx, idx), settings);
+            }
+            write_image_settings(glyph, &all);
+        });
+        self.end_modification();
+    }
+
+    /// Removes image `idx` from the active layer. Must be called during a modification.
+    pub fn remove_image(&mut self, idx: usize) {
+        let layer_idx = self.layer_idx.unwrap();
+        self.with_glyph_mut(|glyph| remove_image(glyph, layer_idx, idx));
         self.recache_images();
     }
 }
+
+pub const IMAGES_DIR: &str = "images";
+/// Glyph lib key of a list of `ImageSettings`, each naming the layer and the index in its images
+/// of the placement it's for, so two placements of one file can differ.
+pub const IMAGES_LIB_KEY: &str = "org.MFEK.images";
+
+/// How an image is shown and whether it can be changed, kept in the glyph lib.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageSettings {
+    pub opacity: f32,
+    pub locked: bool,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            opacity: 1.,
+            locked: false,
+        }
+    }
+}
+
+impl ImageSettings {
+    fn from_dict(dict: &Dictionary) -> Self {
+        let opacity = dict.get("opacity").and_then(|value| {
+            value
+                .as_real()
+                .or_else(|| value.as_signed_integer().map(|i| i as f64))
+        });
+        Self {
+            opacity: opacity.map_or(1., |o| (o as f32).clamp(0., 1.)),
+            locked: dict.get("locked").and_then(Value::as_boolean).unwrap_or(false),
+        }
+    }
+
+    fn to_dict(&self) -> Dictionary {
+        let mut dict = Dictionary::new();
+        dict.insert("opacity".to_string(), Value::Real(self.opacity as f64));
+        dict.insert("locked".to_string(), Value::Boolean(self.locked));
+        dict
+    }
+}
+
+/// The settings of image placements in `lib`, by layer and image index.
+pub fn image_settings_in(lib: &Lib) -> HashMap<(usize, usize), ImageSettings> {
+    let entries = match lib {
+        Lib::Plist(lib) => lib.get(IMAGES_LIB_KEY).and_then(Value::as_array),
+        _ => None,
+    };
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_dictionary)
+        .filter_map(|dict| {
+            let layer = dict.get("layer")?.as_signed_integer()? as usize;
+            let image = dict.get("image")?.as_signed_integer()? as usize;
+            Some(((layer, image), ImageSettings::from_dict(dict)))
+        })
+        .collect()
+}
+
+fn write_image_settings(
+    glyph: &mut MFEKGlif<MFEKPointData>,
+    settings: &HashMap<(usize, usize), ImageSettings>,
+) {
+    let lib = match glyph_lib_mut(glyph) {
+        Some(lib) => lib,
+        None => return,
+    };
+    if settings.is_empty() {
+        lib.remove(IMAGES_LIB_KEY);
+        return;
+    }
+    let mut keys: Vec<_> = settings.keys().copied().collect();
+    keys.sort();
+    let entries = keys
+        .into_iter()
+        .map(|(layer, image)| {
+            let mut dict = settings[&(layer, image)].to_dict();
+            dict.insert("layer".to_string(), Value::Integer((layer as i64).into()));
+            dict.insert("image".to_string(), Value::Integer((image as i64).into()));
+            Value::Dictionary(dict)
+        })
+        .collect();
+    lib.insert(IMAGES_LIB_KEY.to_string(), Value::Array(entries));
+}
+
+/// Keeps image settings with their images when layers or images move. `map` takes the layer and
+/// image index of a placement to where it is now, or to `None` if it's gone.
+pub fn renumber_image_settings(
+    glyph: &mut MFEKGlif<MFEKPointData>,
+    map: impl Fn((usize, usize)) -> Option<(usize, usize)>,
+) {
+    let settings = image_settings_in(&glyph.lib);
+    if settings.is_empty() {
+        return;
+    }
+    let renumbered = settings
+        .into_iter()
+        .filter_map(|(key, settings)| Some((map(key)?, settings)))
+        .collect();
+    write_image_settings(glyph, &renumbered);
+}
+
+/// Removes image `idx` of layer `layer_idx`, along with its settings.
+pub fn remove_image(glyph: &mut MFEKGlif<MFEKPointData>, layer_idx: usize, idx: usize) {
+    glyph.layers[layer_idx].images.remove(idx);
+    renumber_image_settings(glyph, |(layer, image)| {
+        match (layer == layer_idx, image.cmp(&idx)) {
+            (false, _) | (true, Ordering::Less) => Some((layer, image)),
+            (true, Ordering::Equal) => None,
+            (true, Ordering::Greater) => Some((layer, image - 1)),
+        }
+    });
+}
+
+// File names are limited to 255 bytes; leave room for ".png" and the counter that tells apart images
+// whose names collide.
+const MAX_IMAGE_STEM_LEN: usize = 255 - ".png".len() - 2;
+
+/// A UFO-legal file name for an image called `stem`: illegal characters become `_`, as in the UFO
+/// user name to file name convention, and it's always a PNG.
+pub fn legal_image_filename(stem: &str) -> String {
+    const ILLEGAL: &str = "\"*+/:<>?[\\]|";
+    let mut name: String = stem
+        .chars()
+        .map(|c| if c.is_control() || ILLEGAL.contains(c) { '_' } else { c })
+        .collect();
+    if name.starts_with('.') {
+        name.replace_range(..1, "_");
+    }
+    if name.is_empty() {
+        name.push('_');
+    }
+    if let Some((end, _)) = name
+        .char_indices()
+        .find(|(i, c)| i + c.len_utf8() > MAX_IMAGE_STEM_LEN)
+    {
+        name.truncate(end);
+    }
+    format!("{}.png", name)
+}
+
+/// Copies the PNG at `path` into the UFO images directory `dir`, returning where it is now. Reuses
+/// an identical image already there, and otherwise picks a name not in use, ignoring case.
+fn copy_into_images_dir(path: &Path, dir: &Path) -> Result<PathBuf, String> {
+    if path.parent() == Some(dir) {
+        return Ok(path.to_path_buf());
+    }
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    store_in_images_dir(&data, &stem, dir)
+}
+
+/// Writes the PNG `data` into the UFO images directory `dir` under a name based on `stem`.
+fn store_in_images_dir(data: &[u8], stem: &str, dir: &Path) -> Result<PathBuf, String> {
+    if !data.starts_with(b"\x89PNG") {
+        return Err("UFO images must be PNGs".to_string());
+    }
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let existing: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+        .collect();
+    let base = legal_image_filename(stem);
+    for n in 0.. {
+        let name = match n {
+            0 => base.clone(),
+            n => format!("{}{:02}.png", base.trim_end_matches(".png"), n),
+        };
+        let target = dir.join(&name);
+        if existing.contains(&name.to_lowercase()) {
+            if fs::read(&target).map_or(false, |existing| existing == data) {
+                return Ok(target);
+            }
+            continue;
+        }
+        fs::write(&target, data).map_err(|e| e.to_string())?;
+        return Ok(target);
+    }
+    unreachable!()
+}
+
+#[test]
+fn legal_image_filenames() {
+    assert_eq!(legal_image_filename("scan"), "scan.png");
+    assert_eq!(legal_image_filename("a/b:c*d"), "a_b_c_d.png");
+    assert_eq!(legal_image_filename("tab\there"), "tab_here.png");
+    assert_eq!(legal_image_filename(".hidden"), "_hidden.png");
+    assert_eq!(legal_image_filename(""), "_.png");
+
+    // Long names are cut at a character boundary, leaving room for a counter.
+    let long = legal_image_filename(&"é".repeat(200));
+    assert_eq!(long, format!("{}.png", "é".repeat(MAX_IMAGE_STEM_LEN / 2)));
+    assert!(format!("{}99.png", long.trim_end_matches(".png")).len() <= 255);
+}
+
+#[test]
+fn copy_images_into_ufo() {
+    let root = std::env::temp_dir().join(format!("MFEKglif-images-{}", std::process::id()));
+    let (sources, dir) = (root.join("sources"), root.join("font.ufo").join(IMAGES_DIR));
+    fs::create_dir_all(sources.join("other")).unwrap();
+    let scan = sources.join("Scan.png");
+    fs::write(&scan, b"\x89PNG one").unwrap();
+    let other_scan = sources.join("other").join("scan.png");
+    fs::write(&other_scan, b"\x89PNG two").unwrap();
+    let not_png = sources.join("scan.jpg");
+    fs::write(&not_png, b"\xff\xd8\xff").unwrap();
+
+    let copied = copy_into_images_dir(&scan, &dir).unwrap();
+    assert_eq!(copied, dir.join("Scan.png"));
+    // An identical image is reused rather than copied again.
+    assert_eq!(copy_into_images_dir(&scan, &dir).unwrap(), copied);
+    // Names differing only in case collide, as on case-insensitive file systems.
+    let renamed = copy_into_images_dir(&other_scan, &dir).unwrap();
+    assert_eq!(renamed, dir.join("scan01.png"));
+    assert_eq!(fs::read(&renamed).unwrap(), b"\x89PNG two");
+    assert!(copy_into_images_dir(&not_png, &dir).is_err());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -1,4 +1,4 @@
-use super::{events::*, Editor};
+use super::{events::*, quadratic, Editor};
 
 use glifparser::glif::contour::MFEKContourCommon;
 use MFEKmath::mfek::ResolveCubic;
@@ -143,7 +143,10 @@ impl Editor {
         }
         let layer = &mut export.layers[0];
 
-        let glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+        let mut glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+        if let Some(tolerance) = self.args.quadratic {
+            glif_struct.outline = Some(quadratic::to_glif_outline(&layer.outline, tolerance));
+        }
 
         let filename = self.with_glyph(|glyph| {
             let mut filename: PathBuf = if rename {
@@ -239,7 +242,10 @@ impl Editor {
             }
             log::info!("Targeting {:?} to write {}", &target, &layer.name);
 
-            let glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+            let mut glif_struct = self.glyph.as_ref().unwrap().to_exported(layer);
+            if let Some(tolerance) = self.args.quadratic {
+                glif_struct.outline = Some(quadratic::to_glif_outline(&layer.outline, tolerance));
+            }
             glifparser::write_to_filename(&glif_struct, &target)
                 .unwrap_or_else(|e| panic!("Failed to write glif: {:?}", e));
 
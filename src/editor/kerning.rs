@@ -0,0 +1,149 @@
+// Pair kerning from the parent UFO's kerning.plist and groups.plist, following the UFO 3 rules:
+// either side of a pair may be a glyph or a kerning group (`public.kern1.*` for the first glyph,
+// `public.kern2.*` for the second), and glyph pairs take precedence over group pairs.
+
+use plist::Value;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::Editor;
+
+const KERNING: &str = "kerning.plist";
+const GROUPS: &str = "groups.plist";
+const KERN1: &str = "public.kern1.";
+const KERN2: &str = "public.kern2.";
+
+/// Values are kept as they are in kerning.plist, so that writing it back doesn't turn every
+/// `<integer>` in it into a `<real>`.
+#[derive(Clone, Debug, Default)]
+pub struct Kerning {
+    pub pairs: BTreeMap<String, BTreeMap<String, Value>>,
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+impl Kerning {
+    /// Reads the kerning of the UFO at `ufo`. A missing plist is the same as an empty one.
+    pub fn read(ufo: &Path) -> Result<Self, String> {
+        fn read<T: serde::de::DeserializeOwned + Default>(path: PathBuf) -> Result<T, String> {
+            if !path.exists() {
+                return Ok(T::default());
+            }
+            plist::from_file(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))
+        }
+        let pairs = read(ufo.join(KERNING))?;
+        let groups = read(ufo.join(GROUPS))?;
+        Ok(Kerning { pairs, groups })
+    }
+
+    pub fn write(&self, ufo: &Path) -> Result<(), String> {
+        let path = ufo.join(KERNING);
+        plist::to_file_xml(&path, &self.pairs)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    fn group_of(&self, prefix: &str, glyph: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(name, members)| name.starts_with(prefix) && members.iter().any(|m| m == glyph))
+            .map(|(name, _)| name.as_str())
+    }
+
+    // The keys a pair can be kerned by, most specific first.
+    fn candidates(&self, left: &str, right: &str) -> Vec<(String, String)> {
+        let lefts = [Some(left), self.group_of(KERN1, left)];
+        let rights = [Some(right), self.group_of(KERN2, right)];
+        let mut candidates = vec![];
+        for l in lefts.iter().flatten() {
+            for r in rights.iter().flatten() {
+                candidates.push((l.to_string(), r.to_string()));
+            }
+        }
+        candidates
+    }
+
+    /// The kerning of `left` followed by `right`, and the pair in kerning.plist it comes from.
+    pub fn lookup(&self, left: &str, right: &str) -> Option<((String, String), f64)> {
+        self.candidates(left, right).into_iter().find_map(|(l, r)| {
+            let value = self.pairs.get(&l)?.get(&r)?;
+            let value = value
+                .as_real()
+                .or_else(|| value.as_signed_integer().map(|i| i as f64))?;
+            Some(((l, r), value))
+        })
+    }
+
+    pub fn value(&self, left: &str, right: &str) -> f64 {
+        self.lookup(left, right).map(|(_, v)| v).unwrap_or(0.)
+    }
+
+    /// Kerns `left` followed by `right` by `value`. An existing pair is changed where it is; a new
+    /// one is kerned by group where the glyphs have one, as is usual. Zero removes the pair.
+    pub fn set(&mut self, left: &str, right: &str, value: f64) {
+        let (l, r) = match self.lookup(left, right) {
+            Some((key, _)) => key,
+            None => (
+                self.group_of(KERN1, left).unwrap_or(left).to_string(),
+                self.group_of(KERN2, right).unwrap_or(right).to_string(),
+            ),
+        };
+        if value == 0. {
+            if let Some(seconds) = self.pairs.get_mut(&l) {
+                seconds.remove(&r);
+                if seconds.is_empty() {
+                    self.pairs.remove(&l);
+                }
+            }
+        } else {
+            let value = if value.fract() == 0. && value.abs() < i64::MAX as f64 {
+                Value::Integer((value as i64).into())
+            } else {
+                Value::Real(value)
+            };
+            self.pairs.entry(l).or_default().insert(r, value);
+        }
+    }
+}
+
+impl Editor {
+    /// The UFO this glyph belongs to, if it belongs to one.
+    pub fn font_dir(&self) -> Option<PathBuf> {
+        self.ipc_info.as_ref().and_then(|ipc| ipc.font.clone())
+    }
+
+    /// Sets the kerning of a pair in the parent UFO's kerning.plist.
+    pub fn set_kern(&self, left: &str, right: &str, value: f64) -> Result<Kerning, String> {
+        let ufo = self
+            .font_dir()
+            .ok_or_else(|| "this glyph isn't in a UFO".to_string())?;
+        let mut kerning = Kerning::read(&ufo)?;
+        kerning.set(left, right, value);
+        kerning.write(&ufo)?;
+        Ok(kerning)
+    }
+}
+
+#[test]
+fn kerning_precedence() {
+    let mut kerning = Kerning::default();
+    kerning
+        .groups
+        .insert("public.kern1.o".to_string(), vec!["o".to_string(), "oslash".to_string()]);
+    kerning.set("o", "v", -20.);
+    assert_eq!(kerning.value("oslash", "v"), -20.);
+    // Whole numbers are written as integers.
+    assert_eq!(kerning.pairs["public.kern1.o"]["v"], Value::Integer((-20).into()));
+
+    // An exception for one glyph of the group
+    kerning
+        .pairs
+        .entry("oslash".to_string())
+        .or_default()
+        .insert("v".to_string(), Value::Real(-5.5));
+    assert_eq!(kerning.value("oslash", "v"), -5.5);
+    assert_eq!(kerning.value("o", "v"), -20.);
+
+    kerning.set("o", "v", 0.);
+    assert_eq!(kerning.value("o", "v"), 0.);
+    assert!(!kerning.pairs.contains_key("public.kern1.o"));
+}
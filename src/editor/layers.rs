@@ -1,6 +1,9 @@
+use super::images::renumber_image_settings;
 use super::Editor;
 use glifparser::glif::{HistoryEntry, Layer, MFEKOutline};
 
+use std::cmp::Ordering;
+
 impl Editor {
     /// Adds a new layer. This generates a HistoryEntry and sets the selection to point to the newly created
     /// layer.
@@ -60,7 +63,14 @@ impl Editor {
         });
 
         let lidx = self.layer_idx.unwrap();
-        self.with_glyph_mut_no_history(|glyph| glyph.layers.remove(lidx));
+        self.with_glyph_mut_no_history(|glyph| {
+            glyph.layers.remove(lidx);
+            renumber_image_settings(glyph, |(layer, image)| match layer.cmp(&lidx) {
+                Ordering::Less => Some((layer, image)),
+                Ordering::Equal => None,
+                Ordering::Greater => Some((layer - 1, image)),
+            });
+        });
 
         if self.layer_idx != Some(0) {
             self.layer_idx = Some(self.layer_idx.unwrap() - 1);
@@ -117,6 +127,11 @@ impl Editor {
 
         self.glyph.as_mut().unwrap().layers[dest] = src_copy;
         self.glyph.as_mut().unwrap().layers[src] = dest_copy;
+        renumber_image_settings(self.glyph.as_mut().unwrap(), |(layer, image)| match layer {
+            layer if layer == src => Some((dest, image)),
+            layer if layer == dest => Some((src, image)),
+            layer => Some((layer, image)),
+        });
 
         if dest == 0 && self.glyph.as_ref().unwrap().layers[src].operation.is_none() {
             self.glyph.as_mut().unwrap().layers[dest].operation = None;
@@ -0,0 +1,196 @@
+// Sidebearings and advance width, and the metrics expressions that set them from other glyphs of the
+// UFO. An expression is either a number or `=glyph`, which takes the same metric of `glyph`.
+// `=glyph|` takes the opposite sidebearing instead, for mirrored pairs like b and d, and a trailing
+// `+n` or `-n` adjusts the result, e.g. `=o|+10`.
+
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::{FlattenedGlif as _, Glif, MFEKPointData};
+use kurbo::Affine;
+use skia_safe::Rect;
+
+use super::composite::read_glyph;
+use super::Editor;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    LeftSidebearing,
+    RightSidebearing,
+    Advance,
+}
+
+/// A glyph's sidebearings, which only exist if it has an outline, and advance width.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Metrics {
+    pub sidebearings: Option<(f32, f32)>,
+    pub advance: f32,
+}
+
+impl Metrics {
+    fn new(bounds: Option<Rect>, advance: f32) -> Self {
+        Metrics {
+            sidebearings: bounds.map(|b| (b.left, advance - b.right)),
+            advance,
+        }
+    }
+
+    pub fn get(&self, metric: Metric) -> Option<f32> {
+        match metric {
+            Metric::LeftSidebearing => self.sidebearings.map(|(l, _)| l),
+            Metric::RightSidebearing => self.sidebearings.map(|(_, r)| r),
+            Metric::Advance => Some(self.advance),
+        }
+    }
+}
+
+fn union(a: Option<Rect>, b: Rect) -> Rect {
+    match a {
+        None => b,
+        Some(a) => Rect::new(
+            a.left.min(b.left),
+            a.top.min(b.top),
+            a.right.max(b.right),
+            a.bottom.max(b.bottom),
+        ),
+    }
+}
+
+/// Metrics of a glyph read from disk, with its components flattened.
+pub fn glif_metrics(glif: &Glif<MFEKPointData>) -> Result<Metrics, String> {
+    let flattened = glif
+        .flattened(&mut None)
+        .map_err(|e| format!("failed to flatten {}: {:?}", glif.name, e))?;
+    let bounds = flattened
+        .outline
+        .as_ref()
+        .filter(|o| !o.is_empty())
+        .map(|o| o.to_skia_paths(None).combined().compute_tight_bounds());
+    Ok(Metrics::new(bounds, glif.width.unwrap_or(0) as f32))
+}
+
+// Splits a trailing `+n` or `-n` off an expression. Glyph names can contain `-`, so it only counts
+// if what follows is a number.
+fn split_adjustment(expr: &str) -> (&str, f32) {
+    if let Some(idx) = expr.rfind(|c| c == '+' || c == '-') {
+        if idx > 0 {
+            if let Ok(n) = expr[idx..].trim_start_matches('+').trim().parse::<f32>() {
+                return (expr[..idx].trim(), n);
+            }
+        }
+    }
+    (expr, 0.)
+}
+
+impl Editor {
+    /// Bounds of the visible layers after contour operations, together with the components.
+    pub fn preview_bounds(&self) -> Option<Rect> {
+        let mut bounds = None;
+        if let Some(preview) = self.preview.as_ref() {
+            for layer in preview.layers.iter().filter(|l| l.visible) {
+                if layer.outline.is_empty() {
+                    continue;
+                }
+                let b = layer.outline.to_skia_paths(None).combined().compute_tight_bounds();
+                bounds = Some(union(bounds, b));
+            }
+        }
+        let components = self.with_glyph(|g| g.components.vec.len());
+        for idx in 0..components {
+            if let Some(rect) = self.component_rect(idx) {
+                bounds = Some(union(bounds, rect));
+            }
+        }
+        bounds
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        let advance = self.with_glyph(|g| g.width.unwrap_or(0)) as f32;
+        Metrics::new(self.preview_bounds(), advance)
+    }
+
+    /// Evaluates a metrics expression for `metric` of the current glyph.
+    pub fn evaluate_metric(&self, expr: &str, metric: Metric) -> Result<f32, String> {
+        let expr = expr.trim();
+        let reference = match expr.strip_prefix('=') {
+            Some(reference) => reference.trim(),
+            None => {
+                return expr
+                    .parse()
+                    .map_err(|_| format!("{:?} is neither a number nor =glyph", expr))
+            }
+        };
+
+        let (reference, adjustment) = split_adjustment(reference);
+        let (name, opposite) = match reference.strip_suffix('|') {
+            Some(name) => (name.trim(), true),
+            None => (reference, false),
+        };
+        let metric = match (metric, opposite) {
+            (Metric::LeftSidebearing, true) => Metric::RightSidebearing,
+            (Metric::RightSidebearing, true) => Metric::LeftSidebearing,
+            (metric, _) => metric,
+        };
+
+        let dir = self
+            .glyphs_dir()
+            .ok_or_else(|| "this glyph wasn't loaded from a file".to_string())?;
+        let glif = read_glyph(&dir, name)?;
+        let value = glif_metrics(&glif)?
+            .get(metric)
+            .ok_or_else(|| format!("{} has no outline to measure", name))?;
+        Ok(value + adjustment)
+    }
+
+    /// Sets `metric` of the current glyph from a metrics expression.
+    pub fn set_metric(&mut self, metric: Metric, expr: &str) -> Result<(), String> {
+        let value = self.evaluate_metric(expr, metric)?.round();
+        let current = self.metrics();
+
+        match metric {
+            Metric::Advance => self.set_advance(value),
+            Metric::RightSidebearing => {
+                let (_, rsb) = current
+                    .sidebearings
+                    .ok_or_else(|| "an empty glyph has no sidebearings".to_string())?;
+                self.set_advance(current.advance + value - rsb)
+            }
+            Metric::LeftSidebearing => {
+                let (lsb, _) = current
+                    .sidebearings
+                    .ok_or_else(|| "an empty glyph has no sidebearings".to_string())?;
+                self.shift_glyph(value - lsb)
+            }
+        }
+        Ok(())
+    }
+
+    fn set_advance(&mut self, advance: f32) {
+        self.begin_modification("Set advance width.", false);
+        self.with_glyph_mut(|glyph| glyph.width = Some(advance.max(0.).round() as u64));
+        self.add_width_guidelines();
+        self.end_modification();
+    }
+
+    /// Moves everything in the glyph right by `dx`, growing the advance to keep the right
+    /// sidebearing.
+    fn shift_glyph(&mut self, dx: f32) {
+        self.begin_modification("Set left sidebearing.", false);
+        super::util::move_all_layers(self, -dx, 0.);
+        let components = self.with_glyph(|g| g.components.vec.len());
+        for idx in 0..components {
+            let matrix = self.component_matrix(idx);
+            self.set_component_matrix_no_history(
+                idx,
+                Affine::translate((dx as f64, 0.)) * matrix,
+            );
+        }
+        self.with_glyph_mut(|glyph| {
+            for anchor in glyph.anchors.iter_mut() {
+                anchor.x += dx;
+            }
+            let width = glyph.width.unwrap_or(0) as f32 + dx;
+            glyph.width = Some(width.max(0.).round() as u64);
+        });
+        self.add_width_guidelines();
+        self.end_modification();
+    }
+}
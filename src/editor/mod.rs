@@ -12,25 +12,43 @@ use glifparser::{
 pub use skia_safe::Contains as _;
 pub use skia_safe::{Canvas, Matrix, Path as SkPath, Point as SkPoint, Rect as SkRect};
 
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::path;
+use std::rc::Rc;
 use std::sync::mpsc::{Receiver, Sender};
 
-use self::{history::History, selection::EditorClipboard};
+use self::{history::History, selection::EditorClipboard, ufo_index::UfoIndex};
 
+pub mod anchors;
+pub mod autotrace;
+pub mod background;
+pub mod components;
+pub mod composite;
 pub mod contour_handlers;
+pub mod contours;
 pub mod debug;
 pub mod events;
 pub mod filesystem_watch;
+pub mod harmonize;
 pub mod headless;
 pub mod history;
 pub mod images;
 pub mod io;
+pub mod kerning;
 pub mod layers;
+pub mod metrics;
+pub mod nib;
+pub mod offset;
 pub mod operations;
+pub mod point_tags;
+pub mod quadratic;
 pub mod selection;
+pub mod spiro;
+pub mod svg;
 pub mod tools;
 pub mod tunni;
+pub mod ufo_index;
 pub mod util;
 
 #[macro_use]
@@ -54,6 +72,7 @@ pub struct Editor {
     layer_idx: Option<usize>,       // active layer
     pub contour_idx: Option<usize>, // index into Outline
     pub point_idx: Option<usize>,
+    pub component_idx: Option<usize>, // index into the glyph's components
 
     tool_behaviors: Vec<Box<dyn ToolBehavior>>,
     behavior_finished: bool,
@@ -68,6 +87,8 @@ pub struct Editor {
     pub selected: HashSet<(usize, usize)>,
 
     pub images: images::EditorImages,
+    // The glyphs beside this one, read when first needed (see `ufo_index`).
+    ufo_index: RefCell<Option<Rc<UfoIndex>>>,
     // These are UFO-global guidelines which won't be picked up by glifparser.
     pub guidelines: Vec<Guideline<MFEKPointData>>,
 
@@ -95,10 +116,12 @@ impl Editor {
             layer_idx: None,
             contour_idx: None,
             point_idx: None,
+            component_idx: None,
             italic_angle: 0.,
             selected: HashSet::new(),
 
             images: images::EditorImages::new(),
+            ufo_index: RefCell::new(None),
             guidelines: vec![],
             quit_requested: false,
             ipc_info: None,
@@ -271,6 +294,7 @@ impl Editor {
         self.guidelines.clear();
         self.add_width_guidelines();
         self.layer_idx = Some(0);
+        self.component_idx = None;
         self.mark_preview_dirty();
         self.recache_images();
         log::debug!("Images: {:?}", &self.images);
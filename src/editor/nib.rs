@@ -0,0 +1,230 @@
+// Broad nibs swept along skeleton contours (see `contour_operations::broadnib`). Contour operations
+// are defined by glifparser, so a skeleton is instead tagged with the id of its nib on one of its
+// points (see `point_tags`), and the nibs themselves are kept in the glyph lib under NIB_LIB_KEY by
+// id. The tag goes wherever its point does, so a skeleton keeps its nib however the contours around
+// it change; deleting points hands it on to what's left (see `keep_nib`).
+
+use glifparser::glif::{Lib, MFEKContour};
+use glifparser::{MFEKGlif, MFEKPointData};
+use plist::{Dictionary, Value};
+
+use std::collections::{HashMap, HashSet};
+
+use super::point_tags::{contour_tag, keep_contour_tag, set_contour_tag};
+use super::util::glyph_lib_mut;
+use super::Editor;
+use crate::contour_operations::broadnib::{BroadNib, NibShape};
+
+pub const NIB_LIB_KEY: &str = "org.MFEK.nibs";
+/// The point tag naming the nib a skeleton is swept with.
+pub const NIB_TAG: &str = "org.MFEK.nib";
+
+fn real(dict: &Dictionary, key: &str) -> Option<f64> {
+    let value = dict.get(key)?;
+    value
+        .as_real()
+        .or_else(|| value.as_signed_integer().map(|i| i as f64))
+}
+
+fn from_dict(dict: &Dictionary) -> Option<BroadNib> {
+    let shape = match dict.get("polygon").and_then(Value::as_array) {
+        Some(points) => NibShape::Polygon(
+            points
+                .iter()
+                .filter_map(|p| {
+                    let p = p.as_array()?;
+                    let coord = |v: &Value| {
+                        v.as_real()
+                            .or_else(|| v.as_signed_integer().map(|i| i as f64))
+                    };
+                    Some((coord(p.get(0)?)?, coord(p.get(1)?)?))
+                })
+                .collect(),
+        ),
+        None => NibShape::Broad {
+            width: real(dict, "width")?,
+            height: real(dict, "height")?,
+            angle: real(dict, "angle").unwrap_or(0.),
+        },
+    };
+    Some(BroadNib { shape })
+}
+
+fn to_dict(nib: &BroadNib) -> Dictionary {
+    let mut dict = Dictionary::new();
+    match &nib.shape {
+        NibShape::Broad {
+            width,
+            height,
+            angle,
+        } => {
+            dict.insert("width".to_string(), Value::Real(*width));
+            dict.insert("height".to_string(), Value::Real(*height));
+            dict.insert("angle".to_string(), Value::Real(*angle));
+        }
+        NibShape::Polygon(points) => {
+            let points = points
+                .iter()
+                .map(|(x, y)| Value::Array(vec![Value::Real(*x), Value::Real(*y)]))
+                .collect();
+            dict.insert("polygon".to_string(), Value::Array(points));
+        }
+    }
+    dict
+}
+
+/// The nibs in `lib`, by id.
+pub fn nibs_in(lib: &Lib) -> HashMap<String, BroadNib> {
+    match lib {
+        Lib::Plist(lib) => lib
+            .get(NIB_LIB_KEY)
+            .and_then(Value::as_dictionary)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|(id, entry)| {
+                        Some((id.clone(), from_dict(entry.as_dictionary()?)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => HashMap::new(),
+    }
+}
+
+/// The nib `contour` is swept with, if it's a skeleton.
+pub fn nib_of<'a>(
+    nibs: &'a HashMap<String, BroadNib>,
+    contour: &MFEKContour<MFEKPointData>,
+) -> Option<&'a BroadNib> {
+    nibs.get(&contour_tag(contour, NIB_TAG)?)
+}
+
+/// Gives `to`, a part of the skeleton `from` left after points were deleted, the nib of `from`.
+pub fn keep_nib(from: &MFEKContour<MFEKPointData>, to: &mut MFEKContour<MFEKPointData>) {
+    keep_contour_tag(from, to, NIB_TAG);
+}
+
+/// Whether any contour of `glyph` is a skeleton.
+pub fn has_skeletons(glyph: &MFEKGlif<MFEKPointData>) -> bool {
+    let nibs = nibs_in(&glyph.lib);
+    !nibs.is_empty()
+        && glyph
+            .layers
+            .iter()
+            .flat_map(|layer| layer.outline.iter())
+            .any(|contour| nib_of(&nibs, contour).is_some())
+}
+
+// The ids of the nibs skeletons of `glyph` are tagged with, in all layers.
+fn ids_in_use(glyph: &MFEKGlif<MFEKPointData>) -> HashSet<String> {
+    glyph
+        .layers
+        .iter()
+        .flat_map(|layer| layer.outline.iter())
+        .filter_map(|contour| contour_tag(contour, NIB_TAG))
+        .collect()
+}
+
+impl Editor {
+    /// The nib swept along contour `contour_idx` of the active layer, if it's a skeleton.
+    pub fn nib(&self, contour_idx: usize) -> Option<BroadNib> {
+        let layer_idx = self.layer_idx?;
+        self.with_glyph(|glyph| {
+            let contour = glyph.layers[layer_idx].outline.get(contour_idx)?;
+            nib_of(&nibs_in(&glyph.lib), contour).cloned()
+        })
+    }
+
+    /// Sets or, with `None`, removes the nib swept along contour `contour_idx` of the active layer.
+    pub fn set_nib(&mut self, contour_idx: usize, nib: Option<BroadNib>) {
+        if nib == self.nib(contour_idx) {
+            return;
+        }
+        self.begin_modification("Set broad nib.", true);
+        self.write_nib(contour_idx, nib);
+        self.end_modification();
+    }
+
+    /// Sweeps the convex hull of contour `nib_idx` along the skeleton `skeleton_idx`, both of the
+    /// active layer, and removes contour `nib_idx`, as it's then part of the skeleton's stroke.
+    pub fn use_contour_as_nib(&mut self, skeleton_idx: usize, nib_idx: usize) {
+        let nib = match BroadNib::from_contour(&self.get_active_layer_ref().outline[nib_idx]) {
+            Some(nib) => nib,
+            None => {
+                log::warn!("That contour is too small to be used as a nib");
+                return;
+            }
+        };
+        self.begin_modification("Use contour as nib.", true);
+        self.write_nib(skeleton_idx, Some(nib));
+        self.get_active_layer_mut().outline.remove(nib_idx);
+        self.end_modification();
+
+        let skeleton_idx = if nib_idx < skeleton_idx {
+            skeleton_idx - 1
+        } else {
+            skeleton_idx
+        };
+        self.contour_idx = Some(skeleton_idx);
+        self.point_idx = Some(0);
+    }
+
+    // Tags the contour with its nib's id, giving it an id of its own if it shares one with another
+    // contour (e.g. a pasted copy) so that only it changes, and drops nibs no contour uses any more.
+    fn write_nib(&mut self, contour_idx: usize, nib: Option<BroadNib>) {
+        let layer_idx = match self.layer_idx {
+            Some(layer_idx) => layer_idx,
+            None => return,
+        };
+        self.with_glyph_mut(|glyph| {
+            let current = contour_tag(&glyph.layers[layer_idx].outline[contour_idx], NIB_TAG);
+            let shared = current.as_ref().map_or(false, |id| {
+                glyph.layers.iter().enumerate().any(|(li, layer)| {
+                    layer.outline.iter().enumerate().any(|(ci, contour)| {
+                        (li, ci) != (layer_idx, contour_idx)
+                            && contour_tag(contour, NIB_TAG).as_ref() == Some(id)
+                    })
+                })
+            });
+            let mut nibs = nibs_in(&glyph.lib);
+            let id = match current {
+                Some(id) if !shared => id,
+                _ => {
+                    let in_use = ids_in_use(glyph);
+                    (0..)
+                        .map(|n: usize| n.to_string())
+                        .find(|id| !nibs.contains_key(id) && !in_use.contains(id))
+                        .unwrap()
+                }
+            };
+
+            let contour = &mut glyph.layers[layer_idx].outline[contour_idx];
+            match nib {
+                Some(nib) => {
+                    set_contour_tag(contour, NIB_TAG, Some(&id));
+                    nibs.insert(id, nib);
+                }
+                None => set_contour_tag(contour, NIB_TAG, None),
+            }
+            let in_use = ids_in_use(glyph);
+            nibs.retain(|id, _| in_use.contains(id));
+
+            let lib = match glyph_lib_mut(glyph) {
+                Some(lib) => lib,
+                None => return,
+            };
+            if nibs.is_empty() {
+                lib.remove(NIB_LIB_KEY);
+            } else {
+                let mut ids: Vec<_> = nibs.keys().collect();
+                ids.sort();
+                let entries = ids
+                    .into_iter()
+                    .map(|id| (id.clone(), Value::Dictionary(to_dict(&nibs[id]))))
+                    .collect();
+                lib.insert(NIB_LIB_KEY.to_string(), Value::Dictionary(entries));
+            }
+        });
+    }
+}
@@ -0,0 +1,216 @@
+use glifparser::{
+    glif::{
+        contour::MFEKContourCommon,
+        contour_operations::vws::{InterpolationType, VWSHandle},
+        MFEKContour,
+    },
+    CapType, JoinType, MFEKPointData, VWSContour,
+};
+use MFEKmath::{mfek::ResolveCubic, variable_width_stroke, Piecewise, VWSSettings};
+
+use super::Editor;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetMode {
+    /// Grow a closed contour outward.
+    Outset,
+    /// Shrink a closed contour inward.
+    Inset,
+    /// Stroke the contour on both sides, capping the ends of open contours.
+    Expand,
+}
+
+#[derive(Clone, Debug)]
+pub struct OffsetSettings {
+    pub mode: OffsetMode,
+    pub distance: f64,
+    pub join_type: JoinType,
+    pub cap_type: CapType,
+}
+
+impl Default for OffsetSettings {
+    fn default() -> Self {
+        Self {
+            mode: OffsetMode::Expand,
+            distance: 10.,
+            join_type: JoinType::Round,
+            cap_type: CapType::Round,
+        }
+    }
+}
+
+/// The area enclosed by a contour's on-curve points, whichever way it winds.
+fn area(contour: &MFEKContour<MFEKPointData>) -> f32 {
+    let points = match contour.cubic() {
+        Some(points) => points,
+        None => return 0.,
+    };
+    let twice: f32 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(p, q)| p.x * q.y - q.x * p.y)
+        .sum();
+    twice.abs() / 2.
+}
+
+/// Offsets a single contour by a constant distance, reusing the VWS stroker with equal offsets
+/// at every handle. The result is always cubic and carries no contour operation.
+///
+/// A closed contour strokes to an outline on each side of it. Which of them the stroker counts as
+/// inside depends on the way the contour winds, so outsetting keeps the larger of the two and
+/// insetting the smaller.
+pub fn offset_contour(
+    contour: &MFEKContour<MFEKPointData>,
+    settings: &OffsetSettings,
+) -> Option<Vec<MFEKContour<MFEKPointData>>> {
+    if settings.mode != OffsetMode::Expand && contour.is_open() {
+        return None;
+    }
+
+    let cubic = contour.to_cubic();
+    let vws_contour = VWSContour {
+        handles: (0..cubic.len() + 1)
+            .map(|_| VWSHandle {
+                left_offset: settings.distance,
+                right_offset: settings.distance,
+                interpolation: InterpolationType::Linear,
+                tangent_offset: 0.,
+            })
+            .collect(),
+        cap_start_type: settings.cap_type.clone(),
+        cap_end_type: settings.cap_type.clone(),
+        join_type: settings.join_type.clone(),
+        remove_internal: false,
+        remove_external: false,
+    };
+
+    let vws_settings = VWSSettings::<MFEKPointData> {
+        cap_custom_start: None,
+        cap_custom_end: None,
+    };
+
+    let output = variable_width_stroke(&Piecewise::from(cubic), &vws_contour, &vws_settings);
+    let sides: Vec<MFEKContour<MFEKPointData>> = output
+        .segs
+        .iter()
+        .map(|pw| pw.to_contour().into())
+        .collect();
+    let by_area = |a: &MFEKContour<MFEKPointData>, b: &MFEKContour<MFEKPointData>| {
+        area(a).total_cmp(&area(b))
+    };
+    Some(match settings.mode {
+        OffsetMode::Expand => sides,
+        OffsetMode::Outset => sides.into_iter().max_by(by_area).into_iter().collect(),
+        OffsetMode::Inset => sides.into_iter().min_by(by_area).into_iter().collect(),
+    })
+}
+
+impl Editor {
+    /// Replaces the selected contours (or every contour if nothing is selected) with their
+    /// offset. Open contours are skipped when insetting or outsetting.
+    pub fn offset_selection(&mut self, settings: &OffsetSettings) {
+        let mut targets: Vec<usize> = self.selected.iter().map(|(ci, _)| *ci).collect();
+        if let Some(ci) = self.contour_idx {
+            targets.push(ci);
+        }
+        if targets.is_empty() {
+            targets = (0..self.get_active_layer_ref().outline.len()).collect();
+        }
+
+        let layer = self.get_active_layer_ref();
+        let mut new_outline = vec![];
+        let mut changed = false;
+        for (ci, contour) in layer.outline.iter().enumerate() {
+            if !targets.contains(&ci) {
+                new_outline.push(contour.clone());
+                continue;
+            }
+            match offset_contour(contour, settings) {
+                Some(offset) => {
+                    new_outline.extend(offset);
+                    changed = true;
+                }
+                None => {
+                    log::warn!("Cannot {:?} open contour {}, skipping", settings.mode, ci);
+                    new_outline.push(contour.clone());
+                }
+            }
+        }
+
+        if !changed {
+            return;
+        }
+
+        self.begin_modification("Offset contours.", false);
+        self.get_active_layer_mut().outline = new_outline;
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+        self.end_modification();
+    }
+}
+
+#[cfg(test)]
+fn square(clockwise: bool, open: bool) -> MFEKContour<MFEKPointData> {
+    use glifparser::{Contour, Point, PointType};
+
+    let mut corners = vec![(0., 0.), (0., 100.), (100., 100.), (100., 0.)];
+    if !clockwise {
+        corners.reverse();
+    }
+    // A contour is open if it starts with a move point.
+    let contour: Contour<MFEKPointData> = corners
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let ptype = if open && i == 0 {
+                PointType::Move
+            } else {
+                PointType::Line
+            };
+            Point::from_x_y_type(p, ptype)
+        })
+        .collect();
+    contour.into()
+}
+
+#[test]
+fn offset_square_both_ways() {
+    for clockwise in [true, false] {
+        let offset = |mode| {
+            let settings = OffsetSettings {
+                mode,
+                ..OffsetSettings::default()
+            };
+            let mut contours = offset_contour(&square(clockwise, false), &settings).unwrap();
+            assert_eq!(contours.len(), 1, "{:?} clockwise: {}", mode, clockwise);
+            contours.remove(0)
+        };
+
+        let outset = offset(OffsetMode::Outset);
+        assert!(
+            area(&outset) > 100. * 100.,
+            "outset clockwise: {}",
+            clockwise
+        );
+        let xs = outset.cubic().unwrap().iter().map(|p| p.x);
+        assert!(xs.clone().fold(f32::INFINITY, f32::min) < -9.5);
+        assert!(xs.fold(f32::NEG_INFINITY, f32::max) > 109.5);
+
+        let inset = offset(OffsetMode::Inset);
+        assert!(area(&inset) < 100. * 100., "inset clockwise: {}", clockwise);
+        assert!(inset
+            .cubic()
+            .unwrap()
+            .iter()
+            .all(|p| (-0.5..=100.5).contains(&p.x) && (-0.5..=100.5).contains(&p.y)));
+    }
+
+    // Open contours can only be expanded.
+    let open = square(true, true);
+    let inset = OffsetSettings {
+        mode: OffsetMode::Inset,
+        ..OffsetSettings::default()
+    };
+    assert!(offset_contour(&open, &inset).is_none());
+}
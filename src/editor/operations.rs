@@ -10,6 +10,9 @@ use glifparser::{FlattenedGlif, MFEKPointData};
 use skia_safe::PathOp;
 use MFEKmath::mfek::ResolveCubic;
 
+use super::nib::{has_skeletons, nib_of, nibs_in, NIB_TAG};
+use super::point_tags::strip_tags;
+use super::spiro::{self, is_spiro, SPIRO_TAG};
 use super::Editor;
 
 impl Editor {
@@ -33,16 +36,30 @@ impl Editor {
         }
 
         //self.fix_contour_ops();
+        let nibs = nibs_in(&self.glyph.as_ref().unwrap().lib);
         let mut preview_layers = Vec::new();
         for layer in &self.glyph.as_mut().unwrap().layers {
             let mut preview_outline = Vec::new();
 
             for (_idx, glif_contour) in layer.outline.iter().enumerate() {
+                let solved;
+                let glif_contour = if is_spiro(glif_contour) {
+                    solved = spiro::solved(glif_contour);
+                    &solved
+                } else {
+                    glif_contour
+                };
+
                 if glif_contour.inner().len() <= 1 {
                     preview_outline.push(glif_contour.to_cubic());
                     continue;
                 }
 
+                if let Some(nib) = nib_of(&nibs, glif_contour) {
+                    preview_outline.extend(nib.build(glif_contour));
+                    continue;
+                }
+
                 let build_result: Vec<glifparser::glif::MFEKContour<MFEKPointData>> =
                     glif_contour.operation().build(glif_contour);
 
@@ -71,6 +88,12 @@ impl Editor {
     }
 
     pub fn prepare_export(&self) -> MFEKGlif<MFEKPointData> {
+        let mut export = self.combine_layers();
+        strip_tags(&mut export, &[NIB_TAG, SPIRO_TAG]);
+        export
+    }
+
+    fn combine_layers(&self) -> MFEKGlif<MFEKPointData> {
         let glyph = self
             .glyph
             .as_ref()
@@ -80,8 +103,11 @@ impl Editor {
                 .outline
                 .iter()
                 .all(|c| c.operation().clone() == None)
+            && !has_skeletons(glyph)
         {
-            return glyph.clone();
+            let mut export = glyph.clone();
+            spiro::solve_all(&mut export);
+            return export;
         }
 
         let glif = self.preview.as_ref().unwrap_or(glyph);
@@ -0,0 +1,172 @@
+// Data that has to stay with a contour, or a point of it, through any edit. glifparser has no lib
+// for contours or points, and anything kept in the glyph lib by contour index ends up on the wrong
+// contour as soon as one before it is deleted, so such data is kept in the points' names instead.
+// A tag is a `key=value` word after whatever name the point already has, so it's saved with the
+// point in .glifjson and follows it through cuts, merges and deletions. Tags mean nothing to other
+// UFO tools, so they're stripped from exported glyphs (see `strip_tags`).
+
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::point::MFEKPointCommon as _;
+use glifparser::glif::MFEKContour;
+use glifparser::{MFEKGlif, MFEKPointData};
+
+/// The value of tag `key` in the point name `name`.
+pub fn tag<'a>(name: &'a str, key: &str) -> Option<&'a str> {
+    name.split_whitespace().find_map(|word| {
+        word.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix('='))
+    })
+}
+
+/// The point name `name` with tag `key` set to `value` or, with `None`, removed.
+pub fn with_tag(name: &str, key: &str, value: Option<&str>) -> Option<String> {
+    let mut words: Vec<String> = name
+        .split_whitespace()
+        .filter(|word| tag(word, key).is_none())
+        .map(str::to_string)
+        .collect();
+    if let Some(value) = value {
+        words.push(format!("{}={}", key, value));
+    }
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// The value of tag `key` on point `point_idx` of `contour`.
+pub fn point_tag(
+    contour: &MFEKContour<MFEKPointData>,
+    point_idx: usize,
+    key: &str,
+) -> Option<String> {
+    let name = contour.get_point(point_idx)?.get_name()?;
+    tag(&name, key).map(str::to_string)
+}
+
+/// Sets or, with `None`, removes tag `key` on point `point_idx` of `contour`.
+pub fn set_point_tag(
+    contour: &mut MFEKContour<MFEKPointData>,
+    point_idx: usize,
+    key: &str,
+    value: Option<&str>,
+) {
+    let name = contour
+        .get_point(point_idx)
+        .and_then(|point| point.get_name())
+        .unwrap_or_default();
+    let name = with_tag(&name, key, value);
+    if let Some(points) = contour.cubic_mut() {
+        points[point_idx].name = name;
+    } else if let Some(points) = contour.quad_mut() {
+        points[point_idx].name = name;
+    } else if let Some(point) = contour.get_point_mut(point_idx) {
+        point.set_name(name.unwrap_or_default());
+    }
+}
+
+/// The value of tag `key` on the first point of `contour` that has it.
+pub fn contour_tag(contour: &MFEKContour<MFEKPointData>, key: &str) -> Option<String> {
+    (0..contour.len()).find_map(|point_idx| point_tag(contour, point_idx, key))
+}
+
+/// Sets or removes tag `key` of `contour` as a whole. It's kept on a single point, the first.
+pub fn set_contour_tag(contour: &mut MFEKContour<MFEKPointData>, key: &str, value: Option<&str>) {
+    for point_idx in 1..contour.len() {
+        if point_tag(contour, point_idx, key).is_some() {
+            set_point_tag(contour, point_idx, key, None);
+        }
+    }
+    if contour.len() > 0 {
+        set_point_tag(contour, 0, key, value);
+    }
+}
+
+/// Gives `to`, a part of `from` left after points were deleted, the tag `key` of `from` as a whole
+/// if the point carrying it was among those deleted.
+pub fn keep_contour_tag(
+    from: &MFEKContour<MFEKPointData>,
+    to: &mut MFEKContour<MFEKPointData>,
+    key: &str,
+) {
+    if contour_tag(to, key).is_none() {
+        if let Some(value) = contour_tag(from, key) {
+            set_contour_tag(to, key, Some(&value));
+        }
+    }
+}
+
+/// Removes tags `keys` from every point of `glyph`, leaving the names the points had before.
+pub fn strip_tags(glyph: &mut MFEKGlif<MFEKPointData>, keys: &[&str]) {
+    for contour in glyph
+        .layers
+        .iter_mut()
+        .flat_map(|layer| layer.outline.iter_mut())
+    {
+        strip_contour_tags(contour, keys);
+    }
+}
+
+fn strip_contour_tags(contour: &mut MFEKContour<MFEKPointData>, keys: &[&str]) {
+    for point_idx in 0..contour.len() {
+        for key in keys {
+            if point_tag(contour, point_idx, key).is_some() {
+                set_point_tag(contour, point_idx, key, None);
+            }
+        }
+    }
+}
+
+#[test]
+fn point_name_tags() {
+    assert_eq!(tag("org.MFEK.nib=3", "org.MFEK.nib"), Some("3"));
+    assert_eq!(tag("top org.MFEK.spiro=c", "org.MFEK.spiro"), Some("c"));
+    assert_eq!(tag("org.MFEK.nibs=3", "org.MFEK.nib"), None);
+
+    // Other words of the name are kept.
+    let name = with_tag("top", "org.MFEK.nib", Some("1")).unwrap();
+    assert_eq!(name, "top org.MFEK.nib=1");
+    let name = with_tag(&name, "org.MFEK.nib", Some("2")).unwrap();
+    assert_eq!(name, "top org.MFEK.nib=2");
+    assert_eq!(
+        with_tag(&name, "org.MFEK.nib", None).as_deref(),
+        Some("top")
+    );
+    assert_eq!(with_tag("org.MFEK.nib=2", "org.MFEK.nib", None), None);
+}
+
+#[test]
+fn contour_tags() {
+    use glifparser::{Contour, Point, PointType};
+
+    let square: Contour<MFEKPointData> = [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]
+        .iter()
+        .map(|p| Point::from_x_y_type(*p, PointType::Curve))
+        .collect();
+    let mut contour: MFEKContour<MFEKPointData> = square.into();
+    contour.cubic_mut().unwrap()[2].name = Some("corner".to_string());
+
+    set_contour_tag(&mut contour, "org.MFEK.nib", Some("0"));
+    let names: Vec<_> = contour
+        .cubic()
+        .unwrap()
+        .iter()
+        .map(|p| p.name.clone())
+        .collect();
+    assert_eq!(names[0].as_deref(), Some("org.MFEK.nib=0"));
+    assert_eq!(names[1], None);
+
+    // What's left after the tagged point is deleted keeps the tag.
+    let mut rest = contour.clone();
+    rest.cubic_mut().unwrap().remove(0);
+    assert_eq!(contour_tag(&rest, "org.MFEK.nib"), None);
+    keep_contour_tag(&contour, &mut rest, "org.MFEK.nib");
+    assert_eq!(contour_tag(&rest, "org.MFEK.nib").as_deref(), Some("0"));
+
+    // Exported points have only their own names.
+    strip_contour_tags(&mut rest, &["org.MFEK.nib"]);
+    let names: Vec<_> = rest
+        .cubic()
+        .unwrap()
+        .iter()
+        .map(|p| p.name.clone())
+        .collect();
+    assert_eq!(names, vec![None, Some("corner".to_string()), None]);
+}
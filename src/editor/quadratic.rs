@@ -0,0 +1,297 @@
+// Cubic → quadratic conversion for TrueType-flavoured UFOs. Every cubic segment is split into as
+// few equal-parameter pieces as needed for each piece to be within `tolerance` of a single
+// quadratic, in the spirit of cu2qu.
+
+use glifparser::{
+    glif::{
+        contour::MFEKContourCommon, contour_operations::ContourOperation,
+        inner::MFEKContourInner, inner::MFEKContourInnerType, point::quad::QPoint,
+        point::MFEKPointCommon, MFEKContour,
+    },
+    Contour, Handle, MFEKPointData, Point, PointType,
+};
+use MFEKmath::{mfek::ResolveCubic, vec2, Vector};
+
+use super::Editor;
+
+/// Default maximum distance, in font units, between a cubic and its quadratic approximation.
+pub const DEFAULT_QUADRATIC_TOLERANCE: f64 = 1.;
+// Upper bound on pieces per cubic segment; past this we accept whatever error remains.
+const MAX_SPLITS: usize = 16;
+const ERROR_SAMPLES: usize = 16;
+
+type CubicSeg = [Vector; 4];
+
+fn lerp(a: Vector, b: Vector, t: f64) -> Vector {
+    a + (b - a) * t
+}
+
+fn eval_cubic(c: &CubicSeg, t: f64) -> Vector {
+    let mt = 1. - t;
+    c[0] * (mt * mt * mt) + c[1] * (3. * mt * mt * t) + c[2] * (3. * mt * t * t) + c[3] * (t * t * t)
+}
+
+fn eval_quad(q0: Vector, q1: Vector, q2: Vector, t: f64) -> Vector {
+    let mt = 1. - t;
+    q0 * (mt * mt) + q1 * (2. * mt * t) + q2 * (t * t)
+}
+
+// de Casteljau split of a cubic at t.
+fn split_cubic(c: &CubicSeg, t: f64) -> (CubicSeg, CubicSeg) {
+    let ab = lerp(c[0], c[1], t);
+    let bc = lerp(c[1], c[2], t);
+    let cd = lerp(c[2], c[3], t);
+    let abc = lerp(ab, bc, t);
+    let bcd = lerp(bc, cd, t);
+    let mid = lerp(abc, bcd, t);
+    ([c[0], ab, abc, mid], [mid, bcd, cd, c[3]])
+}
+
+// Splits `c` into `n` pieces of equal parameter length.
+fn split_cubic_evenly(c: &CubicSeg, n: usize) -> Vec<CubicSeg> {
+    let mut ret = vec![];
+    let mut rest = *c;
+    for i in 0..n - 1 {
+        // Each split is relative to what's left of the curve.
+        let t = 1. / (n - i) as f64;
+        let (head, tail) = split_cubic(&rest, t);
+        ret.push(head);
+        rest = tail;
+    }
+    ret.push(rest);
+    ret
+}
+
+// Best single quadratic control point for a cubic piece, and how far it strays from it.
+fn approximate(c: &CubicSeg) -> (Vector, f64) {
+    let q1 = ((c[1] + c[2]) * 3. - c[0] - c[3]) * 0.25;
+    let error = (1..ERROR_SAMPLES)
+        .map(|s| {
+            let t = s as f64 / ERROR_SAMPLES as f64;
+            eval_cubic(c, t).distance(eval_quad(c[0], q1, c[3], t))
+        })
+        .fold(0., f64::max);
+    (q1, error)
+}
+
+/// Returns the control points of the quadratics approximating `c`, one per piece.
+fn cubic_seg_to_quads(c: &CubicSeg, tolerance: f64) -> Vec<(Vector, Vector)> {
+    for n in 1..=MAX_SPLITS {
+        let pieces = split_cubic_evenly(c, n);
+        let approximations: Vec<_> = pieces.iter().map(approximate).collect();
+        if n == MAX_SPLITS || approximations.iter().all(|(_, err)| *err <= tolerance) {
+            return pieces
+                .iter()
+                .zip(approximations)
+                .map(|(piece, (q1, _))| (q1, piece[3]))
+                .collect();
+        }
+    }
+    unreachable!()
+}
+
+fn handle_or(handle: Handle, x: f32, y: f32) -> Vector {
+    match handle {
+        Handle::At(hx, hy) => vec2![hx, hy],
+        Handle::Colocated => vec2![x, y],
+    }
+}
+
+/// Converts any contour into a quadratic one, keeping its contour operation in step with the
+/// points that get inserted.
+pub fn contour_to_quad(
+    contour: &MFEKContour<MFEKPointData>,
+    tolerance: f64,
+) -> MFEKContour<MFEKPointData> {
+    if contour.get_type() == MFEKContourInnerType::Quad {
+        return contour.clone();
+    }
+
+    let cubic = contour.to_cubic();
+    let points = cubic.cubic().unwrap();
+    let closed = cubic.is_closed();
+    let mut inserted = vec![];
+    let mut qpoints: Vec<QPoint<MFEKPointData>> = vec![];
+    for (idx, point) in points.iter().enumerate() {
+        qpoints.push(QPoint {
+            x: point.x,
+            y: point.y,
+            a: Handle::Colocated,
+            name: point.name.clone(),
+            ptype: point.ptype,
+            smooth: point.get_smooth().unwrap_or(false),
+            data: None,
+        });
+
+        let next = if idx + 1 < points.len() {
+            &points[idx + 1]
+        } else if closed {
+            &points[0]
+        } else {
+            break;
+        };
+
+        // Straight lines stay straight.
+        if point.a == Handle::Colocated && next.b == Handle::Colocated {
+            continue;
+        }
+
+        let seg = [
+            vec2![point.x, point.y],
+            handle_or(point.a, point.x, point.y),
+            handle_or(next.b, next.x, next.y),
+            vec2![next.x, next.y],
+        ];
+        let quads = cubic_seg_to_quads(&seg, tolerance);
+        let last = quads.len() - 1;
+        for (qidx, (control, end)) in quads.into_iter().enumerate() {
+            qpoints.last_mut().unwrap().a = Handle::At(control.x as f32, control.y as f32);
+            if qidx != last {
+                inserted.push(qpoints.len());
+                qpoints.push(QPoint {
+                    x: end.x as f32,
+                    y: end.y as f32,
+                    a: Handle::Colocated,
+                    name: None,
+                    ptype: PointType::Curve,
+                    smooth: true,
+                    data: None,
+                });
+            }
+        }
+    }
+
+    let mut new_contour = MFEKContour::new(
+        MFEKContourInner::Quad(qpoints),
+        contour.operation().clone(),
+    );
+    // Indices are ascending final positions, so inserting in order lines everything up.
+    for idx in inserted {
+        new_contour.operation_mut().insert_op(idx);
+    }
+    if closed {
+        new_contour.set_closed();
+    } else {
+        new_contour.set_open();
+    }
+    new_contour
+}
+
+/// Lays a quadratic contour out as .glif points: each segment's off-curve point is the `a`
+/// handle of its start, and its end point becomes a `qcurve`.
+pub fn quad_to_glif_contour(contour: &MFEKContour<MFEKPointData>) -> Contour<MFEKPointData> {
+    let qpoints = contour.quad().unwrap();
+    let closed = contour.is_closed();
+    let len = qpoints.len();
+
+    let mut ret: Contour<MFEKPointData> = vec![];
+    for (idx, qpoint) in qpoints.iter().enumerate() {
+        let prev = if idx > 0 {
+            Some(&qpoints[idx - 1])
+        } else if closed && len > 1 {
+            Some(&qpoints[len - 1])
+        } else {
+            None
+        };
+
+        let ptype = match prev.map(|p| p.a) {
+            None => PointType::Move,
+            Some(Handle::At(..)) => PointType::QCurve,
+            Some(Handle::Colocated) => PointType::Line,
+        };
+
+        let mut point = Point::from_x_y_type((qpoint.x, qpoint.y), ptype);
+        point.name = qpoint.name.clone();
+        if idx + 1 < len || closed {
+            point.a = qpoint.a;
+        }
+        ret.push(point);
+    }
+    ret
+}
+
+/// Converts a whole outline for writing to a quadratic .glif.
+pub fn to_glif_outline(
+    outline: &[MFEKContour<MFEKPointData>],
+    tolerance: f64,
+) -> Vec<Contour<MFEKPointData>> {
+    outline
+        .iter()
+        .map(|c| quad_to_glif_contour(&contour_to_quad(c, tolerance)))
+        .collect()
+}
+
+impl Editor {
+    pub fn convert_contour_to_quad(&mut self, contour_idx: usize, tolerance: f64) {
+        let quad = contour_to_quad(&self.get_active_layer_ref().outline[contour_idx], tolerance);
+
+        self.begin_modification("Made contour a quadratic.", false);
+        self.get_active_layer_mut().outline[contour_idx] = quad;
+        // Conversion inserts points, so the old point index may no longer be meaningful.
+        self.point_idx = Some(0);
+        self.selected.clear();
+        self.end_modification();
+    }
+
+    pub fn convert_layer_to_quad(&mut self, tolerance: f64) {
+        let outline: Vec<_> = self
+            .get_active_layer_ref()
+            .outline
+            .iter()
+            .map(|c| contour_to_quad(c, tolerance))
+            .collect();
+
+        self.begin_modification("Made layer quadratic.", false);
+        self.get_active_layer_mut().outline = outline;
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+        self.end_modification();
+    }
+}
+
+#[test]
+fn cubic_to_quads_within_tolerance() {
+    let c: CubicSeg = [vec2![0., 0.], vec2![0., 100.], vec2![100., 200.], vec2![300., 200.]];
+    for tolerance in [1., 0.25] {
+        let quads = cubic_seg_to_quads(&c, tolerance);
+        assert!(quads.len() > 1);
+        assert_eq!(quads.last().unwrap().1, c[3]);
+
+        // The pieces are of equal parameter length, so each can be checked against its stretch.
+        let n = quads.len() as f64;
+        let mut start = c[0];
+        for (k, (control, end)) in quads.iter().enumerate() {
+            for s in 0..=100 {
+                let t = s as f64 / 100.;
+                let on_cubic = eval_cubic(&c, (k as f64 + t) / n);
+                assert!(eval_quad(start, *control, *end, t).distance(on_cubic) <= tolerance);
+            }
+            start = *end;
+        }
+    }
+}
+
+#[test]
+fn quad_glif_contour_point_types() {
+    let mut cubic: Contour<MFEKPointData> = vec![
+        Point::from_x_y_type((0., 0.), PointType::Move),
+        Point::from_x_y_type((100., 0.), PointType::Line),
+        Point::from_x_y_type((300., 200.), PointType::Curve),
+    ];
+    cubic[1].a = Handle::At(200., 0.);
+    cubic[2].b = Handle::At(300., 100.);
+    let mut contour: MFEKContour<MFEKPointData> = cubic.into();
+    contour.set_open();
+
+    let glif = quad_to_glif_contour(&contour_to_quad(&contour, 1.));
+    let types: Vec<_> = glif.iter().map(|p| p.ptype).collect();
+    assert_eq!(types[..2], [PointType::Move, PointType::Line]);
+    assert!(types[2..].iter().all(|t| *t == PointType::QCurve));
+    // The ends stay where they were, and the last point of an open contour has no handle.
+    assert_eq!((glif[0].x, glif[0].y), (0., 0.));
+    let last = glif.last().unwrap();
+    assert_eq!((last.x, last.y), (300., 200.));
+    assert_eq!(last.a, Handle::Colocated);
+    assert!(glif[1..glif.len() - 1].iter().all(|p| matches!(p.a, Handle::At(..))));
+}
@@ -13,6 +13,7 @@ use arboard::{self, Clipboard};
 use serde_json;
 use shrinkwraprs;
 
+use super::nib::keep_nib;
 use super::Editor;
 use crate::user_interface::gui;
 
@@ -142,8 +143,12 @@ impl Editor {
     }
 
     /// If `position` is provided, it means that the client is requesting that the layer outline be
-    /// moved
+    /// moved. An image on the clipboard is pasted as a UFO image instead, see `paste_image`.
     pub fn paste_selection(&mut self, position: Option<(f32, f32)>) {
+        if self.paste_image(position) {
+            return;
+        }
+
         let mut clipboard: Layer<_> = if let Some(data) = self.clipboard.with(|clipboard: &mut Clipboard| {
             let cbtext; // [For borrow checker!]
             let (mimetype, data) = match clipboard.get_text() {
@@ -236,8 +241,10 @@ impl Editor {
         let layer = self.get_active_layer_mut();
         let contour = &mut layer.outline[contour_idx];
 
+        let before = contour.clone();
         contour.inner_mut().delete(point_idx);
         contour.operation_mut().remove_op(point_idx);
+        keep_nib(&before, contour);
 
         self.contour_idx = None;
         self.point_idx = None;
@@ -369,6 +376,7 @@ impl Editor {
 
         self.begin_modification("Simplify selection.", false);
         let layer = self.get_active_layer_mut();
+        let before = layer.outline[contour_idx].clone();
         let contour = layer.outline[contour_idx].cubic_mut().unwrap();
 
         contour[prev_idx].a = Handle::At(fitted_curve.w2.x as f32, fitted_curve.w2.y as f32);
@@ -378,6 +386,7 @@ impl Editor {
         layer.outline[contour_idx]
             .operation_mut()
             .remove_op(point_idx);
+        keep_nib(&before, &mut layer.outline[contour_idx]);
 
         self.contour_idx = None;
         self.point_idx = None;
@@ -421,6 +430,7 @@ impl Editor {
                 if !result.inner().is_empty() {
                     if deleted {
                         result.inner_mut().set_open();
+                        keep_nib(contour, &mut result);
                         //result.inner.first_mut().unwrap().b = Handle::Colocated;
                         //result.inner.last_mut().unwrap().a = Handle::Colocated;
                     }
@@ -0,0 +1,244 @@
+// Spiro contours. glifparser has no Spiro contour type, so a Spiro contour is a cubic contour of
+// its knots alone, without handles, and each knot's Spiro point type is saved with it in the
+// .glifjson (see `point_tags`), staying with the knot however the contour is edited. The curve
+// through the knots is only ever solved into copies: the preview, which is drawn and exported
+// through the same `to_cubic` path as any other contour, and the glyph prepared for export.
+
+mod solver;
+
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::MFEKContour;
+use glifparser::{Handle, MFEKGlif, MFEKPointData, Point, PointType};
+
+use self::solver::{spiro_to_cubics, ControlPoint};
+use super::point_tags::{contour_tag, point_tag, set_point_tag, with_tag};
+use super::Editor;
+
+/// The point tag giving a Spiro knot's type, as its character (see `SpiroPointType::to_char`).
+pub const SPIRO_TAG: &str = "org.MFEK.spiro";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpiroPointType {
+    /// Continuous up to the second derivative of curvature.
+    G4,
+    /// Continuous in curvature.
+    G2,
+    Corner,
+    /// The join from a straight line on the left into a curve.
+    Left,
+    /// The join from a curve into a straight line on the right.
+    Right,
+}
+
+impl SpiroPointType {
+    pub const ALL: [SpiroPointType; 5] = [
+        SpiroPointType::G4,
+        SpiroPointType::G2,
+        SpiroPointType::Corner,
+        SpiroPointType::Left,
+        SpiroPointType::Right,
+    ];
+
+    /// The character libspiro and the Spiro plist format use for this type.
+    pub fn to_char(self) -> char {
+        match self {
+            SpiroPointType::G4 => 'o',
+            SpiroPointType::G2 => 'c',
+            SpiroPointType::Corner => 'v',
+            SpiroPointType::Left => '[',
+            SpiroPointType::Right => ']',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.to_char() == c)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SpiroPointType::G4 => "G4",
+            SpiroPointType::G2 => "G2",
+            SpiroPointType::Corner => "Corner",
+            SpiroPointType::Left => "Left",
+            SpiroPointType::Right => "Right",
+        }
+    }
+}
+
+/// Whether `contour` is a Spiro contour, i.e. any of its knots has a Spiro point type.
+pub fn is_spiro(contour: &MFEKContour<MFEKPointData>) -> bool {
+    contour_tag(contour, SPIRO_TAG).is_some()
+}
+
+/// The Spiro point type of knot `idx` of `contour`. Knots without one are G2.
+pub fn point_type(contour: &MFEKContour<MFEKPointData>, idx: usize) -> SpiroPointType {
+    point_tag(contour, idx, SPIRO_TAG)
+        .and_then(|tag| tag.chars().next())
+        .and_then(SpiroPointType::from_char)
+        .unwrap_or(SpiroPointType::G2)
+}
+
+/// A new knot at `position`, of point type `ptype` and Spiro point type `spiro_type`.
+pub fn knot(
+    position: (f32, f32),
+    ptype: PointType,
+    spiro_type: SpiroPointType,
+) -> Point<MFEKPointData> {
+    let mut knot = Point::from_x_y_type(position, ptype);
+    knot.name = with_tag("", SPIRO_TAG, Some(&spiro_type.to_char().to_string()));
+    knot
+}
+
+/// Solves the spline through the knots of the Spiro contour `contour`, putting the cubic handles
+/// of the solved curve on them.
+pub fn solve(contour: &mut MFEKContour<MFEKPointData>) {
+    let closed = contour.is_closed();
+    let types: Vec<SpiroPointType> = (0..contour.len())
+        .map(|idx| point_type(contour, idx))
+        .collect();
+    let points = match contour.cubic_mut() {
+        Some(points) if points.len() >= 2 => points,
+        _ => return,
+    };
+
+    let n = points.len();
+    let knots: Vec<ControlPoint> = points
+        .iter()
+        .enumerate()
+        .map(|(idx, point)| ControlPoint {
+            x: point.x as f64,
+            y: point.y as f64,
+            ty: match idx {
+                0 if !closed => '{',
+                idx if idx == n - 1 && !closed => '}',
+                idx => types[idx].to_char(),
+            },
+        })
+        .collect();
+
+    let cubics = spiro_to_cubics(&knots);
+    for point in points.iter_mut() {
+        point.a = Handle::Colocated;
+        point.b = Handle::Colocated;
+    }
+    if cubics
+        .iter()
+        .flatten()
+        .any(|(x, y)| !x.is_finite() || !y.is_finite())
+    {
+        log::warn!("Failed to solve Spiro contour, drawing it with straight lines");
+        return;
+    }
+    for (idx, [_, c1, c2, _]) in cubics.iter().enumerate() {
+        points[idx].a = Handle::At(c1.0 as f32, c1.1 as f32);
+        points[(idx + 1) % n].b = Handle::At(c2.0 as f32, c2.1 as f32);
+    }
+}
+
+/// A copy of the Spiro contour `contour` with the cubic handles of its solved curve.
+pub fn solved(contour: &MFEKContour<MFEKPointData>) -> MFEKContour<MFEKPointData> {
+    let mut contour = contour.clone();
+    solve(&mut contour);
+    contour
+}
+
+/// Solves every Spiro contour of `glyph`, a copy for export, in all layers.
+pub fn solve_all(glyph: &mut MFEKGlif<MFEKPointData>) {
+    for layer in glyph.layers.iter_mut() {
+        for contour in layer.outline.iter_mut().filter(|c| is_spiro(c)) {
+            solve(contour);
+        }
+    }
+}
+
+impl Editor {
+    /// Whether contour `contour_idx` of the active layer is a Spiro contour.
+    pub fn is_spiro(&self, contour_idx: usize) -> bool {
+        self.get_active_layer_ref()
+            .outline
+            .get(contour_idx)
+            .map_or(false, is_spiro)
+    }
+
+    /// The Spiro point type of a knot of the active layer, if its contour is a Spiro contour.
+    pub fn spiro_point_type(&self, contour_idx: usize, point_idx: usize) -> Option<SpiroPointType> {
+        let layer = self.get_active_layer_ref();
+        let contour = layer.outline.get(contour_idx).filter(|c| is_spiro(c))?;
+        Some(point_type(contour, point_idx))
+    }
+
+    /// Changes the Spiro point type of a knot on a Spiro contour of the active layer.
+    pub fn set_spiro_point_type(
+        &mut self,
+        contour_idx: usize,
+        point_idx: usize,
+        ptype: SpiroPointType,
+    ) {
+        match self.spiro_point_type(contour_idx, point_idx) {
+            Some(current) if current != ptype => (),
+            _ => return,
+        }
+        self.begin_modification("Set Spiro point type.", false);
+        set_point_tag(
+            &mut self.get_active_layer_mut().outline[contour_idx],
+            point_idx,
+            SPIRO_TAG,
+            Some(&ptype.to_char().to_string()),
+        );
+        self.end_modification();
+    }
+}
+
+#[cfg(test)]
+fn spiro_square(types: &[SpiroPointType]) -> MFEKContour<MFEKPointData> {
+    let corners = [(0., 0.), (100., 0.), (100., 100.), (0., 100.)];
+    let points: glifparser::Contour<MFEKPointData> = corners
+        .iter()
+        .zip(types)
+        .map(|(corner, ptype)| knot(*corner, PointType::Curve, *ptype))
+        .collect();
+    points.into()
+}
+
+#[test]
+fn spiro_types_follow_their_knots() {
+    use SpiroPointType::*;
+    let mut contour = spiro_square(&[G2, Corner, Left, G4]);
+    assert!(is_spiro(&contour));
+    contour.cubic_mut().unwrap().remove(0);
+    let types: Vec<_> = (0..contour.len())
+        .map(|idx| point_type(&contour, idx))
+        .collect();
+    assert_eq!(types, vec![Corner, Left, G4]);
+}
+
+#[test]
+fn spiro_solved_into_a_copy() {
+    use SpiroPointType::*;
+    let knots = spiro_square(&[G2, G2, G2, G2]);
+    let contour = solved(&knots);
+    let points = contour.cubic().unwrap();
+    // The knots stay put, and with four G2 knots the curve is round, so every handle is out.
+    assert_eq!((points[1].x, points[1].y), (100., 0.));
+    assert!(points
+        .iter()
+        .all(|p| matches!((p.a, p.b), (Handle::At(..), Handle::At(..)))));
+    // The knots themselves are left alone.
+    assert!(knots
+        .cubic()
+        .unwrap()
+        .iter()
+        .all(|p| matches!((p.a, p.b), (Handle::Colocated, Handle::Colocated))));
+
+    // Corners all round are straight lines.
+    let mut contour = spiro_square(&[Corner, Corner, Corner, Corner]);
+    solve(&mut contour);
+    for (idx, point) in contour.cubic().unwrap().iter().enumerate() {
+        let next = &contour.cubic().unwrap()[(idx + 1) % 4];
+        if let Handle::At(x, y) = point.a {
+            // On the line to the next knot.
+            let cross = (x - point.x) * (next.y - point.y) - (y - point.y) * (next.x - point.x);
+            assert!(cross.abs() < 1e-2);
+        }
+    }
+}
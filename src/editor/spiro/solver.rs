@@ -0,0 +1,491 @@
+// A port of the curve solver from Raph Levien's libspiro. Each segment between two knots is a
+// piece of an Euler spiral, its curvature a polynomial in arc length described by `ks`; the
+// solver finds the `ks` which make the curvature and its derivatives continuous across each knot,
+// as far as that knot's type asks for, by Newton iteration over a banded matrix.
+//
+// Unlike libspiro, each segment is output as exactly one cubic, so the solved contour has the same
+// points as the knots and point indices, selections and contour operations line up with it.
+
+use std::f64::consts::PI;
+
+/// A knot, typed with libspiro's characters: `o` G4, `c` G2, `v` corner, `[` left and `]` right,
+/// and `{` and `}` for the ends of an open contour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ControlPoint {
+    pub x: f64,
+    pub y: f64,
+    pub ty: char,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Seg {
+    x: f64,
+    y: f64,
+    ty: char,
+    bend_th: f64,
+    ks: [f64; 4],
+    seg_ch: f64,
+    seg_th: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct BandRow {
+    a: [f64; 11],
+    al: [f64; 5],
+}
+
+// Gauss–Legendre nodes and weights on [-1, 1].
+const GAUSS_LEGENDRE: [(f64, f64); 4] = [
+    (-0.861_136_311_594_052_6, 0.347_854_845_137_453_8),
+    (-0.339_981_043_584_856_3, 0.652_145_154_862_546_1),
+    (0.339_981_043_584_856_3, 0.652_145_154_862_546_1),
+    (0.861_136_311_594_052_6, 0.347_854_845_137_453_8),
+];
+const QUADRATURE_INTERVALS: usize = 16;
+
+/// The endpoint, relative to the start, of a spiral of unit length centred on the origin with
+/// θ(s) = k0·s + k1·s²/2 + k2·s³/6 + k3·s⁴/24 for s in -½..½.
+fn integrate_spiro(ks: &[f64; 4]) -> (f64, f64) {
+    let h = 1. / QUADRATURE_INTERVALS as f64;
+    let (mut x, mut y) = (0., 0.);
+    for i in 0..QUADRATURE_INTERVALS {
+        let mid = -0.5 + (i as f64 + 0.5) * h;
+        for (node, weight) in GAUSS_LEGENDRE {
+            let s = mid + node * h / 2.;
+            let th = s * (ks[0] + s * (ks[1] / 2. + s * (ks[2] / 6. + s * ks[3] / 24.)));
+            x += weight * th.cos();
+            y += weight * th.sin();
+        }
+    }
+    (x * h / 2., y * h / 2.)
+}
+
+fn compute_ends(ks: &[f64; 4], seg_ch: f64) -> [[f64; 4]; 2] {
+    let (x, y) = integrate_spiro(ks);
+    let ch = x.hypot(y);
+    let th = y.atan2(x);
+    let l = ch / seg_ch;
+
+    let th_even = 0.5 * ks[0] + (1. / 48.) * ks[2];
+    let th_odd = 0.125 * ks[1] + (1. / 384.) * ks[3] - th;
+    let k0_even = l * (ks[0] + 0.125 * ks[2]);
+    let k0_odd = l * (0.5 * ks[1] + (1. / 48.) * ks[3]);
+    let l2 = l * l;
+    let k1_even = l2 * (ks[1] + 0.125 * ks[3]);
+    let k1_odd = l2 * 0.5 * ks[2];
+    let k2_even = l2 * l * ks[2];
+    let k2_odd = l2 * l * 0.5 * ks[3];
+
+    [
+        [
+            th_even - th_odd,
+            k0_even - k0_odd,
+            k1_even - k1_odd,
+            k2_even - k2_odd,
+        ],
+        [
+            th_even + th_odd,
+            k0_even + k0_odd,
+            k1_even + k1_odd,
+            k2_even + k2_odd,
+        ],
+    ]
+}
+
+/// The ends of `seg`, and their partial derivatives by each of its first `jinc` curvature terms.
+fn compute_pderivs(seg: &Seg, jinc: usize) -> ([[f64; 4]; 2], [[[f64; 4]; 2]; 4]) {
+    const DELTA: f64 = 1e-6;
+    let ends = compute_ends(&seg.ks, seg.seg_ch);
+    let mut derivs = [[[0.; 4]; 2]; 4];
+    for i in 0..jinc {
+        let mut try_ks = seg.ks;
+        try_ks[i] += DELTA;
+        let try_ends = compute_ends(&try_ks, seg.seg_ch);
+        for k in 0..2 {
+            for j in 0..4 {
+                derivs[j][k][i] = (try_ends[k][j] - ends[k][j]) / DELTA;
+            }
+        }
+    }
+    (ends, derivs)
+}
+
+fn mod_2pi(th: f64) -> f64 {
+    let u = th / (2. * PI);
+    2. * PI * (u - (u + 0.5).floor())
+}
+
+fn setup_path(src: &[ControlPoint]) -> Vec<Seg> {
+    let n = src.len();
+    let n_seg = if src[0].ty == '{' { n - 1 } else { n };
+    let mut r: Vec<Seg> = (0..=n_seg)
+        .map(|i| {
+            let p = src[i % n];
+            Seg {
+                x: p.x,
+                y: p.y,
+                ty: p.ty,
+                ..Seg::default()
+            }
+        })
+        .collect();
+
+    for i in 0..n_seg {
+        let dx = r[i + 1].x - r[i].x;
+        let dy = r[i + 1].y - r[i].y;
+        r[i].seg_ch = dx.hypot(dy);
+        r[i].seg_th = dy.atan2(dx);
+    }
+
+    let mut ilast = n_seg.saturating_sub(1);
+    for i in 0..n_seg {
+        r[i].bend_th = if matches!(r[i].ty, '{' | '}' | 'v') {
+            0.
+        } else {
+            mod_2pi(r[i].seg_th - r[ilast].seg_th)
+        };
+        ilast = i;
+    }
+    r
+}
+
+fn bandec11(m: &mut [BandRow], perm: &mut [usize], n: usize) {
+    // Pack the top triangle to the left.
+    for i in 0..5 {
+        for j in 0..i + 6 {
+            m[i].a[j] = m[i].a[j + 5 - i];
+        }
+        for j in i + 6..11 {
+            m[i].a[j] = 0.;
+        }
+    }
+    let mut l = 5;
+    for k in 0..n {
+        let mut pivot = k;
+        let mut pivot_val = m[k].a[0];
+        l = if l < n { l + 1 } else { n };
+        for j in k + 1..l {
+            if m[j].a[0].abs() > pivot_val.abs() {
+                pivot_val = m[j].a[0];
+                pivot = j;
+            }
+        }
+        perm[k] = pivot;
+        if pivot != k {
+            let row = m[k].a;
+            m[k].a = m[pivot].a;
+            m[pivot].a = row;
+        }
+        if pivot_val.abs() < 1e-12 {
+            pivot_val = 1e-12;
+        }
+        let pivot_scale = 1. / pivot_val;
+        for i in k + 1..l {
+            let x = m[i].a[0] * pivot_scale;
+            m[k].al[i - k - 1] = x;
+            for j in 1..11 {
+                m[i].a[j - 1] = m[i].a[j] - x * m[k].a[j];
+            }
+            m[i].a[10] = 0.;
+        }
+    }
+}
+
+fn banbks11(m: &[BandRow], perm: &[usize], v: &mut [f64], n: usize) {
+    // Forward substitution.
+    let mut l = 5;
+    for k in 0..n {
+        let i = perm[k];
+        if i != k {
+            v.swap(k, i);
+        }
+        if l < n {
+            l += 1;
+        }
+        for i in k + 1..l {
+            v[i] -= m[k].al[i - k - 1] * v[k];
+        }
+    }
+    // Back substitution.
+    let mut l = 1;
+    for i in (0..n).rev() {
+        let mut x = v[i];
+        for k in 1..l {
+            x -= m[i].a[k] * v[k + i];
+        }
+        v[i] = x / m[i].a[0];
+        if l < 11 {
+            l += 1;
+        }
+    }
+}
+
+/// How many curvature terms a segment between knots of these types has free.
+fn compute_jinc(ty0: char, ty1: char) -> usize {
+    if ty0 == 'o' || ty1 == 'o' || ty0 == ']' || ty1 == '[' {
+        4
+    } else if ty0 == 'c' && ty1 == 'c' {
+        2
+    } else if ((ty0 == '{' || ty0 == 'v' || ty0 == '[') && ty1 == 'c')
+        || (ty0 == 'c' && (ty1 == '}' || ty1 == 'v' || ty1 == ']'))
+    {
+        1
+    } else {
+        0
+    }
+}
+
+fn count_vec(s: &[Seg], nseg: usize) -> usize {
+    (0..nseg).map(|i| compute_jinc(s[i].ty, s[i + 1].ty)).sum()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_mat_line(
+    m: &mut [BandRow],
+    v: &mut [f64],
+    derivs: &[f64; 4],
+    x: f64,
+    y: f64,
+    j: isize,
+    jj: isize,
+    jinc: usize,
+    nmat: isize,
+) {
+    if jj < 0 {
+        return;
+    }
+    let joff = if nmat < 6 {
+        j + 5 - jj
+    } else if nmat == 6 {
+        2 + (j + 3 - jj + nmat) % nmat
+    } else {
+        (j + 5 - jj + nmat) % nmat
+    };
+    let jj = jj as usize;
+    v[jj] += x;
+    for k in 0..jinc {
+        m[jj].a[joff as usize + k] += y * derivs[k];
+    }
+}
+
+fn spiro_iter(
+    s: &mut [Seg],
+    m: &mut [BandRow],
+    perm: &mut [usize],
+    v: &mut [f64],
+    n: usize,
+) -> f64 {
+    let cyclic = s[0].ty != '{' && s[0].ty != 'v';
+    let nmat = count_vec(s, n);
+    let nmat_i = nmat as isize;
+
+    for i in 0..nmat {
+        v[i] = 0.;
+        m[i] = BandRow::default();
+    }
+
+    let mut j: isize = 0;
+    let mut jj: isize = match s[0].ty {
+        'o' => nmat_i - 2,
+        'c' => nmat_i - 1,
+        _ => 0,
+    };
+    for i in 0..n {
+        let (ty0, ty1) = (s[i].ty, s[i + 1].ty);
+        let jinc = compute_jinc(ty0, ty1);
+        let th = s[i].bend_th;
+        let (mut jthl, mut jk0l, mut jk1l, mut jk2l) = (-1, -1, -1, -1);
+        let (mut jthr, mut jk0r, mut jk1r, mut jk2r) = (-1, -1, -1, -1);
+
+        let (ends, derivs) = compute_pderivs(&s[i], jinc);
+
+        // Constraints crossing the left end of the segment.
+        if matches!(ty0, 'o' | 'c' | '[' | ']') {
+            jthl = jj;
+            jj += 1;
+            jj %= nmat_i;
+            jk0l = jj;
+            jj += 1;
+        }
+        if ty0 == 'o' {
+            jj %= nmat_i;
+            jk1l = jj;
+            jj += 1;
+            jk2l = jj;
+            jj += 1;
+        }
+
+        // Constraints on the left.
+        if matches!(ty0, '[' | 'v' | '{' | 'c') && jinc == 4 {
+            if ty0 != 'c' {
+                jk1l = jj;
+                jj += 1;
+            }
+            jk2l = jj;
+            jj += 1;
+        }
+
+        // Constraints on the right.
+        if matches!(ty1, ']' | 'v' | '}' | 'c') && jinc == 4 {
+            if ty1 != 'c' {
+                jk1r = jj;
+                jj += 1;
+            }
+            jk2r = jj;
+            jj += 1;
+        }
+
+        // Constraints crossing the right end of the segment.
+        if matches!(ty1, 'o' | 'c' | '[' | ']') {
+            jthr = jj;
+            jk0r = (jj + 1) % nmat_i;
+        }
+        if ty1 == 'o' {
+            jk1r = (jj + 2) % nmat_i;
+            jk2r = (jj + 3) % nmat_i;
+        }
+
+        add_mat_line(
+            m,
+            v,
+            &derivs[0][0],
+            th - ends[0][0],
+            1.,
+            j,
+            jthl,
+            jinc,
+            nmat_i,
+        );
+        add_mat_line(m, v, &derivs[1][0], ends[0][1], -1., j, jk0l, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[2][0], ends[0][2], -1., j, jk1l, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[3][0], ends[0][3], -1., j, jk2l, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[0][1], -ends[1][0], 1., j, jthr, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[1][1], -ends[1][1], 1., j, jk0r, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[2][1], -ends[1][2], 1., j, jk1r, jinc, nmat_i);
+        add_mat_line(m, v, &derivs[3][1], -ends[1][3], 1., j, jk2r, jinc, nmat_i);
+        if jthl >= 0 {
+            v[jthl as usize] = mod_2pi(v[jthl as usize]);
+        }
+        if jthr >= 0 {
+            v[jthr as usize] = mod_2pi(v[jthr as usize]);
+        }
+        j += jinc as isize;
+    }
+
+    let (n_invert, mut j) = if cyclic {
+        // Solve the cyclic system as three copies of itself, and take the middle one.
+        for i in 0..nmat {
+            m[nmat + i] = m[i];
+            m[2 * nmat + i] = m[i];
+            v[nmat + i] = v[i];
+            v[2 * nmat + i] = v[i];
+        }
+        (3 * nmat, nmat)
+    } else {
+        (nmat, 0)
+    };
+    bandec11(m, perm, n_invert);
+    banbks11(m, perm, v, n_invert);
+
+    let mut norm = 0.;
+    for i in 0..n {
+        let jinc = compute_jinc(s[i].ty, s[i + 1].ty);
+        for k in 0..jinc {
+            let dk = v[j];
+            j += 1;
+            s[i].ks[k] += dk;
+            norm += dk * dk;
+        }
+        s[i].ks[0] = 2. * mod_2pi(s[i].ks[0] / 2.);
+    }
+    norm
+}
+
+fn solve_spiro(s: &mut [Seg], nseg: usize) {
+    let nmat = count_vec(s, nseg);
+    if nmat == 0 {
+        return;
+    }
+    let mut n_alloc = nmat;
+    if s[0].ty != '{' && s[0].ty != 'v' {
+        n_alloc *= 3;
+    }
+    let n_alloc = n_alloc.max(5);
+    let mut m = vec![BandRow::default(); n_alloc];
+    let mut v = vec![0.; n_alloc];
+    let mut perm = vec![0; n_alloc];
+
+    for _ in 0..10 {
+        if spiro_iter(s, &mut m, &mut perm, &mut v, nseg) < 1e-12 {
+            break;
+        }
+    }
+}
+
+/// The control points of a cubic approximating the spiral `ks` from `p0` to `p1`.
+fn seg_to_cubic(ks: &[f64; 4], p0: (f64, f64), p1: (f64, f64)) -> [(f64, f64); 4] {
+    let bend = ks[0].abs() + (0.5 * ks[1]).abs() + (0.125 * ks[2]).abs() + (ks[3] / 48.).abs();
+    let (dx, dy) = (p1.0 - p0.0, p1.1 - p0.1);
+    if bend <= 1e-8 {
+        return [
+            p0,
+            (p0.0 + dx / 3., p0.1 + dy / 3.),
+            (p0.0 + dx * 2. / 3., p0.1 + dy * 2. / 3.),
+            p1,
+        ];
+    }
+
+    let seg_ch = dx.hypot(dy);
+    let seg_th = dy.atan2(dx);
+    let (x, y) = integrate_spiro(ks);
+    let ch = x.hypot(y);
+    let th = y.atan2(x);
+    let scale = seg_ch / ch;
+    let rot = seg_th - th;
+
+    let th_even = (1. / 384.) * ks[3] + (1. / 8.) * ks[1] + rot;
+    let th_odd = (1. / 48.) * ks[2] + 0.5 * ks[0];
+    let ul = (scale * (1. / 3.)) * (th_even - th_odd).cos();
+    let vl = (scale * (1. / 3.)) * (th_even - th_odd).sin();
+    let ur = (scale * (1. / 3.)) * (th_even + th_odd).cos();
+    let vr = (scale * (1. / 3.)) * (th_even + th_odd).sin();
+    [p0, (p0.0 + ul, p0.1 + vl), (p1.0 - ur, p1.1 - vr), p1]
+}
+
+/// Solves the spiro through `points` and returns one cubic per segment. The contour is open if the
+/// first point is a `{`, and closed otherwise.
+pub fn spiro_to_cubics(points: &[ControlPoint]) -> Vec<[(f64, f64); 4]> {
+    if points.len() < 2 {
+        return vec![];
+    }
+    let mut s = setup_path(points);
+    let nseg = if points[0].ty == '{' {
+        points.len() - 1
+    } else {
+        points.len()
+    };
+    solve_spiro(&mut s, nseg);
+    (0..nseg)
+        .map(|i| seg_to_cubic(&s[i].ks, (s[i].x, s[i].y), (s[i + 1].x, s[i + 1].y)))
+        .collect()
+}
+
+#[test]
+fn spiro_circle() {
+    // Four G2 knots on a circle solve to its quarter arcs.
+    let knots: Vec<ControlPoint> = (0..4)
+        .map(|i| {
+            let angle = i as f64 * PI / 2.;
+            ControlPoint {
+                x: 100. * angle.cos(),
+                y: 100. * angle.sin(),
+                ty: 'c',
+            }
+        })
+        .collect();
+    let cubics = spiro_to_cubics(&knots);
+    assert_eq!(cubics.len(), 4);
+    let [_, c1, c2, _] = cubics[0];
+    assert!((c1.0 - 100.).abs() < 1e-6 && (c1.1 - 100. * PI / 6.).abs() < 1e-6);
+    assert!((c2.0 - 100. * PI / 6.).abs() < 1e-6 && (c2.1 - 100.).abs() < 1e-6);
+}
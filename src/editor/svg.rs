@@ -0,0 +1,284 @@
+// Importing SVG files as contours. Paths, rects, circles, ellipses, lines, polylines and polygons
+// are read, with their `transform`s, from anywhere in the document. SVG is y-down, so the drawing
+// is flipped about its viewBox (or its own bounds), putting its bottom on the baseline.
+
+use flo_curves::BezierCurveFactory;
+use glifparser::glif::{contour::MFEKContourCommon as _, MFEKContour};
+use glifparser::MFEKPointData;
+use kurbo::{Affine, BezPath, Ellipse, PathEl, Point, Rect, Shape as _};
+use xmltree::{Element, XMLNode};
+use MFEKmath::{vec2, Bezier, Piecewise, Vector};
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::Editor;
+
+fn number(element: &Element, attribute: &str) -> f64 {
+    element
+        .attributes
+        .get(attribute)
+        .and_then(|n| n.trim().trim_end_matches("px").parse().ok())
+        .unwrap_or(0.)
+}
+
+fn numbers(list: &str) -> Vec<f64> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Parses an SVG `transform` attribute, like `translate(10 20) scale(2)`.
+fn parse_transform(transform: &str) -> Affine {
+    let mut affine = Affine::IDENTITY;
+    for part in transform.split(')') {
+        let (name, args) = match part.split_once('(') {
+            Some((name, args)) => (name.trim().trim_start_matches(','), numbers(args)),
+            None => continue,
+        };
+        let arg = |i: usize| args.get(i).copied();
+        let t = match name.trim() {
+            "matrix" if args.len() == 6 => Affine::new([args[0], args[1], args[2], args[3], args[4], args[5]]),
+            "translate" => Affine::translate((arg(0).unwrap_or(0.), arg(1).unwrap_or(0.))),
+            "scale" => {
+                let sx = arg(0).unwrap_or(1.);
+                Affine::scale_non_uniform(sx, arg(1).unwrap_or(sx))
+            }
+            "rotate" => {
+                let rotation = Affine::rotate(arg(0).unwrap_or(0.).to_radians());
+                match (arg(1), arg(2)) {
+                    (Some(cx), Some(cy)) => {
+                        Affine::translate((cx, cy)) * rotation * Affine::translate((-cx, -cy))
+                    }
+                    _ => rotation,
+                }
+            }
+            "skewX" => Affine::new([1., 0., arg(0).unwrap_or(0.).to_radians().tan(), 1., 0., 0.]),
+            "skewY" => Affine::new([1., arg(0).unwrap_or(0.).to_radians().tan(), 0., 1., 0., 0.]),
+            other => {
+                log::warn!("Ignored unknown SVG transform {:?}", other);
+                Affine::IDENTITY
+            }
+        };
+        affine = affine * t;
+    }
+    affine
+}
+
+fn points_path(points: &str, close: bool) -> BezPath {
+    let coords = numbers(points);
+    let mut path = BezPath::new();
+    for (i, xy) in coords.chunks_exact(2).enumerate() {
+        if i == 0 {
+            path.move_to((xy[0], xy[1]));
+        } else {
+            path.line_to((xy[0], xy[1]));
+        }
+    }
+    if close {
+        path.close_path();
+    }
+    path
+}
+
+fn element_path(element: &Element) -> Option<BezPath> {
+    let n = |a| number(element, a);
+    match element.name.as_str() {
+        "path" => {
+            let d = element.attributes.get("d")?;
+            BezPath::from_svg(d)
+                .map_err(|e| log::warn!("Ignored SVG path that failed to parse: {:?}", e))
+                .ok()
+        }
+        "rect" => Some(Rect::new(n("x"), n("y"), n("x") + n("width"), n("y") + n("height")).to_path(0.1)),
+        "circle" => Some(Ellipse::new((n("cx"), n("cy")), (n("r"), n("r")), 0.).to_path(0.1)),
+        "ellipse" => Some(Ellipse::new((n("cx"), n("cy")), (n("rx"), n("ry")), 0.).to_path(0.1)),
+        "line" => {
+            let mut path = BezPath::new();
+            path.move_to((n("x1"), n("y1")));
+            path.line_to((n("x2"), n("y2")));
+            Some(path)
+        }
+        "polyline" => Some(points_path(element.attributes.get("points")?, false)),
+        "polygon" => Some(points_path(element.attributes.get("points")?, true)),
+        _ => None,
+    }
+}
+
+// Collects the shapes under `element`, in document coordinates.
+fn collect_paths(element: &Element, transform: Affine, paths: &mut Vec<BezPath>) {
+    // Things that are defined but not drawn.
+    if ["defs", "clipPath", "mask", "symbol", "metadata"].contains(&element.name.as_str()) {
+        return;
+    }
+    let transform = match element.attributes.get("transform") {
+        Some(t) => transform * parse_transform(t),
+        None => transform,
+    };
+    if let Some(mut path) = element_path(element) {
+        path.apply_affine(transform);
+        paths.push(path);
+    }
+    for child in element.children.iter() {
+        if let XMLNode::Element(child) = child {
+            collect_paths(child, transform, paths);
+        }
+    }
+}
+
+fn bezier(a: Point, b: Point, c: Point, d: Point) -> Bezier {
+    let v = |p: Point| -> Vector { vec2!(p.x, p.y) };
+    <Bezier as BezierCurveFactory>::from_points(v(a), (v(b), v(c)), v(d))
+}
+
+/// Splits a path into its subpaths as cubics. Lines and quadratics are raised to cubics.
+fn subpaths(path: &BezPath) -> Vec<Vec<Bezier>> {
+    let mut subpaths = vec![];
+    let mut current: Vec<Bezier> = vec![];
+    let (mut start, mut at) = (Point::ZERO, Point::ZERO);
+    let line = |from: Point, to: Point| bezier(from, from.lerp(to, 1. / 3.), from.lerp(to, 2. / 3.), to);
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                if !current.is_empty() {
+                    subpaths.push(std::mem::take(&mut current));
+                }
+                start = p;
+                at = p;
+            }
+            PathEl::LineTo(p) => {
+                current.push(line(at, p));
+                at = p;
+            }
+            PathEl::QuadTo(p1, p2) => {
+                current.push(bezier(at, at.lerp(p1, 2. / 3.), p2.lerp(p1, 2. / 3.), p2));
+                at = p2;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                current.push(bezier(at, p1, p2, p3));
+                at = p3;
+            }
+            PathEl::ClosePath => {
+                if at.distance(start) > f64::EPSILON {
+                    current.push(line(at, start));
+                }
+                if !current.is_empty() {
+                    subpaths.push(std::mem::take(&mut current));
+                }
+                at = start;
+            }
+        }
+    }
+    if !current.is_empty() {
+        subpaths.push(current);
+    }
+    subpaths
+}
+
+/// Reads the SVG at `path` as contours in glyph coordinates, centered on `center` if given.
+pub fn read_svg(
+    path: &Path,
+    center: Option<(f32, f32)>,
+) -> Result<Vec<MFEKContour<MFEKPointData>>, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let root = Element::parse(data.as_slice()).map_err(|e| e.to_string())?;
+
+    let mut paths = vec![];
+    collect_paths(&root, Affine::IDENTITY, &mut paths);
+    if paths.is_empty() {
+        return Err("found no shapes to import".to_string());
+    }
+
+    let height = match root.attributes.get("viewBox").map(|vb| numbers(vb)) {
+        Some(vb) if vb.len() == 4 => vb[1] + vb[3],
+        _ => paths
+            .iter()
+            .map(|p| p.bounding_box().y1)
+            .fold(f64::NEG_INFINITY, f64::max),
+    };
+    let flip = Affine::new([1., 0., 0., -1., 0., height]);
+    for path in paths.iter_mut() {
+        path.apply_affine(flip);
+    }
+
+    if let Some((x, y)) = center {
+        let bounds = paths
+            .iter()
+            .map(|p| p.bounding_box())
+            .reduce(|a, b| a.union(b))
+            .unwrap();
+        let delta = Affine::translate((x as f64 - bounds.center().x, y as f64 - bounds.center().y));
+        for path in paths.iter_mut() {
+            path.apply_affine(delta);
+        }
+    }
+
+    Ok(paths
+        .iter()
+        .flat_map(subpaths)
+        .map(|beziers| Piecewise::new(beziers, None).to_contour().into())
+        .collect())
+}
+
+impl Editor {
+    /// Adds the shapes of the SVG at `path` to the active layer and selects them. If `position` is
+    /// given, they're centered on it.
+    pub fn import_svg(&mut self, path: &Path, position: Option<(f32, f32)>) -> Result<(), String> {
+        let contours = read_svg(path, position)?;
+
+        self.begin_modification("Import SVG.", false);
+        self.contour_idx = None;
+        self.point_idx = None;
+        self.selected.clear();
+        let layer = self.get_active_layer_mut();
+        let mut new_selected = HashSet::new();
+        for contour in contours {
+            let ci = layer.outline.len();
+            new_selected.extend((0..contour.len()).map(|pi| (ci, pi)));
+            layer.outline.push(contour);
+        }
+        self.selected.extend(new_selected);
+        self.end_modification();
+        Ok(())
+    }
+}
+
+#[test]
+fn transform_chains() {
+    let at = |transform: &str, x: f64, y: f64| {
+        let p = parse_transform(transform) * Point::new(x, y);
+        ((p.x * 1000.).round() / 1000., (p.y * 1000.).round() / 1000.)
+    };
+    // The rightmost transform applies first.
+    assert_eq!(at("translate(10 20) scale(2)", 1., 1.), (12., 22.));
+    assert_eq!(at("scale(2) translate(10 20)", 1., 1.), (22., 42.));
+    assert_eq!(at("translate(10,20),scale(2, 3)", 1., 1.), (12., 23.));
+    assert_eq!(at("rotate(90 5 5) translate(1)", 5., 5.), (5., 6.));
+    assert_eq!(at("matrix(1 0 0 1 3 4) translate(5)", 0., 0.), (8., 4.));
+    assert_eq!(at("", 7., 8.), (7., 8.));
+}
+
+#[test]
+fn svgs_are_flipped_about_their_viewbox() {
+    let dir = std::env::temp_dir().join(format!("MFEKglif-svg-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let rect = r#"<rect x="10" y="10" width="20" height="30"/>"#;
+    let y_range = |root: &str| {
+        let path = dir.join("import.svg");
+        let svg = format!(r#"<svg{}>{}</svg>"#, root, rect);
+        fs::write(&path, svg).unwrap();
+        let contours = read_svg(&path, None).unwrap();
+        let ys: Vec<f32> = contours[0].cubic().unwrap().iter().map(|p| p.y).collect();
+        let min = ys.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = ys.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        (min.round(), max.round())
+    };
+
+    assert_eq!(y_range(r#" viewBox="0 0 100 100""#), (60., 90.));
+    assert_eq!(y_range(r#" viewBox="0 -50 100 100""#), (10., 40.));
+    // Without a viewBox, the drawing's own bottom goes on the baseline.
+    assert_eq!(y_range(""), (0., 30.));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,94 @@
+// The glyphs beside the current one, read once from the glyphs directory and shared by everything
+// that has to look through all of them: the text preview's character map, the anchor tool's mark
+// previews and the anchor window's warnings. The filesystem watcher forgets the index whenever
+// another glyph changes, and it's read again the next time it's asked for. Users of the index can
+// tell it's been read again by comparing it with `Rc::ptr_eq`, and redo what they derive from it.
+
+use glifparser::{Glif, MFEKPointData};
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use super::Editor;
+
+#[derive(Debug, Default)]
+pub struct UfoIndex {
+    pub dir: Option<PathBuf>,
+    /// Every glyph of the directory that could be read, by name.
+    pub glyphs: BTreeMap<String, Glif<MFEKPointData>>,
+    /// The name of the glyph encoding each character.
+    pub cmap: HashMap<char, String>,
+}
+
+impl UfoIndex {
+    /// Reads every glyph listed in the contents.plist of the glyphs directory `dir`.
+    pub fn read(dir: &Path) -> Self {
+        let contents: HashMap<String, String> = match plist::from_file(dir.join("contents.plist")) {
+            Ok(contents) => contents,
+            Err(e) => {
+                log::warn!("Can't read the glyphs beside this one: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let mut glyphs = BTreeMap::new();
+        let mut cmap = HashMap::new();
+        for (name, filename) in contents {
+            let glif: Glif<MFEKPointData> =
+                match glifparser::read_from_filename(&dir.join(filename)) {
+                    Ok(glif) => glif,
+                    Err(e) => {
+                        log::warn!("Failed to read glyph {}: {:?}", name, e);
+                        continue;
+                    }
+                };
+            for c in glif.unicode.iter() {
+                cmap.insert(*c, name.clone());
+            }
+            glyphs.insert(name, glif);
+        }
+
+        UfoIndex {
+            dir: Some(dir.to_path_buf()),
+            glyphs,
+            cmap,
+        }
+    }
+
+    /// The glyphs other than the one named `current`.
+    pub fn others<'a>(
+        &'a self,
+        current: &'a str,
+    ) -> impl Iterator<Item = &'a Glif<MFEKPointData>> + 'a {
+        self.glyphs
+            .iter()
+            .filter(move |(name, _)| name.as_str() != current)
+            .map(|(_, glif)| glif)
+    }
+}
+
+impl Editor {
+    /// The index of the glyphs beside the current one, read now if it hasn't been since it was last
+    /// invalidated or the current glyph moved to another directory.
+    pub fn ufo_index(&self) -> Rc<UfoIndex> {
+        let dir = self.glyphs_dir();
+        let mut index = self.ufo_index.borrow_mut();
+        match index.as_ref() {
+            Some(index) if index.dir == dir => Rc::clone(index),
+            _ => {
+                let read = Rc::new(match &dir {
+                    Some(dir) => UfoIndex::read(dir),
+                    None => UfoIndex::default(),
+                });
+                *index = Some(Rc::clone(&read));
+                read
+            }
+        }
+    }
+
+    /// Forgets the index of the glyphs beside the current one, so it's read again when next needed.
+    pub fn invalidate_ufo_index(&self) {
+        self.ufo_index.replace(None);
+    }
+}
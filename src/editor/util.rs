@@ -7,17 +7,37 @@ use flo_curves::{
     bezier::{solve_curve_for_t_along_axis, Curve as FloCurve},
     geo::Coord2,
 };
-use glifparser::WhichHandle;
+use glifparser::glif::Lib;
+use glifparser::glif::MFEKContour;
+use glifparser::{MFEKGlif, MFEKPointData, Point, WhichHandle};
 use glifrenderer::constants::{POINT_RADIUS, POINT_STROKE_THICKNESS};
+use plist::Dictionary;
 use skia_safe::Contains;
 use skia_safe::Point as SkPoint;
 use skia_safe::Rect as SkRect;
+use MFEKmath::mfek::ResolveCubic;
 use MFEKmath::subdivide::Subdivide;
 use MFEKmath::{Bezier, Piecewise};
 
+use super::spiro::{is_spiro, solved};
 use super::Editor;
 use glifparser::glif::mfek::contour::MFEKContourCommon;
 
+/// The glyph's lib as a plist, made empty if the glyph has none, for storing MFEK's own keys in.
+/// Logs an error and returns `None` if the lib is of some other kind.
+pub fn glyph_lib_mut(glyph: &mut MFEKGlif<MFEKPointData>) -> Option<&mut Dictionary> {
+    if let Lib::None = glyph.lib {
+        glyph.lib = Lib::Plist(Dictionary::new());
+    }
+    match &mut glyph.lib {
+        Lib::Plist(lib) => Some(lib),
+        _ => {
+            log::error!("Can't write to the glyph lib, it isn't a plist");
+            None
+        }
+    }
+}
+
 //TODO: Move to tool utility file
 #[derive(PartialEq, Clone, Copy)]
 pub enum SelectPointInfo {
@@ -169,7 +189,27 @@ pub fn nearest_point_on_curve(
         let mut seg_idx = None;
 
         for (cx, contour) in v.get_active_layer_ref().outline.iter().enumerate() {
-            if let Some(cubic_contour) = contour.cubic() {
+            // Quadratic and hyper contours are searched as the cubics they're drawn as, a hyper
+            // segment often being more than one of them. Spiro contours are searched as their
+            // solved curve, which has a cubic per knot.
+            let resolved;
+            let cubic_contour = match contour.cubic() {
+                Some(_) if is_spiro(contour) => {
+                    resolved = solved(contour);
+                    resolved.cubic()
+                }
+                Some(cubic_contour) => Some(cubic_contour),
+                None => {
+                    resolved = contour.to_cubic();
+                    resolved.cubic()
+                }
+            };
+            if let Some(cubic_contour) = cubic_contour {
+                let segments = if cubic_contour.len() == contour.len() {
+                    (0..cubic_contour.len()).collect()
+                } else {
+                    source_segments(contour, cubic_contour)
+                };
                 let pw: Piecewise<Bezier> = Piecewise::from(cubic_contour);
                 for (bx, mbezier) in pw.segs.iter().enumerate() {
                     use flo_curves::BezierCurveFactory as _;
@@ -197,7 +237,7 @@ pub fn nearest_point_on_curve(
                             current = Some(bezier.point_at_pos(ct));
                             t = Some(ct);
                             contour_idx = Some(cx);
-                            seg_idx = Some(bx);
+                            seg_idx = Some(segments[bx]);
 
                             let subdivisions = Subdivide::split(mbezier, ct);
                             if let Some(subdivisions) = subdivisions {
@@ -228,6 +268,27 @@ pub fn nearest_point_on_curve(
     }
 }
 
+/// The segment of `contour` each point of `cubic`, the contour resolved, lies on. Resolving keeps
+/// the contour's own points in order among the cubic's, so the segment changes at each of them.
+fn source_segments(
+    contour: &MFEKContour<MFEKPointData>,
+    cubic: &[Point<MFEKPointData>],
+) -> Vec<usize> {
+    let knots: Vec<(f32, f32)> = contour.inner().iter().map(|p| (p.x() as f32, p.y() as f32)).collect();
+    let mut segment = 0;
+    cubic
+        .iter()
+        .map(|p| {
+            if let Some(next) = knots.get(segment + 1) {
+                if (p.x - next.0).abs() < 0.01 && (p.y - next.1).abs() < 0.01 {
+                    segment += 1;
+                }
+            }
+            segment
+        })
+        .collect()
+}
+
 pub fn move_all_layers(v: &mut Editor, mut x: f32, mut y: f32) {
     v.with_glyph_mut(|glyph| {
         for li in 0..glyph.layers.len() {
@@ -17,17 +17,20 @@ use crate::editor::{
     events::{EditorEvent, IOEventType, MouseEventType},
     Editor,
 };
+use crate::render::text_preview;
 use crate::tools::zoom::{zoom_in_factor, zoom_out_factor};
 use crate::tools::ToolEnum;
 use crate::user_interface::mouse_input::MouseInfo;
 use crate::user_interface::Interface;
 
 use enum_iterator::IntoEnumIterator as _;
+use glifparser::glif::contour::MFEKContourCommon as _;
 use glifrenderer::toggles::{PointLabels, PreviewMode};
 use sdl2::event::{Event, WindowEvent};
 use sdl2::mouse::MouseButton;
 use tool_behaviors::pan::PanBehavior;
 use user_interface::egui_manager::EguiManager;
+use user_interface::gui;
 use user_interface::gui::window::WindowManager;
 
 #[macro_use]
@@ -154,6 +157,9 @@ fn main() {
                         Command::ToolPen => {
                             editor.set_tool(ToolEnum::Pen);
                         }
+                        Command::ToolPencil => {
+                            editor.set_tool(ToolEnum::Pencil);
+                        }
                         Command::ToolSelect => {
                             editor.set_tool(ToolEnum::Select);
                         }
@@ -163,6 +169,9 @@ fn main() {
                         Command::ToolDash => {
                             editor.set_tool(ToolEnum::Dash);
                         }
+                        Command::ToolNib => {
+                            editor.set_tool(ToolEnum::Nib);
+                        }
                         Command::ToolPAP => {
                             editor.set_tool(ToolEnum::PAP);
                         }
@@ -200,6 +209,9 @@ fn main() {
                                 !command_info.command_mod.shift
                             );
                         }
+                        Command::ToggleSnapping => {
+                            interface.snap.enabled = !interface.snap.enabled;
+                        }
                         /* TODO: Replace console!
                         Command::ToggleConsole => {
                             CONSOLE.with(|c| {
@@ -301,6 +313,31 @@ fn main() {
                         Command::ReverseContour => {
                             log::debug!("Tried to reverse contour outside Select tool");
                         }
+                        Command::HarmonizeSelection => {
+                            editor.harmonize_selection();
+                        }
+                        Command::SetStartPoint => {
+                            if let Some((ci, pi)) = editor.selected_point() {
+                                editor.set_start_point(ci, pi);
+                            }
+                        }
+                        Command::ToggleContourOpen => {
+                            if let Some((ci, pi)) = editor.selected_point() {
+                                if editor.get_active_layer_ref().outline[ci].is_open() {
+                                    editor.close_contour(ci);
+                                } else {
+                                    editor.open_contour_at(ci, pi);
+                                }
+                            }
+                        }
+                        Command::SplitContour => {
+                            if let Some((ci, pi)) = editor.selected_point() {
+                                editor.split_contour_at(ci, pi);
+                            }
+                        }
+                        Command::BuildComposite => {
+                            editor.build_composite_prompt(&mut interface);
+                        }
                         Command::SkiaDump => {
                             editor.skia_dump();
                         }
@@ -370,6 +407,19 @@ fn main() {
                         continue;
                     }
 
+                    if mouse_btn == MouseButton::Left {
+                        let factor = interface.viewport.factor;
+                        let pair = interface.text_preview.kern_pair_at(
+                            &editor,
+                            mouse_info.raw_position,
+                            factor,
+                        );
+                        if let Some((left, right, kern)) = pair {
+                            text_preview::kern_prompt(&mut interface, left, right, kern);
+                            continue;
+                        }
+                    }
+
                     editor.dispatch_editor_event(
                         &mut interface,
                         EditorEvent::MouseEvent {
@@ -413,6 +463,26 @@ fn main() {
                     );
                 }
 
+                Event::DropFile { filename, .. } => {
+                    // SDL doesn't say where a file was dropped, so it goes where the mouse was last.
+                    let position = Some(interface.mouse_info.position);
+                    let path = std::path::PathBuf::from(filename);
+                    let extension = path
+                        .extension()
+                        .map(|e| e.to_string_lossy().to_lowercase())
+                        .unwrap_or_default();
+                    match extension.as_str() {
+                        "glif" | "glifjson" => editor.load_glif(&mut interface, &path),
+                        "png" => drop(editor.add_image_to_active_layer(path, position)),
+                        "svg" => {
+                            if let Err(e) = editor.import_svg(&path, position) {
+                                gui::error!("Failed to import SVG {:?}: {}", path, e);
+                            }
+                        }
+                        _ => log::warn!("Don't know what to do with dropped file {:?}", path),
+                    }
+                }
+
                 Event::Window { win_event, .. } => match win_event {
                     WindowEvent::SizeChanged(x, y) | WindowEvent::Resized(x, y) => {
                         interface.viewport.winsize = (x as f32, y as f32);
@@ -0,0 +1,133 @@
+// Draws the background glyph set in `editor::background`, reading its outline only when the source
+// changes.
+
+use font_kit::family_name::FamilyName;
+use font_kit::hinting::HintingOptions;
+use font_kit::outline::{OutlineBuilder, PointFlags};
+use font_kit::properties::Properties;
+use font_kit::source::SystemSource;
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Path};
+
+use std::collections::HashMap;
+
+use super::text_preview::PreviewGlyph;
+use crate::editor::background::{Background, BackgroundSource};
+use crate::editor::Editor;
+
+fn units_per_em(v: &Editor) -> f32 {
+    v.font_dir()
+        .and_then(|ufo| {
+            let fontinfo: HashMap<String, plist::Value> =
+                plist::from_file(ufo.join("fontinfo.plist")).ok()?;
+            let upm = fontinfo.get("unitsPerEm")?;
+            upm.as_real()
+                .or_else(|| upm.as_signed_integer().map(|i| i as f64))
+        })
+        .map(|upm| upm as f32)
+        .unwrap_or(1000.)
+}
+
+/// The outline of `character` in the system font `family`, scaled to `upm`.
+fn system_glyph_path(family: &str, character: char, upm: f32) -> Result<Path, String> {
+    let font = SystemSource::new()
+        .select_best_match(&[FamilyName::Title(family.to_string())], &Properties::new())
+        .map_err(|e| format!("no system font {:?}: {:?}", family, e))?
+        .load()
+        .map_err(|e| format!("failed to load {:?}: {:?}", family, e))?;
+    let glyph = font
+        .glyph_for_char(character)
+        .ok_or_else(|| format!("{} has no glyph for {:?}", font.full_name(), character))?;
+    let mut builder = OutlineBuilder::new();
+    font.outline(glyph, HintingOptions::None, &mut builder)
+        .map_err(|e| format!("failed to read the outline of {:?}: {:?}", character, e))?;
+
+    let scale = upm / font.metrics().units_per_em as f32;
+    let mut path = Path::new();
+    for contour in builder.into_outline().contours {
+        let points: Vec<_> = contour
+            .positions
+            .iter()
+            .zip(contour.flags.iter())
+            .map(|(p, f)| ((p.x() * scale, p.y() * scale), *f))
+            .collect();
+        let mut points = points.into_iter();
+        match points.next() {
+            Some((start, _)) => path.move_to(start),
+            None => continue,
+        };
+        let mut controls = vec![];
+        for (point, flags) in points {
+            if flags.intersects(PointFlags::CONTROL_POINT_0 | PointFlags::CONTROL_POINT_1) {
+                controls.push(point);
+                continue;
+            }
+            match controls[..] {
+                [] => path.line_to(point),
+                [c] => path.quad_to(c, point),
+                [c0, c1] => path.cubic_to(c0, c1, point),
+                _ => return Err(format!("malformed outline for {:?}", character)),
+            };
+            controls.clear();
+        }
+        path.close();
+    }
+    Ok(path)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BackgroundRenderer {
+    // The outline last read, and what it was read from
+    cached: Option<(BackgroundSource, Option<Path>)>,
+}
+
+impl BackgroundRenderer {
+    /// Forgets the outline, so it's read again the next time it's drawn.
+    pub fn invalidate(&mut self) {
+        self.cached = None;
+    }
+
+    fn path(&mut self, v: &Editor, background: &Background) -> Option<&Path> {
+        if self.cached.as_ref().map(|(source, _)| source) != Some(&background.source) {
+            let path = match &background.source {
+                BackgroundSource::Ufo(name) => v
+                    .glyphs_dir()
+                    .ok_or_else(|| "this glyph wasn't loaded from a file".to_string())
+                    .and_then(|dir| PreviewGlyph::read(&dir, name))
+                    .map(|glyph| glyph.path),
+                BackgroundSource::System { family, character } => {
+                    system_glyph_path(family, *character, units_per_em(v))
+                }
+            };
+            let path = path
+                .map_err(|e| log::error!("Can't draw background glyph: {}", e))
+                .ok();
+            self.cached = Some((background.source.clone(), path));
+        }
+        self.cached.as_ref().and_then(|(_, path)| path.as_ref())
+    }
+
+    pub fn draw(&mut self, v: &Editor, canvas: &Canvas) {
+        let background = match v.background() {
+            Some(background) => background,
+            None => return,
+        };
+        let path = match self.path(v, &background) {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Fill);
+        paint.set_color(Color::from_argb(
+            (background.opacity.clamp(0., 1.) * 255.) as u8,
+            0,
+            0,
+            0,
+        ));
+        canvas.save();
+        canvas.translate(background.offset);
+        canvas.draw_path(path, &paint);
+        canvas.restore();
+    }
+}
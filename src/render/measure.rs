@@ -1,19 +1,90 @@
+use glifparser::outline::skia::ToSkiaPaths as _;
 use glifrenderer::constants::OUTLINE_STROKE_THICKNESS;
-use skia_safe::{Canvas, Font, Matrix, Paint, Path, Point, Rect};
+use skia_safe::{Canvas, Color, Font, Matrix, Paint, Path, Point, Rect};
 use MFEKmath::{vec2, Vector};
 
 use crate::editor::Editor;
-use crate::tools::cut::{Cut, Intersection};
+use crate::tools::cut::Cut;
 use crate::user_interface::Interface;
 use glifrenderer::constants::MEASURE_STROKE;
 
+// Crossings closer than this along the line, in units, are one crossing at a node between segments.
+const CROSSING_EPSILON: f64 = 0.01;
+
 pub struct Measure {
     pub start_point: Option<(f32, f32)>,
     pub end_point: Option<(f32, f32)>,
     pub enabled: bool,
+    /// Whether measure lines are pinned when drawn, to stay in place while editing.
+    pub pin: bool,
+    pub pinned: Vec<((f32, f32), (f32, f32))>,
+}
+
+/// Where the line from `start` to `end` crosses the visible outline, in order along the line.
+pub fn crossings(v: &Editor, start: (f32, f32), end: (f32, f32)) -> Vec<(f32, f32)> {
+    let preview = match v.preview.as_ref() {
+        Some(preview) => preview,
+        None => return vec![],
+    };
+    let mut crossings: Vec<_> = preview
+        .layers
+        .iter()
+        .filter(|layer| layer.visible)
+        .flat_map(|layer| Cut::find_intersections_in(&layer.outline, start, end))
+        .map(|i| (i.line_t, i.coords))
+        .collect();
+    crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let length = ((end.0 - start.0) as f64).hypot((end.1 - start.1) as f64);
+    crossings.dedup_by(|b, a| (b.0 - a.0).abs() * length < CROSSING_EPSILON);
+    crossings
+        .into_iter()
+        .map(|(_, (x, y))| (x as f32, y as f32))
+        .collect()
+}
+
+// The visible outline as it's filled, to tell stems from counters.
+fn ink(v: &Editor) -> Path {
+    let mut path = Path::new();
+    if let Some(preview) = v.preview.as_ref() {
+        for layer in preview.layers.iter().filter(|l| l.visible) {
+            path.add_path(&layer.outline.to_skia_paths(None).combined(), (0., 0.), None);
+        }
+    }
+    path
 }
 
 impl Measure {
+    pub fn pin_line(&mut self) {
+        if let (Some(start), Some(end)) = (self.start_point, self.end_point) {
+            if start != end {
+                self.pinned.push((start, end));
+            }
+        }
+    }
+
+    /// Draws the pinned lines with their crossings, which follow the outline as it's edited.
+    pub fn draw_pinned(&self, v: &Editor, canvas: &Canvas, factor: f32) {
+        if self.pinned.is_empty() {
+            return;
+        }
+        let ink = ink(v);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(skia_safe::PaintStyle::Stroke);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / factor));
+        paint.set_color(MEASURE_STROKE);
+
+        for (start, end) in self.pinned.iter() {
+            let mut path = Path::new();
+            path.move_to(*start);
+            path.line_to(*end);
+            canvas.draw_path(&path, &paint);
+            self.draw_crossings(canvas, &crossings(v, *start, *end), &ink, factor);
+        }
+    }
+
     pub fn draw_line(&self, i: &Interface, v: &Editor, canvas: &Canvas, factor: f32) {
         if !self.enabled {
             return;
@@ -94,21 +165,13 @@ impl Measure {
             factor,
         );
 
-        let intersections = Cut::find_intersections(&self.start_point, &self.end_point, v);
-        self.draw_intersection_distances(canvas, intersections.as_slice(), factor);
-
-        if intersections.len() > 1 {
-            let first_intersection = intersections.first().unwrap();
-            let last_intersection = intersections.last().unwrap();
-            let fcoords = (
-                first_intersection.coords.0 as f32,
-                first_intersection.coords.1 as f32,
-            );
-            let lcoords = (
-                last_intersection.coords.0 as f32,
-                last_intersection.coords.1 as f32,
-            );
-            self.draw_bracket(canvas, fcoords, lcoords, -20.0, factor);
+        let crossings = crossings(v, self.start_point.unwrap(), self.end_point.unwrap());
+        self.draw_crossings(canvas, &crossings, &ink(v), factor);
+
+        if crossings.len() > 1 {
+            let first = *crossings.first().unwrap();
+            let last = *crossings.last().unwrap();
+            self.draw_bracket(canvas, first, last, -20.0, factor);
         }
     }
 
@@ -120,9 +183,24 @@ impl Measure {
         angle: f32,
         size: f32,
         factor: f32,
+    ) {
+        self.draw_text_colored(canvas, text, position, angle, size, factor, Color::BLACK)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text_colored(
+        &self,
+        canvas: &Canvas,
+        text: &str,
+        position: (f32, f32),
+        angle: f32,
+        size: f32,
+        factor: f32,
+        color: Color,
     ) {
         let mut paint = Paint::default();
         paint.set_anti_alias(true);
+        paint.set_color(color);
 
         let mut font = Font::default();
         font.set_size(size / factor);
@@ -148,22 +226,19 @@ impl Measure {
         canvas.restore();
     }
 
-    fn draw_intersection_distances(
-        &self,
-        canvas: &Canvas,
-        intersections: &[Intersection],
-        factor: f32,
-    ) {
-        let mut paint = Paint::default();
-        paint.set_color(MEASURE_STROKE);
-        paint.set_anti_alias(true);
-
-        let mut font = Font::default();
-        font.set_size(16. / factor);
+    /// Marks each crossing, and labels the distance between successive ones: stems, where the
+    /// space between is inside the `ink`, and counters and sidebearings, where it isn't.
+    fn draw_crossings(&self, canvas: &Canvas, crossings: &[(f32, f32)], ink: &Path, factor: f32) {
+        let mut marker_paint = Paint::default();
+        marker_paint.set_color(MEASURE_STROKE);
+        marker_paint.set_anti_alias(true);
+        for crossing in crossings {
+            canvas.draw_circle(*crossing, 3. / factor, &marker_paint);
+        }
 
-        for window in intersections.windows(2) {
-            let start = Point::from((window[0].coords.0 as f32, window[0].coords.1 as f32));
-            let end = Point::from((window[1].coords.0 as f32, window[1].coords.1 as f32));
+        for window in crossings.windows(2) {
+            let start = Point::from(window[0]);
+            let end = Point::from(window[1]);
 
             // Calculate distance
             let dx = end.x - start.x;
@@ -172,12 +247,18 @@ impl Measure {
 
             // Find midpoint for the text
             let midpoint = ((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+            let is_stem = ink.contains(midpoint);
 
             let text_angle = dy.atan2(dx).to_degrees();
 
             // Draw the distance text
             let text = format!("{:.2}", distance);
-            self.draw_text(canvas, &text, midpoint, -text_angle, 8., factor)
+            let color = if is_stem {
+                MEASURE_STROKE
+            } else {
+                Color::from_rgb(128, 128, 128)
+            };
+            self.draw_text_colored(canvas, &text, midpoint, -text_angle, 10., factor, color)
         }
     }
 
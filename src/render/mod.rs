@@ -11,11 +11,14 @@ use glifrenderer::toggles::*;
 
 use skia_safe::{self as skia, Canvas};
 
+use crate::editor::images::{image_settings_in, remove_image};
 use crate::user_interface::PAPER_DRAW_GUIDELINES;
 use crate::{editor::Editor, user_interface::Interface};
 
+pub mod background;
 pub mod measure;
-mod speed_visualization;
+pub mod speed_visualization;
+pub mod text_preview;
 
 pub fn render_frame(v: &mut Editor, i: &mut Interface, canvas: &Canvas) {
     canvas.save();
@@ -32,12 +35,13 @@ pub fn render_frame(v: &mut Editor, i: &mut Interface, canvas: &Canvas) {
     i.viewport.redraw(canvas);
 
     let dropped = v.with_glyph(|glif| {
+        let settings = image_settings_in(&glif.lib);
         let mut dropped = vec![];
-        for layer in &glif.layers {
-            for (l_image, i_matrix) in &layer.images {
+        for (layer_idx, layer) in glif.layers.iter().enumerate() {
+            for (image_idx, (l_image, i_matrix)) in layer.images.iter().enumerate() {
                 if !v.images.contains_key(&l_image.filename) {
                     log::error!("Layer's image {} has gone out of scope and will be dropped! Did you save the glyph into a location without the image?", l_image.filename.to_string_lossy());
-                    dropped.push(l_image.filename.clone());
+                    dropped.push((layer_idx, image_idx));
                     continue;
                 }
                 let image = &v.images[&l_image.filename];
@@ -47,19 +51,19 @@ pub fn render_frame(v: &mut Editor, i: &mut Interface, canvas: &Canvas) {
                 let matrix = tm * i_matrix.to_skia_matrix() * matrix2;
                 canvas.set_matrix(&((matrix).into()));
                 //eprintln!("{:?}", Matrix::new_identity().set_rotate(45., None).to_affine());
-                canvas.draw_image(&image.img, (0., 0.), None);
+                let mut paint = skia::Paint::default();
+                let opacity = settings.get(&(layer_idx, image_idx)).copied().unwrap_or_default().opacity;
+                paint.set_alpha_f(opacity);
+                canvas.draw_image(&image.img, (0., 0.), Some(&paint));
                 canvas.restore();
             }
         }
         dropped
     });
 
-    for dropee in dropped {
-        v.with_glyph_mut_no_history(|glif| {
-            for layer in glif.layers.iter_mut() {
-                layer.images.retain(|(gi, _)| gi.filename != dropee);
-            }
-        });
+    // Later images first, so the indices of the rest still hold.
+    for (layer_idx, image_idx) in dropped.into_iter().rev() {
+        v.with_glyph_mut_no_history(|glif| remove_image(glif, layer_idx, image_idx));
     }
 
     if pm != PreviewMode::Paper || PAPER_DRAW_GUIDELINES {
@@ -91,13 +95,16 @@ pub fn render_frame(v: &mut Editor, i: &mut Interface, canvas: &Canvas) {
         }
     }
 
+    i.background.draw(v, canvas);
+    i.text_preview.draw(v, i.viewport.factor, canvas);
     glifrenderer::glyph::draw(canvas, v.preview.as_ref().unwrap(), &i.viewport, None);
 
-    speed_visualization::draw_velocity(v, i, canvas);
+    speed_visualization::draw_curvature_comb(v, i, canvas);
     v.with_glyph(|glyph| {
         // Cache component rects and flattened outline on MFEKGlif
         draw_components(glyph, &i.viewport, canvas);
     });
+    i.measure.draw_pinned(v, canvas, i.viewport.factor);
     i.measure.draw_line(i, v, canvas, i.viewport.factor);
 
     // TODO: let _path = glyph::draw_previews(v, canvas);
@@ -132,6 +139,7 @@ pub fn render_frame(v: &mut Editor, i: &mut Interface, canvas: &Canvas) {
             );
 
             v.dispatch_tool_draw(i, canvas);
+            i.snap.draw(&i.viewport, canvas);
         }
         PreviewMode::Paper => (),
     }
@@ -1,5 +1,7 @@
 use glifrenderer::toggles::PreviewMode;
-use skia_safe::{Canvas, Color, Paint};
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::point::MFEKPointCommon as _;
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Path, Rect};
 use MFEKmath::{
     mfek::ResolveCubic, ArcLengthParameterization, Bezier, Evaluate, Parameterization, Piecewise,
     Vector,
@@ -7,88 +9,271 @@ use MFEKmath::{
 
 use crate::{editor::Editor, get_contour, user_interface::Interface};
 
-pub fn draw_velocity(v: &Editor, i: &Interface, canvas: &Canvas) {
+/// Settings for the curvature comb overlay.
+#[derive(Clone, Debug)]
+pub struct CurvatureVis {
+    pub enabled: bool,
+    /// Length of a comb tooth per unit of curvature.
+    pub scale: f64,
+    /// Teeth drawn per segment.
+    pub density: u32,
+    /// Draw combs for every contour of the active layer instead of only the selected one.
+    pub all_contours: bool,
+    pub show_inflections: bool,
+    pub show_discontinuities: bool,
+}
+
+impl Default for CurvatureVis {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scale: 2000.,
+            density: 40,
+            all_contours: false,
+            show_inflections: true,
+            show_discontinuities: true,
+        }
+    }
+}
+
+// Relative difference in curvature across a smooth node above which we mark it.
+const DISCONTINUITY_TOLERANCE: f64 = 0.05;
+// Distance, in font units, within which a joint between cubics is taken to be a contour point.
+const NODE_TOLERANCE: f64 = 0.01;
+
+pub fn draw_curvature_comb(v: &Editor, i: &Interface, canvas: &Canvas) {
     if i.viewport.preview_mode == PreviewMode::Paper {
         return;
     };
-    if i.curvature_vis == false {
+    let settings = &i.curvature_vis;
+    if !settings.enabled {
         return;
     };
 
-    if let Some((cidx, _)) = v.selected_point() {
-        let selected_contour = &get_contour!(v.get_active_layer_ref(), cidx);
-
-        let scaling_factor: f64 = 200.;
-        let quality = 6.;
-
-        let piecewise: Piecewise<Bezier> = selected_contour.to_cubic().into();
-        let mut first = false;
-        for bez in piecewise.segs {
-            let arclen_param = ArcLengthParameterization::from(&bez, 1000);
-            let total_len = arclen_param.get_total_arclen();
-
-            let samples = (quality * total_len as f32 * i.viewport.factor) as u32;
-            let max_velocity_magnitude = 0.2;
-            let min_velocity_magnitude = 0.0;
-
-            // Function to map velocity magnitude to a color
-            let alpha = 32u8; // Semi-transparent
-            let map_velocity_to_color = |velocity_magnitude: f64| -> Color {
-                let normalized = (velocity_magnitude.abs() - min_velocity_magnitude)
-                    / (max_velocity_magnitude - min_velocity_magnitude);
-                let red = (normalized * 255.0) as u8;
-                let blue = ((1.0 - normalized) * 255.0) as u8;
-                Color::from_argb(alpha, red, 0, blue) // Gradient from blue to red
+    let layer = v.get_active_layer_ref();
+    let contours: Vec<usize> = if settings.all_contours {
+        (0..layer.outline.len()).collect()
+    } else if let Some((cidx, _)) = v.selected_point() {
+        vec![cidx]
+    } else {
+        vec![]
+    };
+
+    for cidx in contours {
+        let contour = &get_contour!(layer, cidx);
+        // Quadratic and hyper contours go through the same conversion used by the preview.
+        let piecewise: Piecewise<Bezier> = contour.to_cubic().into();
+        draw_comb(&piecewise.segs, settings, i.viewport.factor, canvas);
+
+        if settings.show_inflections {
+            for bez in &piecewise.segs {
+                for t in find_inflections(bez) {
+                    draw_marker(bez.at(t), false, i.viewport.factor, canvas);
+                }
+            }
+        }
+
+        if settings.show_discontinuities {
+            // Hyperbeziers are drawn with several cubics per segment, so only the joints that fall
+            // on one of the contour's own points are nodes.
+            let nodes: Vec<Vector> = contour
+                .inner()
+                .iter()
+                .map(|point| Vector {
+                    x: point.x() as f64,
+                    y: point.y() as f64,
+                })
+                .collect();
+            let is_node = |at: Vector| nodes.iter().any(|node| node.distance(at) < NODE_TOLERANCE);
+
+            let segs = &piecewise.segs;
+            let mut pairs: Vec<(usize, usize)> =
+                (1..segs.len()).map(|idx| (idx - 1, idx)).collect();
+            if contour.is_closed() && segs.len() > 1 {
+                pairs.push((segs.len() - 1, 0));
+            }
+            for (prev, next) in pairs {
+                if is_node(segs[next].w1) && curvature_discontinuous(&segs[prev], &segs[next]) {
+                    draw_marker(segs[next].w1, true, i.viewport.factor, canvas);
+                }
+            }
+        }
+    }
+}
+
+fn draw_comb(segs: &[Bezier], settings: &CurvatureVis, factor: f32, canvas: &Canvas) {
+    let max_velocity_magnitude = 0.2;
+    let min_velocity_magnitude = 0.0;
+
+    // Function to map velocity magnitude to a color
+    let alpha = 64u8; // Semi-transparent
+    let map_velocity_to_color = |velocity_magnitude: f64| -> Color {
+        let normalized = (velocity_magnitude.abs() - min_velocity_magnitude)
+            / (max_velocity_magnitude - min_velocity_magnitude);
+        let red = (normalized * 255.0) as u8;
+        let blue = ((1.0 - normalized) * 255.0) as u8;
+        Color::from_argb(alpha, red, 0, blue) // Gradient from blue to red
+    };
+
+    let mut normal_paint = Paint::default();
+    normal_paint.set_stroke_width(1.0 / factor);
+    normal_paint.set_anti_alias(true);
+
+    let mut outline_paint = Paint::default();
+    outline_paint.set_style(PaintStyle::Stroke);
+    outline_paint.set_color(Color::from_argb(160, 255, 0, 128));
+    outline_paint.set_stroke_width(1.0 / factor);
+    outline_paint.set_anti_alias(true);
+
+    let samples = settings.density.max(2);
+    for bez in segs {
+        let arclen_param = ArcLengthParameterization::from(bez, 1000);
+        let mut comb_outline = Path::new();
+
+        for sample in 0..=samples {
+            let t = arclen_param.parameterize(sample as f64 / samples as f64);
+            let (curvature, normal) = match curvature_and_normal(bez, t) {
+                Some(cn) => cn,
+                None => continue,
             };
 
-            let derivative = bezier_derivative(&bez);
-            // Second pass to draw the lines with gradient colors
-            let start = if first { 0 } else { 1 };
-            first = false;
-            for sample in start..=samples {
-                let t = sample as f64 / samples as f64;
-                let t = arclen_param.parameterize(t);
-
-                // Calculate the first and second derivatives
-                let first_derivative =
-                    eval_quadratic_bezier(derivative.0, derivative.1, derivative.2, t);
-                let second_derivative = eval_linear_bezier(
-                    bezier_second_derivative(&bez).0,
-                    bezier_second_derivative(&bez).1,
-                    t,
-                );
-
-                // Calculate the curvature
-                let curvature = (first_derivative.x * second_derivative.y
-                    - first_derivative.y * second_derivative.x)
-                    / (first_derivative.x.powi(2) + first_derivative.y.powi(2)).powf(1.5);
-
-                let scaled_curvature = curvature.signum() * curvature.abs().sqrt();
-                let clamped_curvature =
-                    scaled_curvature.signum() * scaled_curvature.abs().min(max_velocity_magnitude);
-                let normal = Vector {
-                    x: -first_derivative.y,
-                    y: first_derivative.x,
+            let clamped_curvature = curvature.signum()
+                * curvature.abs().sqrt().min(max_velocity_magnitude);
+
+            // Scale the normal by the curvature
+            let point_on_curve = bez.at(t);
+            let end = point_on_curve - normal * curvature * settings.scale;
+
+            normal_paint.set_color(map_velocity_to_color(clamped_curvature));
+            canvas.draw_line(
+                point_on_curve.to_skia_point(),
+                end.to_skia_point(),
+                &normal_paint,
+            );
+
+            if comb_outline.is_empty() {
+                comb_outline.move_to(end.to_skia_point());
+            } else {
+                comb_outline.line_to(end.to_skia_point());
+            }
+        }
+
+        canvas.draw_path(&comb_outline, &outline_paint);
+    }
+}
+
+// Circles mark inflections, squares mark curvature discontinuities at nodes.
+fn draw_marker(at: Vector, square: bool, factor: f32, canvas: &Canvas) {
+    let mut paint = Paint::default();
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_stroke_width(1.5 / factor);
+    paint.set_anti_alias(true);
+
+    let radius = 4. / factor;
+    let center = at.to_skia_point();
+    if square {
+        paint.set_color(Color::from_rgb(255, 128, 0));
+        canvas.draw_rect(
+            Rect::from_xywh(center.x - radius, center.y - radius, radius * 2., radius * 2.),
+            &paint,
+        );
+    } else {
+        paint.set_color(Color::from_rgb(0, 160, 0));
+        canvas.draw_circle(center, radius, &paint);
+    }
+}
+
+/// Signed curvature and unit normal of `bez` at `t`, or None where the curve is degenerate.
+fn curvature_and_normal(bez: &Bezier, t: f64) -> Option<(f64, Vector)> {
+    let derivative = bezier_derivative(bez);
+    let second = bezier_second_derivative(bez);
+
+    // Calculate the first and second derivatives
+    let first_derivative = eval_quadratic_bezier(derivative.0, derivative.1, derivative.2, t);
+    let second_derivative = eval_linear_bezier(second.0, second.1, t);
+
+    let speed_squared = first_derivative.x.powi(2) + first_derivative.y.powi(2);
+    if speed_squared < f64::EPSILON {
+        return None;
+    }
+
+    // Calculate the curvature
+    let curvature = (first_derivative.x * second_derivative.y
+        - first_derivative.y * second_derivative.x)
+        / speed_squared.powf(1.5);
+    let normal = Vector {
+        x: -first_derivative.y,
+        y: first_derivative.x,
+    }
+    .normalize();
+
+    Some((curvature, normal))
+}
+
+// Inflections are where the cross product of the first and second derivatives changes sign. We
+// look for sign changes on a coarse grid and then bisect.
+fn find_inflections(bez: &Bezier) -> Vec<f64> {
+    let derivative = bezier_derivative(bez);
+    let second = bezier_second_derivative(bez);
+    let cross = |t: f64| {
+        let d1 = eval_quadratic_bezier(derivative.0, derivative.1, derivative.2, t);
+        let d2 = eval_linear_bezier(second.0, second.1, t);
+        d1.x * d2.y - d1.y * d2.x
+    };
+
+    const STEPS: usize = 64;
+    let mut ret = vec![];
+    let mut prev_t = 0.;
+    let mut prev = cross(prev_t);
+    for step in 1..=STEPS {
+        let t = step as f64 / STEPS as f64;
+        let cur = cross(t);
+        if cur == 0. {
+            // An inflection right on a sample, as at the middle of a symmetric S-curve, is one if
+            // the samples either side of it differ in sign.
+            let next = cross((step + 1) as f64 / STEPS as f64);
+            if step < STEPS && prev != 0. && next != 0. && prev.signum() != next.signum() {
+                ret.push(t);
+            }
+        } else if prev != 0. && prev.signum() != cur.signum() {
+            let (mut lo, mut hi) = (prev_t, t);
+            for _ in 0..32 {
+                let mid = (lo + hi) / 2.;
+                if cross(mid).signum() == prev.signum() {
+                    lo = mid;
+                } else {
+                    hi = mid;
                 }
-                .normalize();
-
-                // Scale the normal by the curvature
-                let scaled_normal = -normal * scaled_curvature * scaling_factor;
-
-                let color = map_velocity_to_color(clamped_curvature);
-
-                // Draw the normal line
-                let point_on_curve = bez.at(t);
-                let start = point_on_curve;
-                let end = point_on_curve + scaled_normal;
-                let mut normal_paint = Paint::default();
-                normal_paint.set_color(color);
-                normal_paint.set_stroke_width(1.0 / i.viewport.factor);
-                normal_paint.set_anti_alias(true);
-                canvas.draw_line(start.to_skia_point(), end.to_skia_point(), &normal_paint);
+            }
+            let t = (lo + hi) / 2.;
+            // Inflections at the very ends belong to the node, not the segment.
+            if t > 1e-3 && t < 1. - 1e-3 {
+                ret.push(t);
             }
         }
+        prev_t = t;
+        prev = cur;
+    }
+    ret
+}
+
+// Only smooth (G1) nodes are considered; corners are discontinuous by design.
+fn curvature_discontinuous(prev: &Bezier, next: &Bezier) -> bool {
+    let (k_in, n_in) = match curvature_and_normal(prev, 1.) {
+        Some(kn) => kn,
+        None => return false,
+    };
+    let (k_out, n_out) = match curvature_and_normal(next, 0.) {
+        Some(kn) => kn,
+        None => return false,
+    };
+
+    if n_in.distance(n_out) > 0.01 {
+        return false;
     }
+
+    let largest = k_in.abs().max(k_out.abs());
+    largest > f64::EPSILON && (k_in - k_out).abs() / largest > DISCONTINUITY_TOLERANCE
 }
 
 // Function to calculate the derivative of a cubic Bézier curve
@@ -118,3 +303,24 @@ fn bezier_second_derivative(bez: &Bezier) -> (Vector, Vector) {
 fn eval_linear_bezier(p0: Vector, p1: Vector, t: f64) -> Vector {
     p0 * (1.0 - t as f64) + p1 * t as f64
 }
+
+#[test]
+fn inflection_of_a_symmetric_s_curve() {
+    use flo_curves::BezierCurveFactory;
+
+    let point = |x, y| Vector { x, y };
+    let s_curve = <Bezier as BezierCurveFactory>::from_points(
+        point(0., 0.),
+        (point(1., 1.), point(2., -1.)),
+        point(3., 0.),
+    );
+    // Its inflection lands exactly on a sample.
+    assert_eq!(find_inflections(&s_curve), vec![0.5]);
+
+    let arch = <Bezier as BezierCurveFactory>::from_points(
+        point(0., 0.),
+        (point(1., 1.), point(2., 1.)),
+        point(3., 0.),
+    );
+    assert!(find_inflections(&arch).is_empty());
+}
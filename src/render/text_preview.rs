@@ -0,0 +1,347 @@
+// A line of the UFO's other glyphs drawn beside the one being edited, for judging it in context.
+// The text is typed like `nn/current/oo`: plain characters are looked up by their Unicode value,
+// `/name` is a glyph by name, and `/current` is the glyph being edited, live as it changes. Pairs
+// are kerned from the parent UFO, and clicking the handle under a pair edits its kerning.
+
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::FlattenedGlif as _;
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Path};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path as FsPath, PathBuf};
+use std::rc::Rc;
+
+use crate::editor::composite::read_glyph;
+use crate::editor::kerning::Kerning;
+use crate::editor::Editor;
+use crate::user_interface::{InputPrompt, Interface};
+
+const CURRENT: &str = "current";
+const DEFAULT_TEXT: &str = "nn/current/oo";
+// Kerning handles sit this many pixels under the baseline.
+const KERN_HANDLE_DEPTH: f32 = 16.;
+const KERN_HANDLE_RADIUS: f32 = 4.;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PreviewItem {
+    Current,
+    Glyph(String),
+}
+
+/// A glyph of the preview text, placed on the baseline at `x`. `kern` is the kerning between it and
+/// the glyph before it.
+#[derive(Clone, Debug)]
+pub struct PlacedGlyph {
+    pub item: PreviewItem,
+    pub name: String,
+    pub x: f32,
+    pub kern: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct PreviewGlyph {
+    pub path: Path,
+    pub advance: f32,
+}
+
+impl PreviewGlyph {
+    pub fn read(dir: &FsPath, name: &str) -> Result<Self, String> {
+        let glif = read_glyph(dir, name)?;
+        let flattened = glif
+            .flattened(&mut None)
+            .map_err(|e| format!("failed to flatten {}: {:?}", name, e))?;
+        let path = flattened
+            .outline
+            .as_ref()
+            .map(|o| o.to_skia_paths(None).combined())
+            .unwrap_or_default();
+        Ok(PreviewGlyph {
+            path,
+            advance: glif.width.unwrap_or(0) as f32,
+        })
+    }
+}
+
+/// Splits preview text into glyphs. `cmap` maps characters to the glyph names encoding them.
+pub fn parse_preview_text(text: &str, cmap: &HashMap<char, String>) -> Vec<PreviewItem> {
+    let mut items = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '/' {
+            match cmap.get(&c) {
+                Some(name) => items.push(PreviewItem::Glyph(name.clone())),
+                None => log::debug!("No glyph in the UFO encodes {:?}", c),
+            }
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(&next) = chars.peek() {
+            if next == '/' || next.is_whitespace() {
+                break;
+            }
+            name.push(next);
+            chars.next();
+        }
+        // A single space or slash ends a name without being drawn, as in `/a.sc /b.sc` or
+        // `nn/current/oo`.
+        if matches!(chars.peek(), Some(' ') | Some('/')) && !name.is_empty() {
+            chars.next();
+        }
+
+        if name == CURRENT {
+            items.push(PreviewItem::Current);
+        } else if !name.is_empty() {
+            items.push(PreviewItem::Glyph(name));
+        } else if let Some(slash) = cmap.get(&'/') {
+            // `//` is the slash itself
+            if chars.peek() == Some(&'/') {
+                chars.next();
+            }
+            items.push(PreviewItem::Glyph(slash.clone()));
+        }
+    }
+    items
+}
+
+#[derive(Clone, Debug)]
+pub struct TextPreview {
+    pub enabled: bool,
+    pub text: String,
+    // Everything below is read from the glyphs directory `dir` when first needed.
+    dir: Option<PathBuf>,
+    glyphs: HashMap<String, Option<PreviewGlyph>>,
+    kerning: Option<Kerning>,
+    // Kerning just written by `kern_prompt`, to be used in place of `kerning` from the next draw on.
+    written_kerning: Rc<RefCell<Option<Kerning>>>,
+}
+
+impl Default for TextPreview {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            text: DEFAULT_TEXT.to_string(),
+            dir: None,
+            glyphs: HashMap::new(),
+            kerning: None,
+            written_kerning: Rc::default(),
+        }
+    }
+}
+
+impl TextPreview {
+    /// Forgets everything read from the UFO, so it's read again the next time it's drawn.
+    pub fn invalidate(&mut self) {
+        self.glyphs.clear();
+        self.kerning = None;
+    }
+
+    /// Forgets the UFO's kerning, e.g. after another program changed it.
+    pub fn invalidate_kerning(&mut self) {
+        self.kerning = None;
+    }
+
+    fn kerning(&mut self, v: &Editor) -> &Kerning {
+        if let Some(kerning) = self.written_kerning.take() {
+            self.kerning = Some(kerning);
+        }
+        self.kerning.get_or_insert_with(|| match v.font_dir() {
+            Some(ufo) => Kerning::read(&ufo).unwrap_or_else(|e| {
+                log::warn!("Can't kern preview: {}", e);
+                Kerning::default()
+            }),
+            None => Kerning::default(),
+        })
+    }
+
+    fn glyph(&mut self, name: &str) -> Option<&PreviewGlyph> {
+        let dir = self.dir.as_ref()?;
+        self.glyphs
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                PreviewGlyph::read(dir, name)
+                    .map_err(|e| log::warn!("Can't preview glyph: {}", e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// Lays the preview text out on the baseline, with the first `/current` at the origin. If the
+    /// text doesn't include the current glyph, it follows it.
+    pub fn layout(&mut self, v: &Editor) -> Vec<PlacedGlyph> {
+        let dir = v.glyphs_dir();
+        if dir != self.dir {
+            self.dir = dir;
+            self.invalidate();
+        }
+        if self.dir.is_none() {
+            return vec![];
+        }
+        let mut items = parse_preview_text(&self.text, &v.ufo_index().cmap);
+        if !items.contains(&PreviewItem::Current) {
+            items.insert(0, PreviewItem::Current);
+        }
+
+        let current_name = v.with_glyph(|g| g.name.clone());
+        let current_advance = v.metrics().advance;
+        let mut x = 0.;
+        let mut origin = None;
+        let mut ret: Vec<PlacedGlyph> = vec![];
+        for item in items {
+            let (name, advance) = match &item {
+                PreviewItem::Current => (current_name.clone(), current_advance),
+                PreviewItem::Glyph(name) => match self.glyph(name) {
+                    Some(glyph) => (name.clone(), glyph.advance),
+                    None => continue,
+                },
+            };
+            let kern = match ret.last() {
+                Some(previous) => self.kerning(v).value(&previous.name, &name) as f32,
+                None => 0.,
+            };
+            x += kern;
+            if item == PreviewItem::Current {
+                origin.get_or_insert(x);
+            }
+            ret.push(PlacedGlyph {
+                item,
+                name,
+                x,
+                kern,
+            });
+            x += advance;
+        }
+
+        let origin = origin.unwrap_or(0.);
+        for placed in ret.iter_mut() {
+            placed.x -= origin;
+        }
+        ret
+    }
+
+    fn kern_handle(x: f32, factor: f32) -> (f32, f32) {
+        (x, -KERN_HANDLE_DEPTH / factor)
+    }
+
+    /// The pair whose kerning handle is at `position`, as the glyph names and current kerning.
+    pub fn kern_pair_at(
+        &mut self,
+        v: &Editor,
+        position: (f32, f32),
+        factor: f32,
+    ) -> Option<(String, String, f32)> {
+        if !self.enabled || v.font_dir().is_none() {
+            return None;
+        }
+        let layout = self.layout(v);
+        layout.windows(2).find_map(|pair| {
+            let (hx, hy) = Self::kern_handle(pair[1].x, factor);
+            let distance = ((position.0 - hx).powi(2) + (position.1 - hy).powi(2)).sqrt();
+            (distance <= KERN_HANDLE_RADIUS * 2. / factor).then(|| {
+                (pair[0].name.clone(), pair[1].name.clone(), pair[1].kern)
+            })
+        })
+    }
+
+    pub fn draw(&mut self, v: &Editor, factor: f32, canvas: &Canvas) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Fill);
+        paint.set_color(Color::from_rgb(60, 60, 60));
+
+        // The edited glyph is drawn by the editor itself at the origin; other copies of it are
+        // drawn from the live preview.
+        let current = v.preview.as_ref().map(|preview| {
+            let mut path = Path::new();
+            for layer in preview.layers.iter().filter(|l| l.visible) {
+                path.add_path(&layer.outline.to_skia_paths(None).combined(), (0., 0.), None);
+            }
+            path
+        });
+
+        let mut handle_paint = Paint::default();
+        handle_paint.set_anti_alias(true);
+        let has_font = v.font_dir().is_some();
+
+        let layout = self.layout(v);
+        for (idx, placed) in layout.iter().enumerate() {
+            if has_font && idx > 0 {
+                handle_paint.set_color(if placed.kern == 0. {
+                    Color::from_rgb(150, 150, 150)
+                } else {
+                    Color::from_rgb(255, 100, 0)
+                });
+                let handle = Self::kern_handle(placed.x, factor);
+                canvas.draw_circle(handle, KERN_HANDLE_RADIUS / factor, &handle_paint);
+            }
+
+            let path = match &placed.item {
+                PreviewItem::Current if placed.x == 0. => continue,
+                PreviewItem::Current => match current.as_ref() {
+                    Some(path) => path.clone(),
+                    None => continue,
+                },
+                PreviewItem::Glyph(name) => match self.glyph(name) {
+                    Some(glyph) => glyph.path.clone(),
+                    None => continue,
+                },
+            };
+            canvas.save();
+            canvas.translate((placed.x, 0.));
+            canvas.draw_path(&path, &paint);
+            canvas.restore();
+        }
+    }
+}
+
+/// Asks for a new kerning value for a pair of the preview, and writes it to the UFO.
+pub fn kern_prompt(i: &mut Interface, left: String, right: String, kern: f32) {
+    let written_kerning = Rc::clone(&i.text_preview.written_kerning);
+    i.push_prompt(InputPrompt::Text {
+        label: format!("Kerning of {} {}:", left, right),
+        default: kern.to_string(),
+        func: Rc::new(move |v, value| {
+            let value: f64 = match value.trim().parse() {
+                Ok(value) => value,
+                Err(_) => {
+                    log::error!("Kerning must be a number, not {:?}", value);
+                    return;
+                }
+            };
+            match v.set_kern(&left, &right, value.round()) {
+                Ok(kerning) => *written_kerning.borrow_mut() = Some(kerning),
+                Err(e) => log::error!("Failed to kern {} {}: {}", left, right, e),
+            }
+        }),
+    });
+}
+
+#[test]
+fn parse_text_preview() {
+    let cmap: HashMap<char, String> =
+        [('n', "n".to_string()), ('o', "o".to_string())].into_iter().collect();
+    let n = PreviewItem::Glyph("n".to_string());
+    let o = PreviewItem::Glyph("o".to_string());
+    assert_eq!(
+        parse_preview_text("nn/current/oo", &cmap),
+        vec![n.clone(), n.clone(), PreviewItem::Current, o.clone(), o.clone()]
+    );
+    assert_eq!(parse_preview_text(DEFAULT_TEXT, &cmap).len(), 5);
+
+    let items = parse_preview_text("n/current /a.sc o/n", &cmap);
+    assert_eq!(
+        items,
+        vec![
+            n.clone(),
+            PreviewItem::Current,
+            PreviewItem::Glyph("a.sc".to_string()),
+            o,
+            n,
+        ]
+    );
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use kurbo::Affine;
+
+use super::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct MoveComponent {
+    selected_idx: usize,
+    mouse_info: MouseInfo,
+    start_matrix: Affine,
+}
+
+impl MoveComponent {
+    pub fn new(v: &Editor, selected_idx: usize, mouse_info: MouseInfo) -> Self {
+        MoveComponent {
+            selected_idx,
+            mouse_info,
+            start_matrix: v.component_matrix(selected_idx),
+        }
+    }
+
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        // The component's origin is what snaps, so it can be put on e.g. an anchor or the baseline.
+        let [.., ox, oy] = self.start_matrix.as_coeffs();
+        let origin = (ox as f32, oy as f32);
+        let (sx, sy) = self.mouse_info.position;
+        let (mx, my) = mouse_info.position;
+        let mut dragged_info = mouse_info;
+        dragged_info.raw_position = (origin.0 + mx - sx, origin.1 + my - sy);
+        dragged_info.position = dragged_info.raw_position;
+        let (x, y) = i.snap_position(v, dragged_info, Some(origin), &HashSet::new());
+
+        if !v.is_modifying() {
+            v.begin_modification("Move component.", false);
+        }
+
+        let translate = Affine::translate(((x - origin.0) as f64, (y - origin.1) as f64));
+        v.set_component_matrix_no_history(self.selected_idx, translate * self.start_matrix);
+    }
+
+    pub fn mouse_released(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button == self.mouse_info.button {
+            i.snap.indicators.clear();
+            if v.is_modifying() {
+                v.end_modification();
+            }
+            v.pop_behavior();
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl ToolBehavior for MoveComponent {
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Released => self.mouse_released(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                _ => (),
+            }
+        }
+    }
+}
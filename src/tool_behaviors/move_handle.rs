@@ -5,6 +5,7 @@ use glifparser::PointData;
 use glifparser::glif::contour::MFEKContourCommon;
 use glifparser::glif::point::MFEKPointCommon;
 use MFEKmath::polar::PolarCoordinates;
+use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
 pub struct MoveHandle {
@@ -30,17 +31,19 @@ impl MoveHandle {
         }
     }
 
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
-        if !v.is_modifying() {
-            v.begin_modification("Move handle.", false);
-        }
-
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         self.mouse_info.modifiers = mouse_info.modifiers;
 
-        let (x, y) = mouse_info.position;
-
         let (vci, vpi) = (v.contour_idx.unwrap(), v.point_idx.unwrap());
 
+        // Handles angle-snap around their own on-curve point.
+        let origin = get_point!(v.get_active_layer_ref(), vci, vpi).unwrap().get_position();
+        let (x, y) = i.snap_position(v, mouse_info, Some(origin), &HashSet::from([(vci, vpi)]));
+
+        if !v.is_modifying() {
+            v.begin_modification("Move handle.", false);
+        }
+
         {
             let layer = v.get_active_layer_mut();
             let point = get_point_mut!(layer, vci, vpi).unwrap();
@@ -86,8 +89,9 @@ impl MoveHandle {
         }
     }
 
-    pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+    pub fn mouse_released(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if mouse_info.button == self.mouse_info.button {
+            i.snap.indicators.clear();
             v.end_modification();
             v.pop_behavior();
         }
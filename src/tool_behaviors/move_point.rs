@@ -2,6 +2,7 @@ use crate::get_point_mut;
 
 use super::prelude::*;
 use glifparser::glif::{mfek::contour::MFEKContourCommon};
+use std::collections::HashSet;
 #[derive(Clone, Debug)]
 pub struct MovePoint {
     // we hold on to a clone of the mouse info when the behavior gets put on the stack
@@ -11,6 +12,9 @@ pub struct MovePoint {
 
     // should we move all selected points?
     move_selected: bool,
+
+    // where the dragged point was before the drag, for angle snapping
+    start_position: Option<(f32, f32)>,
 }
 
 impl MovePoint {
@@ -18,14 +22,23 @@ impl MovePoint {
         Self {
             mouse_info,
             move_selected,
+            start_position: None,
         }
     }
 
-    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
-        let x = mouse_info.position.0 as f32;
-        let y = mouse_info.position.1 as f32;
-
+    pub fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         let (vci, vpi) = v.selected_point().unwrap();
+        if self.start_position.is_none() {
+            self.start_position =
+                Some(get_point!(v.get_active_layer_ref(), vci, vpi).unwrap().get_position());
+        }
+
+        let mut exclude = HashSet::from([(vci, vpi)]);
+        if self.move_selected {
+            exclude.extend(v.selected.iter().copied());
+        }
+        let (x, y) = i.snap_position(v, mouse_info, self.start_position, &exclude);
+
         if !v.is_modifying() {
             v.begin_modification("Move point.", false);
         }
@@ -53,6 +66,7 @@ impl MovePoint {
 
     pub fn mouse_released(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if mouse_info.button == self.mouse_info.button {
+            i.snap.indicators.clear();
             let (vci, vpi) = (v.contour_idx.unwrap(), v.point_idx.unwrap());
 
             // we are going to check if we're dropping this point onto another and if this is the end, and that the
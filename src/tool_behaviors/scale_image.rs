@@ -0,0 +1,81 @@
+use kurbo::{Affine, Point};
+
+use super::prelude::*;
+
+/// Scales an image by one of its corners, keeping the opposite corner in place. Holding shift keeps
+/// the aspect ratio. Corners are given in the image's own coordinates.
+#[derive(Clone, Debug)]
+pub struct ScaleImage {
+    selected_idx: usize,
+    initial: Affine,
+    corner: (f64, f64),
+    anchor: (f64, f64),
+    mouse_info: MouseInfo,
+}
+
+impl ScaleImage {
+    pub fn new(
+        selected_idx: usize,
+        initial: Affine,
+        corner: (f64, f64),
+        anchor: (f64, f64),
+        mouse_info: MouseInfo,
+    ) -> Self {
+        ScaleImage {
+            selected_idx,
+            initial,
+            corner,
+            anchor,
+            mouse_info,
+        }
+    }
+
+    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        let (x, y) = mouse_info.position;
+        let local = self.initial.inverse() * Point::new(x as f64, y as f64);
+
+        let (cx, cy) = self.corner;
+        let (ax, ay) = self.anchor;
+        let mut sx = (local.x - ax) / (cx - ax);
+        let mut sy = (local.y - ay) / (cy - ay);
+        if mouse_info.modifiers.shift {
+            let s = if sx.abs() > sy.abs() { sx } else { sy };
+            sx = s.abs() * sx.signum();
+            sy = s.abs() * sy.signum();
+        }
+        // Flattening the image to nothing would lose its matrix for good.
+        if sx.abs() < 1e-3 || sy.abs() < 1e-3 || !sx.is_finite() || !sy.is_finite() {
+            return;
+        }
+
+        if !v.is_modifying() {
+            v.begin_modification("Scale image.", false);
+        }
+        v.get_active_layer_mut().images[self.selected_idx].1 = self.initial
+            * Affine::translate((ax, ay))
+            * Affine::scale_non_uniform(sx, sy)
+            * Affine::translate((-ax, -ay));
+    }
+
+    pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button == self.mouse_info.button {
+            if v.is_modifying() {
+                v.end_modification();
+            }
+            v.pop_behavior();
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl ToolBehavior for ScaleImage {
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Released => self.mouse_released(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                _ => (),
+            }
+        }
+    }
+}
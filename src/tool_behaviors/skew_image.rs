@@ -0,0 +1,76 @@
+use kurbo::{Affine, Point};
+
+use super::prelude::*;
+
+/// Skews an image by the middle of one of its edges, keeping the opposite edge in place. Points are
+/// given in the image's own coordinates.
+#[derive(Clone, Debug)]
+pub struct SkewImage {
+    selected_idx: usize,
+    initial: Affine,
+    handle: (f64, f64),
+    anchor: (f64, f64),
+    mouse_info: MouseInfo,
+}
+
+impl SkewImage {
+    pub fn new(
+        selected_idx: usize,
+        initial: Affine,
+        handle: (f64, f64),
+        anchor: (f64, f64),
+        mouse_info: MouseInfo,
+    ) -> Self {
+        SkewImage {
+            selected_idx,
+            initial,
+            handle,
+            anchor,
+            mouse_info,
+        }
+    }
+
+    pub fn mouse_moved(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        let (x, y) = mouse_info.position;
+        let local = self.initial.inverse() * Point::new(x as f64, y as f64);
+
+        let (hx, hy) = self.handle;
+        let (ax, ay) = self.anchor;
+        // Top and bottom edges slide sideways, left and right edges up and down.
+        let skew = if hy != ay {
+            Affine::new([1., 0., (local.x - hx) / (hy - ay), 1., 0., 0.])
+        } else {
+            Affine::new([1., (local.y - hy) / (hx - ax), 0., 1., 0., 0.])
+        };
+
+        if !v.is_modifying() {
+            v.begin_modification("Skew image.", false);
+        }
+        v.get_active_layer_mut().images[self.selected_idx].1 = self.initial
+            * Affine::translate((ax, ay))
+            * skew
+            * Affine::translate((-ax, -ay));
+    }
+
+    pub fn mouse_released(&mut self, v: &mut Editor, _i: &mut Interface, mouse_info: MouseInfo) {
+        if mouse_info.button == self.mouse_info.button {
+            if v.is_modifying() {
+                v.end_modification();
+            }
+            v.pop_behavior();
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl ToolBehavior for SkewImage {
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        if let EditorEvent::MouseEvent { mouse_info, event_type } = event {
+            match event_type {
+                MouseEventType::Released => self.mouse_released(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
+                _ => (),
+            }
+        }
+    }
+}
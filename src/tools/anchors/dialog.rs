@@ -23,10 +23,41 @@ impl Anchors {
         }
 
         if anchor != v.with_glyph(|glif| glif.anchors[anchor_idx].clone()) {
-            v.with_glyph_mut(|glif| {
-                glif.anchors[anchor_idx] = anchor.clone();
-            });
+            v.set_anchor(anchor_idx, anchor.clone());
         }
 
+        ui.collapsing("Mark Preview", |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.mark_preview.enabled, "Show marks");
+                if ui.button("Rescan").clicked() {
+                    self.mark_preview.rescan(v);
+                }
+            });
+
+            let name = match anchor.class.as_ref().filter(|n| !n.starts_with('_')) {
+                Some(name) => name.clone(),
+                None => return,
+            };
+            let attaching: Vec<String> = self
+                .mark_preview
+                .marks(v)
+                .iter()
+                .filter(|m| m.attachment(&name).is_some())
+                .map(|m| m.name.clone())
+                .collect();
+            if attaching.is_empty() {
+                ui.label(format!("No marks have a _{} anchor", name));
+            }
+            for mark in attaching {
+                let mut shown = !self.mark_preview.hidden.contains(&mark);
+                ui.checkbox(&mut shown, &mark);
+                if shown {
+                    self.mark_preview.hidden.remove(&mark);
+                } else {
+                    self.mark_preview.hidden.insert(mark);
+                }
+            }
+        });
+
     }
 }
\ No newline at end of file
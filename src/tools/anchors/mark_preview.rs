@@ -0,0 +1,120 @@
+// Ghosted previews of the UFO's mark glyphs, attached to the selected anchor. A mark attaches by its
+// anchor of the same name with a leading underscore, so `acutecomb` sits on `top` by its `_top`.
+
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::{FlattenedGlif as _, Glif, MFEKPointData};
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Path};
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::editor::ufo_index::UfoIndex;
+use crate::editor::Editor;
+
+#[derive(Clone, Debug)]
+pub struct MarkGlyph {
+    pub name: String,
+    path: Path,
+    anchors: HashMap<String, (f32, f32)>,
+}
+
+impl MarkGlyph {
+    // Flattens the glyph's components like MFEKGlif::flattened does for the open glyph.
+    fn from_glif(glif: &Glif<MFEKPointData>) -> Option<Self> {
+        let anchors: HashMap<_, _> = glif
+            .anchors
+            .iter()
+            .filter_map(|a| Some((a.class.clone()?, (a.x, a.y))))
+            .collect();
+        if !anchors.keys().any(|name| name.starts_with('_')) {
+            return None;
+        }
+
+        let flattened = glif
+            .flattened(&mut None)
+            .map_err(|e| log::warn!("Failed to flatten mark {}: {:?}", glif.name, e))
+            .ok()?;
+        let path = flattened
+            .outline
+            .as_ref()
+            .map(|o| o.to_skia_paths(None).combined())
+            .unwrap_or_default();
+
+        Some(MarkGlyph {
+            name: glif.name.clone(),
+            path,
+            anchors,
+        })
+    }
+
+    /// Where the mark attaches to an anchor named `anchor`, if it can.
+    pub fn attachment(&self, anchor: &str) -> Option<(f32, f32)> {
+        self.anchors.get(&format!("_{}", anchor)).copied()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MarkPreview {
+    pub enabled: bool,
+    // The marks among the glyphs of a UFO index, found when first needed after it's read.
+    marks: Option<(Rc<UfoIndex>, Vec<MarkGlyph>)>,
+    pub hidden: HashSet<String>,
+}
+
+impl Default for MarkPreview {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            marks: None,
+            hidden: HashSet::new(),
+        }
+    }
+}
+
+impl MarkPreview {
+    /// Reads the glyphs beside the current one again, and so the marks among them.
+    pub fn rescan(&mut self, v: &Editor) {
+        v.invalidate_ufo_index();
+        self.marks = None;
+    }
+
+    /// The mark glyphs beside the current glyph, found again whenever the UFO index is read again.
+    pub fn marks(&mut self, v: &Editor) -> &[MarkGlyph] {
+        let index = v.ufo_index();
+        match &self.marks {
+            Some((scanned, _)) if Rc::ptr_eq(scanned, &index) => (),
+            _ => {
+                let current = v.with_glyph(|g| g.name.clone());
+                // The index is sorted by name, and so are the marks.
+                let marks = index
+                    .others(&current)
+                    .filter_map(MarkGlyph::from_glif)
+                    .collect();
+                self.marks = Some((index, marks));
+            }
+        }
+        &self.marks.as_ref().unwrap().1
+    }
+
+    /// Draws every shown mark that attaches to `anchor`, which sits at `at`.
+    pub fn draw(&mut self, v: &Editor, anchor: &str, at: (f32, f32), canvas: &Canvas) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Fill);
+        paint.set_color(Color::from_argb(64, 0, 0, 255));
+
+        let hidden = self.hidden.clone();
+        for mark in self.marks(v).iter().filter(|m| !hidden.contains(&m.name)) {
+            if let Some((mx, my)) = mark.attachment(anchor) {
+                canvas.save();
+                canvas.translate((at.0 - mx, at.1 - my));
+                canvas.draw_path(&mark.path, &paint);
+                canvas.restore();
+            }
+        }
+    }
+}
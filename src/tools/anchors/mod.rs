@@ -4,21 +4,30 @@ use crate::tool_behaviors::{zoom_scroll::ZoomScroll};
 use crate::user_interface::{InputPrompt, Interface};
 
 mod dialog;
+mod mark_preview;
+
+use self::mark_preview::MarkPreview;
 
 #[derive(Clone, Debug)]
 pub struct Anchors {
     /// Selected anchor
     anchor_idx: Option<usize>,
+    /// Where the selected anchor was when the current drag began
+    start_position: Option<(f32, f32)>,
 
     // for it's text dialog
-    edit_buf: HashMap<String, String>
+    edit_buf: HashMap<String, String>,
+
+    mark_preview: MarkPreview,
 }
 
 impl Anchors {
     pub fn new() -> Self {
         Anchors { 
             anchor_idx: None,
-            edit_buf: HashMap::new()
+            start_position: None,
+            edit_buf: HashMap::new(),
+            mark_preview: MarkPreview::default(),
         }
     }
 }
@@ -30,9 +39,9 @@ impl Tool for Anchors {
                 event_type,
                 mouse_info,
             } => match event_type {
-                MouseEventType::Moved => self.mouse_moved(v, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
                 MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
-                MouseEventType::Released => self.mouse_released(v),
+                MouseEventType::Released => self.mouse_released(v, i),
                 _ => {}
             },
             EditorEvent::ToolCommand {
@@ -62,15 +71,19 @@ impl Tool for Anchors {
 }
 
 impl Anchors {
-    fn draw_selected(&self, v: &Editor, i: &Interface, canvas: &Canvas) {
+    fn draw_selected(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
         if let Some(idx) = self.anchor_idx {
             let _scale = i.viewport.factor;
-            v.with_glyph(|glif| {
-                let anchor = &glif.anchors[idx];
-                let mut paint = Paint::default();
-                paint.set_color(SELECTED_ANCHOR_COLOR);
-                canvas.draw_circle((anchor.x, anchor.y), 2.5, &paint);
-            });
+            let anchor = v.with_glyph(|glif| glif.anchors[idx].clone());
+
+            // Mark anchors (`_top`) don't have marks attached to them.
+            if let Some(name) = anchor.class.as_ref().filter(|n| !n.starts_with('_')) {
+                self.mark_preview.draw(v, name, (anchor.x, anchor.y), canvas);
+            }
+
+            let mut paint = Paint::default();
+            paint.set_color(SELECTED_ANCHOR_COLOR);
+            canvas.draw_circle((anchor.x, anchor.y), 2.5, &paint);
         }
     }
 }
@@ -80,7 +93,7 @@ impl Anchors {
 use crate::editor::Editor;
 use glifparser::Anchor as GlifAnchor;
 use skia_safe::Paint;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 impl Anchors {
     fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
@@ -131,25 +144,29 @@ impl Anchors {
         });
     }
 
-    fn mouse_moved(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+    fn mouse_moved(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if let Some(idx) = self.anchor_idx {
             if !mouse_info.is_down {
                 return;
             }
 
             if !v.is_modifying() {
+                self.start_position = v.with_glyph(|glif| Some((glif.anchors[idx].x, glif.anchors[idx].y)));
                 v.begin_modification("Move anchor.", false);
             }
 
+            let position = i.snap_position(v, mouse_info, self.start_position, &HashSet::new());
             v.with_glyph_mut(|glif| {
                 // Anchors can't be non-integers in OT spec
-                glif.anchors[idx].x = f32::floor(mouse_info.position.0);
-                glif.anchors[idx].y = f32::floor(mouse_info.position.1);
+                glif.anchors[idx].x = f32::floor(position.0);
+                glif.anchors[idx].y = f32::floor(position.1);
             });
         }
     }
 
-    fn mouse_released(&mut self, v: &mut Editor) {
+    fn mouse_released(&mut self, v: &mut Editor, i: &mut Interface) {
+        i.snap.indicators.clear();
+        self.start_position = None;
         v.end_modification();
     }
 }
@@ -0,0 +1,47 @@
+use super::super::prelude::*;
+use super::Cut;
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::inner::MFEKContourInnerType;
+
+impl Cut {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut egui::Ui) {
+        ui.radio_value(&mut self.slice, false, "Split contours open");
+        ui.radio_value(&mut self.slice, true, "Slice shapes into closed pieces");
+        let has_hyper = v
+            .get_active_layer_ref()
+            .outline
+            .iter()
+            .any(|c| c.get_type() == MFEKContourInnerType::Hyper);
+        if has_hyper {
+            ui.label("Hyperbezier contours come out of a cut as cubic ones.");
+        }
+
+        ui.separator();
+
+        let names: Vec<String> = v
+            .with_glyph(|glyph| glyph.guidelines.clone())
+            .iter()
+            .chain(v.guidelines.iter())
+            .enumerate()
+            .map(|(idx, g)| g.name.clone().unwrap_or(format!("Unnamed {}", idx + 1)))
+            .collect();
+        if names.is_empty() {
+            ui.label("Add a guideline to cut along it.");
+            return;
+        }
+        self.guideline = self.guideline.min(names.len() - 1);
+
+        egui::ComboBox::from_label("Guideline")
+            .selected_text(names[self.guideline].as_str())
+            .show_ui(ui, |ui| {
+                for (idx, name) in names.iter().enumerate() {
+                    ui.selectable_value(&mut self.guideline, idx, name.as_str());
+                }
+            });
+        if ui.button("Cut along guideline").clicked() {
+            if let Some((start, end)) = Self::guideline_line(v, self.guideline) {
+                self.cut_along(v, start, end);
+            }
+        }
+    }
+}
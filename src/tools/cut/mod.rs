@@ -22,9 +22,20 @@ use crate::user_interface::Interface;
 
 use super::prelude::*;
 
+mod dialog;
+pub mod slice;
+
+// How far a guideline is followed either way from its point when cutting along it.
+const GUIDELINE_REACH: f32 = 100_000.;
+
 #[derive(Clone, Debug)]
 pub struct Cut {
     start_point: Option<(f32, f32)>,
+    /// Slice closed shapes into closed pieces, instead of splitting contours open at the cut.
+    slice: bool,
+    // Index into the glyph's guidelines followed by the global ones, as the Guidelines tool lists
+    // them.
+    guideline: usize,
 }
 
 pub struct Intersection {
@@ -50,11 +61,16 @@ impl Tool for Cut {
     fn draw(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
         Self::draw_line(i, v, canvas, &self.start_point, &Some(i.mouse_info.position));
     }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        self.tool_dialog(v, i, ui);
+        true
+    }
 }
 
 impl Cut {
     pub fn new() -> Self {
-        Self { start_point: None }
+        Self { start_point: None, slice: false, guideline: 0 }
     }
 
     fn mouse_pressed(&mut self, _v: &Editor, mouse_info: MouseInfo) {
@@ -62,17 +78,57 @@ impl Cut {
     }
 
     fn mouse_released(&mut self, v: &mut Editor, mouse_info: MouseInfo) {        
-        let intersections = Self::find_intersections(&self.start_point, &Some(mouse_info.position.into()), v);
-        Self::split_at_intersections(v, &intersections);
-        self.start_point = None;
+        if let Some(start_point) = self.start_point.take() {
+            self.cut_along(v, start_point, mouse_info.position);
+        }
+    }
+
+    /// Cuts the active layer along the line from `start_point` to `end_point`, in the current mode.
+    pub fn cut_along(&self, v: &mut Editor, start_point: (f32, f32), end_point: (f32, f32)) {
+        if !self.slice {
+            let intersections = Self::find_intersections(&Some(start_point), &Some(end_point), v);
+            Self::split_at_intersections(v, &intersections);
+            return;
+        }
+
+        match slice::slice_outline(&v.get_active_layer_ref().outline, start_point, end_point) {
+            Some(outline) => {
+                v.begin_modification("Slice", false);
+                v.contour_idx = None;
+                v.point_idx = None;
+                v.selected.clear();
+                v.get_active_layer_mut().outline = outline;
+                v.end_modification();
+            }
+            None => log::warn!("To slice a closed shape, the cut has to start and end outside it and cross it all the way through"),
+        }
+    }
+
+    /// The line through guideline `idx`, long enough to cross the whole glyph.
+    pub fn guideline_line(v: &Editor, idx: usize) -> Option<((f32, f32), (f32, f32))> {
+        let guideline = v
+            .with_glyph(|glyph| glyph.guidelines.clone())
+            .into_iter()
+            .chain(v.guidelines.iter().cloned())
+            .nth(idx)?;
+        // Negated, as wherever else guidelines are drawn or followed.
+        let angle = f32::from(-guideline.angle);
+        let (dx, dy) = (angle.to_radians().cos() * GUIDELINE_REACH, angle.to_radians().sin() * GUIDELINE_REACH);
+        let (x, y) = (guideline.at.x, guideline.at.y);
+        Some(((x - dx, y - dy), (x + dx, y + dy)))
     }
 
     pub fn find_intersections(start_point: &Option<(f32, f32)>, end_point: &Option<(f32, f32)>, v: &Editor) -> Vec<Intersection> {
-        let mut intersections = vec![];
-        if start_point.is_none() || end_point.is_none() { return intersections }
+        match (start_point, end_point) {
+            (Some(sp), Some(ep)) => Self::find_intersections_in(&v.get_active_layer_ref().outline, *sp, *ep),
+            _ => vec![],
+        }
+    }
 
-        let start_point = start_point.unwrap();
-        let end_point = end_point.unwrap();
+    /// Intersections of the line from `start_point` to `end_point` with `outline`, in order along
+    /// the line.
+    pub fn find_intersections_in(outline: &MFEKOutline<MFEKPointData>, start_point: (f32, f32), end_point: (f32, f32)) -> Vec<Intersection> {
+        let mut intersections = vec![];
 
         fn intersect_cubic(pw: Piecewise<Bezier>, ci: usize, intersections: &mut Vec<Intersection>, sp: (f32, f32), ep: (f32, f32)) {
             for (bi, bez) in pw.segs.iter().enumerate() {
@@ -103,7 +159,7 @@ impl Cut {
 
         // first we've gotta look at the active layer and find intersections between the line we drew
         // and the contours of the active layer
-        for (ci, c) in v.get_active_layer_ref().outline.iter().enumerate() {
+        for (ci, c) in outline.iter().enumerate() {
             match c.get_type() {
                 glifparser::glif::inner::MFEKContourInnerType::Cubic => {
                     let pw: Piecewise<Bezier> = Piecewise::from(c.cubic().unwrap());
@@ -160,9 +216,11 @@ impl Cut {
             let previous_operation = contour.operation.clone();
 
             match contour.get_type() {
-                glifparser::glif::inner::MFEKContourInnerType::Cubic => {
+                // Hyper contours are cut as the cubics they're drawn as, like in find_intersections_in.
+                glifparser::glif::inner::MFEKContourInnerType::Cubic | glifparser::glif::inner::MFEKContourInnerType::Hyper => {
                     let mut new_beziers = Vec::new();
-                    let pw: Piecewise<Bezier> = Piecewise::from(contour.cubic().unwrap());
+                    let cubic = contour.to_cubic();
+                    let pw: Piecewise<Bezier> = Piecewise::from(cubic.cubic().unwrap());
                 
                     for (bi, bez) in pw.segs.iter().enumerate() {
                         // Find intersections relevant for the current Bezier
@@ -249,7 +307,6 @@ impl Cut {
 
                     new_outline.push(mfek_contour);
                 },
-                glifparser::glif::inner::MFEKContourInnerType::Hyper => {},
             }
         }
 
@@ -0,0 +1,376 @@
+// Slicing closed shapes into closed pieces along a line. The line has to start and end outside the
+// ink, so that crossings paired in order along it each bound a stretch of the line inside the ink;
+// the pieces are found by following the outline from one crossing to the next and jumping across
+// the cut to the paired crossing, until coming back around. This treats holes the same as the
+// contours around them, so an `o` cut through the middle comes out as two closed `c` shapes. The
+// points of the outline between crossings are kept as they are, and the cut segments are split
+// exactly, so nothing the cut doesn't touch moves. Quadratic contours stay quadratic; hyperbezier
+// ones are cut as the cubics they're drawn as, and come out cubic.
+
+use flo_curves::BezierCurveFactory;
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::inner::{MFEKContourInner, MFEKContourInnerType};
+use glifparser::glif::point::{quad::QPoint, MFEKPointCommon as _};
+use glifparser::glif::{MFEKContour, MFEKOutline};
+use glifparser::outline::skia::ToSkiaPaths as _;
+use glifparser::{Contour, Handle, MFEKPointData, Point, PointType};
+use skia_safe::PathFillType;
+use MFEKmath::{Bezier, Evaluate as _, Piecewise, Vector};
+
+use std::collections::HashSet;
+
+use super::{Cut, Intersection};
+
+// Crossings closer than this are one crossing found twice, on both sides of an on-curve point.
+const SAME_CROSSING: f64 = 1e-3;
+
+fn lerp(a: Vector, b: Vector, t: f64) -> Vector {
+    a + (b - a) * t
+}
+
+fn from_points(a: Vector, b: Vector, c: Vector, d: Vector) -> Bezier {
+    <Bezier as BezierCurveFactory>::from_points(a, (b, c), d)
+}
+
+fn split(bez: &Bezier, t: f64) -> (Bezier, Bezier) {
+    let (ab, bc, cd) = (lerp(bez.w1, bez.w2, t), lerp(bez.w2, bez.w3, t), lerp(bez.w3, bez.w4, t));
+    let (abc, bcd) = (lerp(ab, bc, t), lerp(bc, cd, t));
+    let at = lerp(abc, bcd, t);
+    (from_points(bez.w1, ab, abc, at), from_points(at, bcd, cd, bez.w4))
+}
+
+/// The part of `bez` between `t0` and `t1`.
+fn segment(bez: &Bezier, t0: f64, t1: f64) -> Bezier {
+    let (before, _) = split(bez, t1);
+    if t1 <= f64::EPSILON {
+        return before;
+    }
+    let (_, between) = split(&before, (t0 / t1).clamp(0., 1.));
+    between
+}
+
+fn new_point(at: Vector) -> Point<MFEKPointData> {
+    Point::from_x_y_type((at.x as f32, at.y as f32), PointType::Curve)
+}
+
+/// The points of the closed contour `points` from position `from` around to position `to`, where a
+/// position is a segment index plus the time within that segment, at least 0 and less than 1.
+/// The points in between are the contour's own; only the segments the ends fall on are split, and
+/// straight ones stay straight. The ends have no handle outside the arc, as the cut goes straight
+/// on from them.
+fn arc(
+    points: &[Point<MFEKPointData>],
+    segs: &[Bezier],
+    from: (usize, f64),
+    to: (usize, f64),
+) -> Vec<Point<MFEKPointData>> {
+    let n = segs.len();
+    // The handles of segment `bi` between `t0` and `t1`, and where it ends.
+    let part = |bi: usize, t0: f64, t1: f64| {
+        let part = segment(&segs[bi], t0, t1);
+        let handles = if points[bi].a == Handle::Colocated
+            && points[(bi + 1) % n].b == Handle::Colocated
+        {
+            (Handle::Colocated, Handle::Colocated)
+        } else {
+            (
+                Handle::At(part.w2.x as f32, part.w2.y as f32),
+                Handle::At(part.w3.x as f32, part.w3.y as f32),
+            )
+        };
+        (handles, part.w4)
+    };
+
+    let (mut bi, mut t) = from;
+    let mut ret = vec![if t == 0. {
+        points[bi].clone()
+    } else {
+        new_point(segs[bi].at(t))
+    }];
+    ret[0].b = Handle::Colocated;
+    // Arcs that start and end on one segment either stay on it or go all the way around.
+    let wraps = to.0 < from.0 || (to.0 == from.0 && to.1 <= from.1);
+    let mut steps = if wraps { n + to.0 - from.0 } else { to.0 - from.0 };
+    loop {
+        if steps == 0 {
+            if to.1 > t {
+                let ((a, b), at) = part(bi, t, to.1);
+                ret.last_mut().unwrap().a = a;
+                let mut end = new_point(at);
+                end.b = b;
+                ret.push(end);
+            }
+            ret.last_mut().unwrap().a = Handle::Colocated;
+            return ret;
+        }
+        let mut next = points[(bi + 1) % n].clone();
+        if t > 0. {
+            let ((a, b), _) = part(bi, t, 1.);
+            ret.last_mut().unwrap().a = a;
+            next.b = b;
+        }
+        ret.push(next);
+        bi = (bi + 1) % n;
+        t = 0.;
+        steps -= 1;
+    }
+}
+
+// The quadratic contour whose segments are those of the cubic contour `points`, each of which is a
+// quadratic raised to a cubic (a line or a piece of a quadratic contour), so the conversion is exact.
+fn to_quad(points: Vec<Point<MFEKPointData>>) -> MFEKContour<MFEKPointData> {
+    let n = points.len();
+    let qpoints = (0..n)
+        .map(|idx| {
+            let (point, next) = (&points[idx], &points[(idx + 1) % n]);
+            let control = match (point.a, next.b) {
+                (Handle::At(x, y), _) => Handle::At(
+                    point.x + (x - point.x) * 1.5,
+                    point.y + (y - point.y) * 1.5,
+                ),
+                (Handle::Colocated, Handle::At(x, y)) => Handle::At(
+                    next.x + (x - next.x) * 1.5,
+                    next.y + (y - next.y) * 1.5,
+                ),
+                (Handle::Colocated, Handle::Colocated) => Handle::Colocated,
+            };
+            QPoint {
+                x: point.x,
+                y: point.y,
+                a: control,
+                name: point.name.clone(),
+                ptype: point.ptype,
+                smooth: point.get_smooth().unwrap_or(false),
+                data: None,
+            }
+        })
+        .collect();
+    let mut contour = MFEKContour::new(MFEKContourInner::Quad(qpoints), None);
+    contour.set_closed();
+    contour
+}
+
+/// Slices the closed contours of `outline` along the line from `start` to `end`. Returns `None`
+/// if the line starts or ends inside a closed contour, or doesn't cross any. Open contours, and
+/// closed ones the line misses, are kept as they are.
+pub fn slice_outline(
+    outline: &MFEKOutline<MFEKPointData>,
+    start: (f32, f32),
+    end: (f32, f32),
+) -> Option<MFEKOutline<MFEKPointData>> {
+    // Everything is cut as cubics, so quadratic and hyper contours take the same path.
+    let cubic: MFEKOutline<MFEKPointData> = outline.iter().map(|c| c.to_cubic()).collect();
+    let segs: Vec<Vec<Bezier>> = cubic
+        .iter()
+        .map(|c| Piecewise::<Bezier>::from(c.cubic().unwrap()).segs)
+        .collect();
+
+    let closed: MFEKOutline<MFEKPointData> =
+        cubic.iter().filter(|c| c.is_closed()).cloned().collect();
+    // Even-odd, as holes count as contours like any other here.
+    let mut ink = closed.to_skia_paths(None).combined();
+    ink.set_fill_type(PathFillType::EvenOdd);
+    if ink.contains(start) || ink.contains(end) {
+        return None;
+    }
+
+    let mut crossings: Vec<Intersection> = vec![];
+    for crossing in Cut::find_intersections_in(&cubic, start, end) {
+        if !outline[crossing.ci].is_closed() {
+            continue;
+        }
+        let seen = crossings.iter().any(|c| {
+            c.ci == crossing.ci
+                && (c.coords.0 - crossing.coords.0).hypot(c.coords.1 - crossing.coords.1)
+                    < SAME_CROSSING
+        });
+        if !seen {
+            crossings.push(crossing);
+        }
+    }
+    // `find_intersections_in` sorted them along the line.
+
+    let cut_contours: HashSet<usize> = crossings.iter().map(|c| c.ci).collect();
+    let odd = cut_contours
+        .iter()
+        .any(|ci| crossings.iter().filter(|c| c.ci == *ci).count() % 2 != 0);
+    if crossings.is_empty() || odd {
+        return None;
+    }
+
+    // For each crossing, the arc of its contour that starts there, and the crossing it ends at.
+    let mut arcs: Vec<Vec<Point<MFEKPointData>>> = vec![vec![]; crossings.len()];
+    let mut arc_end: Vec<usize> = vec![0; crossings.len()];
+    for &ci in cut_contours.iter() {
+        let n = segs[ci].len();
+        // A crossing at the very end of a segment is at the start of the next one.
+        let position = |c: &Intersection| {
+            if c.t >= 1. {
+                ((c.bi + 1) % n, 0.)
+            } else {
+                (c.bi, c.t.max(0.))
+            }
+        };
+        let mut on_contour: Vec<usize> = (0..crossings.len())
+            .filter(|&k| crossings[k].ci == ci)
+            .collect();
+        on_contour.sort_by(|&a, &b| {
+            position(&crossings[a])
+                .partial_cmp(&position(&crossings[b]))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let points = cubic[ci].cubic().unwrap();
+        for (idx, &k) in on_contour.iter().enumerate() {
+            let next = on_contour[(idx + 1) % on_contour.len()];
+            let (from, to) = (position(&crossings[k]), position(&crossings[next]));
+            arcs[k] = arc(points, &segs[ci], from, to);
+            arc_end[k] = next;
+        }
+    }
+
+    let mut pieces: Vec<Vec<Point<MFEKPointData>>> = vec![];
+    let mut visited = vec![false; crossings.len()];
+    for first in 0..crossings.len() {
+        if visited[first] {
+            continue;
+        }
+        // Each arc ends where the cut starts across to the next, so the cut is the straight
+        // segment between them.
+        let mut piece = vec![];
+        let mut at = first;
+        while !visited[at] {
+            visited[at] = true;
+            piece.extend(arcs[at].iter().cloned());
+            // Crossings pair up in order along the line: 0 with 1, 2 with 3…
+            at = arc_end[at] ^ 1;
+        }
+        if at != first {
+            // Only a self-intersecting outline can lead back somewhere else.
+            return None;
+        }
+        pieces.push(piece);
+    }
+
+    // Pieces made only of quadratics stay quadratic.
+    let all_quad = cut_contours
+        .iter()
+        .all(|ci| outline[*ci].get_type() == MFEKContourInnerType::Quad);
+
+    let mut ret: MFEKOutline<MFEKPointData> = outline
+        .iter()
+        .enumerate()
+        .filter(|(ci, _)| !cut_contours.contains(ci))
+        .map(|(_, c)| c.clone())
+        .collect();
+    for piece in pieces {
+        ret.push(if all_quad {
+            to_quad(piece)
+        } else {
+            let contour: Contour<MFEKPointData> = piece;
+            contour.into()
+        });
+    }
+    Some(ret)
+}
+
+#[cfg(test)]
+fn polygon(points: &[(f32, f32)]) -> MFEKContour<MFEKPointData> {
+    use glifparser::{Contour, Point, PointType};
+
+    // A contour without a move point is closed.
+    let contour: Contour<MFEKPointData> = points
+        .iter()
+        .map(|p| Point::from_x_y_type(*p, PointType::Line))
+        .collect();
+    contour.into()
+}
+
+#[cfg(test)]
+fn x_range(contour: &MFEKContour<MFEKPointData>) -> (f32, f32) {
+    let xs = contour.cubic().unwrap().iter().map(|p| p.x);
+    (
+        xs.clone().fold(f32::INFINITY, f32::min),
+        xs.fold(f32::NEG_INFINITY, f32::max),
+    )
+}
+
+#[test]
+fn slice_o_in_two() {
+    let outer = polygon(&[(0., 0.), (100., 0.), (100., 100.), (0., 100.)]);
+    let hole = polygon(&[(25., 25.), (25., 75.), (75., 75.), (75., 25.)]);
+    let pieces = slice_outline(&vec![outer, hole], (50., -10.), (50., 110.)).unwrap();
+    assert_eq!(pieces.len(), 2);
+
+    // The sides the cut crosses are split, and stay straight.
+    assert!(pieces.iter().all(|c| c
+        .cubic()
+        .unwrap()
+        .iter()
+        .all(|p| p.a == Handle::Colocated && p.b == Handle::Colocated)));
+
+    let mut ranges: Vec<(f32, f32)> = pieces.iter().map(x_range).collect();
+    ranges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert!((ranges[0].0 - 0.).abs() < 1e-3 && (ranges[0].1 - 50.).abs() < 1e-3);
+    assert!((ranges[1].0 - 50.).abs() < 1e-3 && (ranges[1].1 - 100.).abs() < 1e-3);
+}
+
+#[test]
+fn slice_u_across_its_arms() {
+    let u = polygon(&[
+        (0., 0.),
+        (100., 0.),
+        (100., 100.),
+        (70., 100.),
+        (70., 30.),
+        (30., 30.),
+        (30., 100.),
+        (0., 100.),
+    ]);
+    // Across both arms, the tops of the arms come off and the bottom stays in one piece.
+    let pieces = slice_outline(&vec![u.clone()], (-10., 50.), (110., 50.)).unwrap();
+    assert_eq!(pieces.len(), 3);
+
+    // From one arm to the other, the gap between them isn't ink and mustn't be bridged.
+    assert!(slice_outline(&vec![u.clone()], (15., 50.), (85., 50.)).is_none());
+    // Nor can a cut stop partway through.
+    assert!(slice_outline(&vec![u], (-10., 50.), (15., 50.)).is_none());
+}
+
+#[test]
+fn slice_quadratic_contour() {
+    // A round diamond with one off-curve point per side.
+    let corners = [(0., 50.), (50., 100.), (100., 50.), (50., 0.)];
+    let controls = [(0., 100.), (100., 100.), (100., 0.), (0., 0.)];
+    let qpoints = corners
+        .iter()
+        .zip(controls)
+        .map(|(&(x, y), (cx, cy))| QPoint {
+            x,
+            y,
+            a: Handle::At(cx, cy),
+            name: None,
+            ptype: PointType::QCurve,
+            smooth: true,
+            data: None,
+        })
+        .collect();
+    let mut diamond = MFEKContour::new(MFEKContourInner::Quad(qpoints), None);
+    diamond.set_closed();
+
+    let pieces = slice_outline(&vec![diamond], (30., -10.), (30., 110.)).unwrap();
+    assert_eq!(pieces.len(), 2);
+    assert!(pieces
+        .iter()
+        .all(|c| c.get_type() == MFEKContourInnerType::Quad));
+
+    // The corner the cut doesn't reach keeps its off-curve point.
+    let kept = pieces
+        .iter()
+        .flat_map(|c| c.quad().unwrap().iter())
+        .find(|p| (p.x, p.y) == (100., 50.))
+        .unwrap();
+    match kept.a {
+        Handle::At(x, y) => assert!((x - 100.).abs() < 1e-3 && y.abs() < 1e-3),
+        Handle::Colocated => panic!("the off-curve point was lost"),
+    }
+}
@@ -28,9 +28,35 @@ impl Image {
                 v.end_modification()
             }
 
+            let mut settings = v.image_settings(selected);
+            ui.add(egui::Slider::new(&mut settings.opacity, 0.0..=1.0).text("Opacity"));
+            ui.checkbox(&mut settings.locked, "Locked");
+            v.set_image_settings(selected, settings);
+
+            ui.collapsing("Autotrace", |ui| {
+                let settings = &mut self.autotrace;
+                ui.add(egui::Slider::new(&mut settings.threshold, 0..=255).text("Threshold"));
+                ui.horizontal(|ui| {
+                    ui.label("Corner angle");
+                    settings.corner_angle = egui_parsed_textfield(ui, "corner_angle", settings.corner_angle, &mut self.edit_buf);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Tolerance");
+                    settings.tolerance = egui_parsed_textfield(ui, "tolerance", settings.tolerance, &mut self.edit_buf);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Despeckle");
+                    settings.despeckle = egui_parsed_textfield(ui, "despeckle", settings.despeckle, &mut self.edit_buf);
+                });
+                if ui.button("Autotrace").clicked() {
+                    let count = v.autotrace_image(selected, settings);
+                    log::info!("Traced {} contours", count);
+                }
+            });
+
             if ui.button("Remove Image").clicked() {
                 v.begin_modification("Removed image.", false);
-                v.get_active_layer_mut().images.remove(selected);
+                v.remove_image(selected);
                 v.end_modification();
 
                 self.selected_idx = None;
@@ -2,10 +2,12 @@ use std::collections::HashMap;
 
 use super::prelude::*;
 use crate::command::Command;
+use crate::editor::autotrace::AutotraceSettings;
 use crate::editor::Editor;
 use crate::filedialog;
 use crate::tool_behaviors::{
-    move_image::MoveImage, rotate_image::RotateImage, zoom_scroll::ZoomScroll,
+    move_image::MoveImage, rotate_image::RotateImage, scale_image::ScaleImage,
+    skew_image::SkewImage, zoom_scroll::ZoomScroll,
 };
 use crate::user_interface::{Interface, MouseInfo};
 use glifparser::matrix::ToSkiaMatrix;
@@ -15,10 +17,12 @@ mod dialog;
 
 // The image tool is for adding and manipulating images on layers. With the image tool selected you can click an empty space in order
 // to add an image to the current layer. Clicking an image without holding any modifiers translates that image. Clicking while holding
-// ctrl rotates the image around it's center.
+// ctrl rotates the image around it's center. The selected image has handles on its corners to scale it and on its edges to
+// skew it. Locked images can be selected, but not changed.
 #[derive(Clone, Debug)]
 pub struct Image {
     selected_idx: Option<usize>,
+    autotrace: AutotraceSettings,
 
     edit_buf: HashMap<String, String>,
 }
@@ -29,7 +33,7 @@ impl Tool for Image {
         match event {
             EditorEvent::MouseEvent { mouse_info, event_type } => {
                 match event_type {
-                    MouseEventType::Pressed => self.mouse_pressed(v, mouse_info),
+                    MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
                     _ => (),
                 }
             },
@@ -41,17 +45,41 @@ impl Tool for Image {
         }
     }
 
-    fn draw(&mut self, v: &Editor, _i: &Interface, canvas: &Canvas) {
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
         if let Some(selected) = self.selected_idx {
+            let layer = v.get_active_layer_ref();
+            let matrix = layer.images[selected].1;
+            let (w, h) = self.get_image_size(v, selected);
+
+            // The image's outline follows any skew, so it's drawn through the image's matrix.
             let mut selected_path = Path::new();
-            let img_rect = self.get_image_rect(v, selected);
-            selected_path.add_rect(img_rect, None);
+            let corners = [(0., 0.), (w, 0.), (w, h), (0., h)];
+            for (idx, corner) in corners.iter().enumerate() {
+                let p = matrix * kurbo::Point::new(corner.0, corner.1);
+                if idx == 0 {
+                    selected_path.move_to((p.x as f32, p.y as f32));
+                } else {
+                    selected_path.line_to((p.x as f32, p.y as f32));
+                }
+            }
+            selected_path.close();
 
             let mut paint = Paint::default();
             paint.set_style(PaintStyle::Stroke);
             paint.set_color(SELECTED_STROKE);
-
+            paint.set_stroke_width(1. / i.viewport.factor);
             canvas.draw_path(&selected_path, &paint);
+
+            if v.image_settings(selected).locked {
+                return;
+            }
+            paint.set_style(PaintStyle::Fill);
+            for (handle, _, _) in self.get_handles(v, selected) {
+                let p = matrix * kurbo::Point::new(handle.0, handle.1);
+                let half = IMAGE_HANDLE_SIZE / 2. / i.viewport.factor;
+                let rect = SkRect::from_xywh(p.x as f32 - half, p.y as f32 - half, half * 2., half * 2.);
+                canvas.draw_rect(rect, &paint);
+            }
         }
     }
 
@@ -61,11 +89,21 @@ impl Tool for Image {
     }
 }
 
+// Size of the scale and skew handles, in pixels.
+const IMAGE_HANDLE_SIZE: f32 = 8.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImageHandle {
+    Scale,
+    Skew,
+}
+
 // Here you can implement behaviors for events.
 impl Image {
     pub fn new() -> Self {
         Self { 
             selected_idx: None,
+            autotrace: AutotraceSettings::default(),
             edit_buf: HashMap::new(),
         }
     }
@@ -110,27 +148,61 @@ impl Image {
         (point.x, point.y)
     }
 
-    fn get_image_rect(&self, v: &Editor, idx: usize) -> SkRect {
+    fn get_image_size(&self, v: &Editor, idx: usize) -> (f64, f64) {
         let layer = v.get_active_layer_ref();
         let image = &v.images[&layer.images[idx].0.filename];
+        (image.img.width() as f64, image.img.height() as f64)
+    }
 
-        let img_rect = image.img.bounds();
-
-        let origin_mat = layer.images[idx].1.to_skia_matrix();
+    // Handles of image `idx` in its own coordinates, each with the point that stays put when it's
+    // dragged: corners scale about the opposite corner, edge middles skew about the opposite edge.
+    fn get_handles(&self, v: &Editor, idx: usize) -> Vec<((f64, f64), (f64, f64), ImageHandle)> {
+        let (w, h) = self.get_image_size(v, idx);
+        let (cx, cy) = (w / 2., h / 2.);
+        vec![
+            ((0., 0.), (w, h), ImageHandle::Scale),
+            ((w, 0.), (0., h), ImageHandle::Scale),
+            ((w, h), (0., 0.), ImageHandle::Scale),
+            ((0., h), (w, 0.), ImageHandle::Scale),
+            ((cx, 0.), (cx, h), ImageHandle::Skew),
+            ((w, cy), (0., cy), ImageHandle::Skew),
+            ((cx, h), (cx, 0.), ImageHandle::Skew),
+            ((0., cy), (w, cy), ImageHandle::Skew),
+        ]
+    }
 
-        let f_rect = SkRect::new(
-            img_rect.left() as f32,
-            img_rect.top() as f32,
-            img_rect.right() as f32,
-            img_rect.bottom() as f32,
-        );
-        origin_mat.map_rect(f_rect).0
+    fn is_handle_clicked(&self, v: &Editor, i: &Interface, mouse_info: MouseInfo) -> Option<((f64, f64), (f64, f64), ImageHandle)> {
+        let selected = self.selected_idx?;
+        let matrix = v.get_active_layer_ref().images[selected].1;
+        let reach = (IMAGE_HANDLE_SIZE / i.viewport.factor) as f64;
+        let (mx, my) = mouse_info.position;
+        self.get_handles(v, selected).into_iter().find(|(handle, _, _)| {
+            let p = matrix * kurbo::Point::new(handle.0, handle.1);
+            (p.x - mx as f64).abs() <= reach && (p.y - my as f64).abs() <= reach
+        })
     }
 
-    fn mouse_pressed(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+    fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        let locked = |v: &Editor, idx: usize| v.image_settings(idx).locked;
+
+        if let Some((handle, anchor, kind)) = self.is_handle_clicked(v, i, mouse_info) {
+            let selected = self.selected_idx.unwrap();
+            if !locked(v, selected) {
+                let initial = v.get_active_layer_ref().images[selected].1;
+                match kind {
+                    ImageHandle::Scale => v.set_behavior(Box::new(ScaleImage::new(selected, initial, handle, anchor, mouse_info))),
+                    ImageHandle::Skew => v.set_behavior(Box::new(SkewImage::new(selected, initial, handle, anchor, mouse_info))),
+                }
+                return;
+            }
+        }
+
         // if we did click an image we're going to want to let the user translate/rotate that image
         if let Some(img_idx) = self.is_image_clicked(v, mouse_info) {
             self.selected_idx = Some(img_idx);
+            if locked(v, img_idx) {
+                return;
+            }
 
             if mouse_info.modifiers.ctrl {
                 let pivot = self.get_image_pivot(v, img_idx);
@@ -150,9 +222,7 @@ impl Image {
                 None => return,
             };
 
-            v.begin_modification("Add image to layer.", false);
-            v.add_image_to_active_layer(filename);
-            v.end_modification();
+            drop(v.add_image_to_active_layer(filename, None));
         }
     }
 
@@ -160,8 +230,7 @@ impl Image {
         if let Some(idx) = self.selected_idx {
             v.begin_modification("Delete image from layer.", false);
             self.selected_idx = None;
-            v.get_active_layer_mut().images.remove(idx);
-            v.recache_images();
+            v.remove_image(idx);
             v.end_modification();
         }
     }
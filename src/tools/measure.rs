@@ -16,7 +16,7 @@ impl Tool for Measure {
             match event_type {
                 MouseEventType::Pressed => self.mouse_pressed(i, mouse_info),
                 MouseEventType::Moved => self.mouse_moved(i, mouse_info),
-                MouseEventType::Released => self.mouse_released(),
+                MouseEventType::Released => self.mouse_released(i),
                 _ => (),
             }
         }
@@ -33,8 +33,11 @@ impl Measure {
         Self { dragging: false }
     }
 
-    fn mouse_released(&mut self) {
-        self.dragging = false
+    fn mouse_released(&mut self, i: &mut Interface) {
+        self.dragging = false;
+        if i.measure.pin {
+            i.measure.pin_line();
+        }
     }
 
     fn mouse_pressed(&mut self, i: &mut Interface, mouse_info: MouseInfo) {
@@ -4,8 +4,8 @@ pub_mod!("src/tools");
 use self::cut::Cut;
 use self::prelude::*;
 use self::{
-    anchors::Anchors, dash::Dash, guidelines::Guidelines, image::Image, measure::Measure, pan::Pan,
-    pap::PAP, pen::Pen, select::Select, shapes::Shapes, vws::VWS, zoom::Zoom,
+    anchors::Anchors, dash::Dash, guidelines::Guidelines, image::Image, measure::Measure, nib::Nib, pan::Pan,
+    pap::PAP, pen::Pen, pencil::Pencil, select::Select, shapes::Shapes, vws::VWS, zoom::Zoom,
 };
 
 use dyn_clone::DynClone;
@@ -43,6 +43,7 @@ use strum_macros::{AsRefStr, EnumString};
 pub enum ToolEnum {
     Pan,
     Pen,
+    Pencil,
     Cut,
     Select,
     Anchors,
@@ -51,6 +52,7 @@ pub enum ToolEnum {
     VWS,
     PAP,
     Dash,
+    Nib,
     Shapes,
     Image,
     Guidelines,
@@ -72,6 +74,7 @@ pub fn tool_enum_to_tool(tool: ToolEnum) -> Box<dyn Tool> {
     match tool {
         ToolEnum::Pan => Box::new(Pan::new()),
         ToolEnum::Pen => Box::new(Pen::new()),
+        ToolEnum::Pencil => Box::new(Pencil::new()),
         ToolEnum::Select => Box::new(Select::new()),
         ToolEnum::Zoom => Box::new(Zoom::new()),
         ToolEnum::Anchors => Box::new(Anchors::new()),
@@ -79,6 +82,7 @@ pub fn tool_enum_to_tool(tool: ToolEnum) -> Box<dyn Tool> {
         ToolEnum::Shapes => Box::new(Shapes::new()),
         ToolEnum::VWS => Box::new(VWS::new()),
         ToolEnum::Dash => Box::new(Dash::new()),
+        ToolEnum::Nib => Box::new(Nib::new()),
         ToolEnum::Image => Box::new(Image::new()),
         ToolEnum::PAP => Box::new(PAP::new()),
         ToolEnum::Guidelines => Box::new(Guidelines::new()),
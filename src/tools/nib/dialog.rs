@@ -0,0 +1,56 @@
+use super::super::prelude::*;
+use super::Nib;
+use crate::contour_operations::broadnib::{BroadNib, NibShape};
+
+impl Nib {
+    pub fn tool_dialog(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut egui::Ui) {
+        let contour_idx = match v.contour_idx {
+            Some(ci) if ci < v.get_active_layer_ref().outline.len() => ci,
+            _ => {
+                ui.label("No selection!");
+                return;
+            }
+        };
+        let original = match v.nib(contour_idx) {
+            Some(nib) => nib,
+            None => {
+                ui.label("Click a contour to sweep a nib along it.");
+                return;
+            }
+        };
+
+        let mut nib = original.clone();
+        match &mut nib.shape {
+            NibShape::Broad {
+                width,
+                height,
+                angle,
+            } => {
+                ui.label("Width");
+                ui.add(egui::Slider::new(width, 1.0..=300.));
+                ui.label("Height");
+                ui.add(egui::Slider::new(height, 0.0..=300.));
+                ui.label("Angle");
+                ui.add(egui::Slider::new(angle, -90.0..=90.));
+            }
+            NibShape::Polygon(points) => {
+                ui.label(format!(
+                    "Nib from a contour, with {} corners.",
+                    points.len()
+                ));
+                if ui.button("Use a broad nib").clicked() {
+                    nib = BroadNib::default();
+                }
+            }
+        }
+        ui.label("Shift-click another contour to use it as the nib.");
+
+        ui.separator();
+
+        if ui.button("Remove nib").clicked() {
+            v.set_nib(contour_idx, None);
+        } else if nib != original {
+            v.set_nib(contour_idx, Some(nib));
+        }
+    }
+}
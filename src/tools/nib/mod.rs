@@ -0,0 +1,94 @@
+mod dialog;
+
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::point::MFEKPointCommon as _;
+use skia_safe::Path;
+
+use super::prelude::*;
+use crate::contour_operations::broadnib::BroadNib;
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+
+#[derive(Clone, Debug, Default)]
+pub struct Nib {}
+
+impl Tool for Nib {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        self.draw_nib(v, i, canvas);
+    }
+
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        self.tool_dialog(v, i, ui);
+        true
+    }
+}
+
+impl Nib {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Clicking a contour makes it a skeleton; shift-clicking another contour while a skeleton is
+    // selected makes that contour its nib, taking it out of the outline.
+    fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        let (ci, pi) = match clicked_point_or_handle(v, i, mouse_info.raw_position, None) {
+            Some((ci, pi, _wh)) => (ci, pi),
+            None => return,
+        };
+
+        if let Some(skeleton) = v.contour_idx {
+            if mouse_info.modifiers.shift && skeleton != ci && v.nib(skeleton).is_some() {
+                v.use_contour_as_nib(skeleton, ci);
+                return;
+            }
+        }
+
+        v.contour_idx = Some(ci);
+        v.point_idx = Some(pi);
+        if v.nib(ci).is_none() {
+            v.set_nib(ci, Some(BroadNib::default()));
+        }
+    }
+
+    // Outlines the nib at each point of the selected skeleton.
+    fn draw_nib(&self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        let ci = match v.contour_idx {
+            Some(ci) if ci < v.get_active_layer_ref().outline.len() => ci,
+            _ => return,
+        };
+        let nib = match v.nib(ci) {
+            Some(nib) => nib.polygon(),
+            None => return,
+        };
+        if nib.is_empty() {
+            return;
+        }
+
+        let mut path = Path::new();
+        for point in v.get_active_layer_ref().outline[ci].inner().iter() {
+            let (x, y) = (point.x() as f64, point.y() as f64);
+            path.move_to(((x + nib[0].x) as f32, (y + nib[0].y) as f32));
+            for corner in nib[1..].iter() {
+                path.line_to(((x + corner.x) as f32, (y + corner.y) as f32));
+            }
+            path.close();
+        }
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(SELECTED_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+        canvas.draw_path(&path, &paint);
+    }
+}
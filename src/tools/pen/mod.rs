@@ -1,30 +1,44 @@
 mod modes;
 
-use self::modes::PenMode;
+use self::modes::{PenMode, PenModeType};
 use self::modes::cubic::CubicMode;
 use self::modes::hyper::HyperMode;
 use self::modes::quad::QuadMode;
+use self::modes::spiro::SpiroMode;
 
 use super::prelude::*;
 
 use crate::constants::FONT_SCALE_FACTOR;
 use crate::is_contour_open;
-use crate::tool_behaviors::{move_handle::MoveHandle, pan::PanBehavior, zoom_scroll::ZoomScroll};
+use crate::tool_behaviors::{move_handle::MoveHandle, move_point::MovePoint, pan::PanBehavior, zoom_scroll::ZoomScroll};
 use crate::user_interface::{Interface, gui::build_icon_button as build_button, gui::icons};
+use crate::editor::spiro::{self, SpiroPointType};
 use egui::{Align2, Color32};
-use glifparser::glif::inner::MFEKContourInnerType;
 use glifrenderer::points::draw_point;
 use glifparser::glif::mfek::contour::MFEKContourCommon;
+use glifparser::outline::skia::ToSkiaPaths as _;
+use MFEKmath::mfek::ResolveCubic as _;
 
 
 use editor::util::get_contour_start_or_end;
 
 #[derive(Clone, Debug)]
 pub struct Pen {
-    mode: MFEKContourInnerType,
+    mode: PenModeType,
     cubic: CubicMode,
     quad: QuadMode,
     hyper: HyperMode,
+    spiro: SpiroMode,
+    // What clicking would do to the selected contour, if it would continue or close it.
+    rubber_band: Option<RubberBand>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RubberBand {
+    /// Add a point here.
+    To((f32, f32)),
+    /// Close the contour.
+    Close,
 }
 
 
@@ -34,6 +48,7 @@ impl Tool for Pen {
         match event {
             EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
                 MouseEventType::Pressed => self.mouse_pressed(v, i, mouse_info),
+                MouseEventType::Moved => self.mouse_moved(v, i, mouse_info),
                 _ => (),
             }
             EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
@@ -50,45 +65,62 @@ impl Tool for Pen {
                 ui.horizontal(|ui| {
                     let cubic_button = build_button::<"icons">(v, ui, icons::PEN, "Cubic Bézier");
 
-                    let cubic_button = if self.mode == MFEKContourInnerType::Cubic {
+                    let cubic_button = if self.mode == PenModeType::Cubic {
                         cubic_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         cubic_button.button.unwrap()
                     };
 
                     if ui.add(cubic_button).clicked() {
-                        self.mode = MFEKContourInnerType::Cubic
+                        self.mode = PenModeType::Cubic
                     }
 
                     let quad_button = build_button::<"icons">(v, ui, icons::QUADRATIC, "Quadratic Bézier");
 
-                    let quad_button = if self.mode == MFEKContourInnerType::Quad {
+                    let quad_button = if self.mode == PenModeType::Quad {
                         quad_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         quad_button.button.unwrap()
                     };
 
                     if ui.add(quad_button).clicked() {
-                        self.mode = MFEKContourInnerType::Quad
+                        self.mode = PenModeType::Quad
                     }
 
                     let hyper_button = build_button::<"icons">(v, ui, icons::HYPERBEZIER, "Hyperbezier");
 
-                    let hyper_button = if self.mode == MFEKContourInnerType::Hyper {
+                    let hyper_button = if self.mode == PenModeType::Hyper {
                         hyper_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
                     } else {
                         hyper_button.button.unwrap()
                     };
         
                     if ui.add(hyper_button).clicked() {
-                        self.mode = MFEKContourInnerType::Hyper
+                        self.mode = PenModeType::Hyper
+                    }
+
+                    let spiro_button = build_button::<"icons">(v, ui, icons::SPIRO, "Spiro");
+
+                    let spiro_button = if self.mode == PenModeType::Spiro {
+                        spiro_button.button.unwrap().stroke(egui::Stroke::new(2., Color32::from_rgb(9, 82, 128)))
+                    } else {
+                        spiro_button.button.unwrap()
+                    };
+
+                    if ui.add(spiro_button).clicked() {
+                        self.mode = PenModeType::Spiro
                     }
                 });
+
+                if self.mode == PenModeType::Spiro {
+                    self.spiro_point_types(v, ui);
+                }
             });
     }
 
     fn draw(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
-        self.draw_merge_preview(v, i, canvas);
+        self.draw_rubber_band(v, i, canvas);
+        self.draw_end_hint(v, i, canvas);
         self.draw_nearest_point(v, i, canvas);
     }
 }
@@ -96,113 +128,177 @@ impl Tool for Pen {
 impl Pen {
     pub fn new() -> Self {
         Self {
-            mode: MFEKContourInnerType::Cubic,
+            mode: PenModeType::Cubic,
             cubic: CubicMode {  },
             quad: QuadMode {  },
             hyper: HyperMode { },
+            spiro: SpiroMode { point_type: SpiroPointType::G2 },
+            rubber_band: None,
         }
     }
 
-    fn mouse_pressed(&mut self, v: &mut Editor, i: &Interface, mouse_info: MouseInfo) {
+    fn mouse_pressed(&mut self, v: &mut Editor, i: &mut Interface, mouse_info: MouseInfo) {
         if mouse_info.button != MouseButton::Left {
             v.set_behavior(Box::new(PanBehavior::new(i.viewport.clone(), mouse_info)));
             return;
         };
 
+        self.rubber_band = None;
+
+        // Clicking the open end of a contour closes the selected contour, joins it to another one, or picks that end
+        // to carry on drawing from.
+        let end = end_action(v, i, mouse_info.raw_position);
+        if let Some((EndAction::Continue, (ci, pi))) = end {
+            v.set_selected(ci, pi);
+            return;
+        }
+
         v.begin_modification("Add point.", false);
 
-        // We check if we have a point selected and are clicking on the beginning of another contour.
-        // If that is the case we merge them
-        if let (Some(fcidx), Some(fpidx)) = (v.contour_idx, v.point_idx) {
-            // we've clicked a point?
-            if let Some((lcidx, lpidx, _)) = clicked_point_or_handle(v, i, mouse_info.raw_position, None)
+        if let Some((EndAction::Close, (ci, pi))) = end {
             {
-                // we have the end of one contour active and clicked the start of another?
-                let end_is_active =
-                    get_contour_start_or_end(v, fcidx, fpidx) == Some(SelectPointInfo::End);
-                let start_is_clicked =
-                    get_contour_start_or_end(v, lcidx, lpidx) == Some(SelectPointInfo::Start);
-
-                // make sure these contours are open
-                let selected_open = is_contour_open!(v.get_active_layer_ref(), fcidx);
-                let target_open = is_contour_open!(v.get_active_layer_ref(), lcidx);
-                if end_is_active && start_is_clicked && selected_open && target_open {
-                    // we're closing an open path
-                    if fcidx == lcidx {
-                        {
-                            let layer = v.get_active_layer_mut();
-                            let contour = get_contour_mut!(layer, fcidx);
-                            
-                            contour.set_closed();
-                        }
-                        
-                        v.set_selected(fcidx, 0);
-                        v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
-                        return;
-                    } else {
-                        // TODO: Handle contourtype specific merging here. If the contour types don't match we resolve both to cubic types.
-                        // If they do match we use a specific handler from the pen modes.
-                        v.merge_contours(lcidx, fcidx)
-                    }
-                }
+                let layer = v.get_active_layer_mut();
+                let contour = get_contour_mut!(layer, ci);
+
+                contour.set_closed();
             }
+
+            v.set_selected(ci, pi);
+            push_drag_behavior(v, mouse_info);
+            return;
+        }
+        if let Some((EndAction::Merge { start, end }, _)) = end {
+            // TODO: Handle contourtype specific merging here. If the contour types don't match we resolve both to cubic types.
+            // If they do match we use a specific handler from the pen modes.
+            v.merge_contours(start, end);
+            push_drag_behavior(v, mouse_info);
+            return;
         }
 
         // Next we check if our mouse is over an existing curve. If so we add a point to the curve.
         if let Some(info) = nearest_point_on_curve(v, i, mouse_info.position) {
-            self.get_mode(v).subdivide_curve(v, info)
-        }
-        // If we've got the end of a contour selected we'll continue drawing that contour.
-        else if can_add_point(v) {
-            self.get_mode(v).add_point(v, mouse_info);
+            self.get_mode_for_contour(v, info.contour_idx).subdivide_curve(v, info)
         } else {
-            // Lastly if we get here we create a new contour.
-            self.get_mode(v).new_contour(v, mouse_info)
+            // New points snap, angle-constrained from the point we're continuing from.
+            let origin = if can_add_point(v) {
+                v.selected_point().map(|(ci, pi)| {
+                    get_contour!(v.get_active_layer_ref(), ci).get_point(pi).unwrap().get_position()
+                })
+            } else {
+                None
+            };
+            let mut snapped_info = mouse_info;
+            snapped_info.position = i.snap_pen_position(v, mouse_info, origin);
+
+            // If we've got the end of a contour selected we'll continue drawing that contour.
+            if can_add_point(v) {
+                self.get_mode(v).add_point(v, snapped_info);
+            } else {
+                // Lastly if we get here we create a new contour.
+                self.get_mode(v).new_contour(v, snapped_info)
+            }
         }
 
-        // No matter how you move the point we want you to be able to manipulate it so we push the MoveHandle
-        // vehavior onto the editor's behavior stack.
-        v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
+        push_drag_behavior(v, mouse_info);
+    }
+
+    // Works out where clicking would put the next point, so it can be drawn.
+    fn mouse_moved(&mut self, v: &Editor, i: &mut Interface, mouse_info: MouseInfo) {
+        self.rubber_band = None;
+        i.snap.indicators.clear();
+        if !can_add_point(v) {
+            return;
+        }
+
+        match end_action(v, i, mouse_info.raw_position) {
+            Some((EndAction::Continue, _)) => return,
+            Some((EndAction::Close, _)) => {
+                self.rubber_band = Some(RubberBand::Close);
+                return;
+            }
+            Some((EndAction::Merge { .. }, (ci, pi))) => {
+                self.rubber_band = get_contour!(v.get_active_layer_ref(), ci).get_point(pi).map(|p| RubberBand::To(p.get_position()));
+                return;
+            }
+            None => (),
+        }
+        // Clicking on a curve puts the point on it instead.
+        if nearest_point_on_curve(v, i, mouse_info.position).is_some() {
+            return;
+        }
+
+        let (ci, pi) = v.selected_point().unwrap();
+        let origin = get_contour!(v.get_active_layer_ref(), ci).get_point(pi).unwrap().get_position();
+        self.rubber_band = Some(RubberBand::To(i.snap_pen_position(v, mouse_info, Some(origin))));
     }
 
     fn draw_nearest_point(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
-        if i.mouse_info.is_down {
+        if i.mouse_info.is_down || end_action(v, i, i.mouse_info.raw_position).is_some() {
             return;
         };
         let info = nearest_point_on_curve(v, i, i.mouse_info.position);
 
         if let Some(info) = info {
-            self.get_mode(v).draw_nearest_point(i, canvas, info);
+            self.get_mode_for_contour(v, info.contour_idx).draw_nearest_point(i, canvas, info);
         }
     }
 
-    fn draw_merge_preview(&self, v: &Editor, i: &Interface, canvas: &Canvas) {
-        // we've got a point selected?
-        if let (Some(c_idx), Some(p_idx)) = (v.contour_idx, v.point_idx) {
-            // we've clicked a handle?
-            if let Some((info_ci, info_pi, _)) =
-                clicked_point_or_handle(v, i, i.mouse_info.raw_position, None)
-            {
-                // we have the end of one contour active and clicked the start of another?
-                let end_is_active =
-                    get_contour_start_or_end(v, c_idx, p_idx) == Some(SelectPointInfo::End);
-                let start_is_clicked =
-                    get_contour_start_or_end(v, info_ci, info_pi) == Some(SelectPointInfo::Start);
-
-                // make sure these contours are open
-                let selected_open = is_contour_open!(v.get_active_layer_ref(), c_idx);
-                let target_open = is_contour_open!(v.get_active_layer_ref(), info_ci);
-                if end_is_active && start_is_clicked && selected_open && target_open {
-                    let point = get_contour!(v.get_active_layer_ref(), info_ci).get_point(p_idx).unwrap();
-                    draw_point(
-                        &i.viewport,
-                        point,
-                        None,
-                        true,
-                        canvas,
-                    );
-                }
+    // The selected contour as clicking would leave it, drawn by the pen mode that would change it. Hyperbezier and
+    // Spiro contours change shape all along when a point is added, so the whole contour is drawn.
+    fn draw_rubber_band(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        let rubber_band = match self.rubber_band {
+            Some(rubber_band) if !i.mouse_info.is_down && can_add_point(v) => rubber_band,
+            _ => return,
+        };
+        let (ci, pi) = v.selected_point().unwrap();
+        let mut contour = get_contour!(v.get_active_layer_ref(), ci).clone();
+        match rubber_band {
+            RubberBand::To(to) => {
+                let mut mouse_info = i.mouse_info;
+                mouse_info.position = to;
+                let at_start = pi != contour.len() - 1;
+                self.get_mode_for_contour(v, ci).extend_contour(&mut contour, at_start, mouse_info);
             }
+            RubberBand::Close => contour.set_closed(),
+        }
+        if spiro::is_spiro(&contour) {
+            spiro::solve(&mut contour);
+        }
+        let path = vec![contour.to_cubic()].to_skia_paths(None).combined();
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(OUTLINE_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+        canvas.draw_path(&path, &paint);
+    }
+
+    // Highlights the open end under the cursor. A ring around it means clicking closes or joins the contour.
+    fn draw_end_hint(&self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        if i.mouse_info.is_down {
+            return;
+        }
+        let (action, (ci, pi)) = match end_action(v, i, i.mouse_info.raw_position) {
+            Some(hovered) => hovered,
+            None => return,
+        };
+        let point = get_contour!(v.get_active_layer_ref(), ci).get_point(pi).unwrap();
+        draw_point(
+            &i.viewport,
+            point,
+            None,
+            true,
+            canvas,
+        );
+
+        if action != EndAction::Continue {
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_color(SELECTED_STROKE);
+            paint.set_stroke_width(POINT_STROKE_THICKNESS * (1. / i.viewport.factor));
+            canvas.draw_circle(point.get_position(), POINT_RADIUS * 2.5 * (1. / i.viewport.factor), &paint);
         }
     }
 
@@ -211,23 +307,91 @@ impl Pen {
             let (cidx, _) = v.selected_point().unwrap();
             return self.get_mode_for_contour(v, cidx);
         } else {
-            return self.get_mode_by_type(self.mode.clone());
+            return self.get_mode_by_type(self.mode);
         }
     }
 
     fn get_mode_for_contour(&mut self, v: &Editor, cidx: usize) -> &mut dyn PenMode {
+        if v.is_spiro(cidx) {
+            return self.get_mode_by_type(PenModeType::Spiro);
+        }
         let contour = &get_contour!(v.get_active_layer_ref(), cidx);
         
-        return self.get_mode_by_type(contour.get_type());
+        return self.get_mode_by_type(contour.get_type().into());
     }
 
-    fn get_mode_by_type(&mut self, kind: MFEKContourInnerType) -> &mut dyn PenMode {
+    fn get_mode_by_type(&mut self, kind: PenModeType) -> &mut dyn PenMode {
         match kind {
-            MFEKContourInnerType::Cubic => &mut self.cubic,
-            MFEKContourInnerType::Quad => &mut self.quad,
-            MFEKContourInnerType::Hyper => &mut self.hyper,
+            PenModeType::Cubic => &mut self.cubic,
+            PenModeType::Quad => &mut self.quad,
+            PenModeType::Hyper => &mut self.hyper,
+            PenModeType::Spiro => &mut self.spiro,
+        }
+    }
+
+    // The type new Spiro knots get. If a knot of a Spiro contour is selected, choosing a type changes that knot too.
+    fn spiro_point_types(&mut self, v: &mut Editor, ui: &mut egui::Ui) {
+        let selected = v.selected_point().and_then(|(ci, pi)| v.spiro_point_type(ci, pi).map(|ptype| (ci, pi, ptype)));
+        let current = selected.map(|(_, _, ptype)| ptype).unwrap_or(self.spiro.point_type);
+
+        ui.horizontal(|ui| {
+            for ptype in SpiroPointType::ALL {
+                if ui.selectable_label(current == ptype, ptype.name()).clicked() {
+                    self.spiro.point_type = ptype;
+                    if let Some((ci, pi, _)) = selected {
+                        v.set_spiro_point_type(ci, pi, ptype);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// What clicking the open end of a contour with the pen does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EndAction {
+    /// Select it, to carry on drawing the contour from that end.
+    Continue,
+    /// Close the selected contour, whose other end this is.
+    Close,
+    /// Join the contour whose end is `end` onto the contour whose start is `start`. One of them is selected.
+    Merge { start: usize, end: usize },
+}
+
+// The open end at `position`, if there is one, and what clicking it would do.
+fn end_action(v: &Editor, i: &Interface, position: (f32, f32)) -> Option<(EndAction, (usize, usize))> {
+    let (ci, pi, wh) = clicked_point_or_handle(v, i, position, None)?;
+    if !matches!(wh, WhichHandle::Neither) || !is_contour_open!(v.get_active_layer_ref(), ci) {
+        return None;
+    }
+    let clicked = get_contour_start_or_end(v, ci, pi)?;
+
+    if can_add_point(v) {
+        let (sci, spi) = v.selected_point().unwrap();
+        let selected = get_contour_start_or_end(v, sci, spi);
+        if selected.is_some() && selected != Some(clicked) {
+            if sci == ci {
+                if spi != pi {
+                    return Some((EndAction::Close, (ci, pi)));
+                }
+            } else if v.is_spiro(sci) == v.is_spiro(ci) {
+                // A Spiro contour joined onto a cubic one would have its handles solved away.
+                let (start, end) = if clicked == SelectPointInfo::Start { (ci, sci) } else { (sci, ci) };
+                return Some((EndAction::Merge { start, end }, (ci, pi)));
+            }
         }
     }
+    Some((EndAction::Continue, (ci, pi)))
+}
+
+// No matter how you move the point we want you to be able to manipulate it so we push the MoveHandle
+// vehavior onto the editor's behavior stack. Spiro knots have no handles of their own, so they're dragged instead.
+fn push_drag_behavior(v: &mut Editor, mouse_info: MouseInfo) {
+    if v.selected_point().map_or(false, |(ci, _)| v.is_spiro(ci)) {
+        v.push_behavior(Box::new(MovePoint::new(false, mouse_info)));
+    } else {
+        v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
+    }
 }
 
 // quick utility function
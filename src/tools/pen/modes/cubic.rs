@@ -1,10 +1,9 @@
 use MFEKmath::{Bezier, subdivide::Subdivide};
-use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation}, Point, PointType, WhichHandle, Contour, MFEKPointData};
-use glifrenderer::points::draw_point;
+use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation, MFEKContour}, Point, PointType, WhichHandle, Contour, MFEKPointData};
 use skia_safe::Canvas;
 
 use crate::{editor::{Editor, util::{HoveredPointInfo}}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
-use super::PenMode;
+use super::{draw_insertion_point, PenMode};
 
 /// This is the cubic specific pen implementation, and should serve as an example of the new pen API.
 /// One of the biggest things of note is that these mode structs should -not- contain modification specific state.
@@ -38,7 +37,6 @@ impl PenMode for CubicMode {
     }
     
     fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo) {
-        let mouse_pos = mouse_info.position;
         let contour_idx = v.contour_idx.unwrap();
         let contour_len = get_contour_len!(v.get_active_layer_ref(), contour_idx);
 
@@ -46,28 +44,14 @@ impl PenMode for CubicMode {
             v.point_idx = {
                 let layer = v.get_active_layer_mut();
                 layer.outline[contour_idx].operation_mut().insert_op(contour_len);
-                let contour = layer.outline[contour_idx].cubic_mut().unwrap();
-                contour.push(Point::from_x_y_type(
-                    (mouse_pos.0 as f32, mouse_pos.1 as f32),
-                    PointType::Curve,
-                ));
+                self.extend_contour(&mut layer.outline[contour_idx], false, mouse_info);
 
                 Some(get_contour_len!(layer, contour_idx) - 1)
             };
         } else if v.point_idx.unwrap() == 0 {
             {
                 let layer = v.get_active_layer_mut();
-                let contour = layer.outline[contour_idx].cubic_mut().unwrap();
-                let point_type = contour[0].ptype;
-
-                if contour.is_open() {
-                    contour[0].ptype = PointType::Curve;
-                }
-
-                contour.insert(
-                    0,
-                    Point::from_x_y_type((mouse_pos.0 as f32, mouse_pos.1 as f32), point_type),
-                );
+                self.extend_contour(&mut layer.outline[contour_idx], true, mouse_info);
 
                 layer.outline[contour_idx].operation_mut().insert_op( 0);
             };
@@ -76,14 +60,31 @@ impl PenMode for CubicMode {
         v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
     }
 
+    fn extend_contour(&self, contour: &mut MFEKContour<MFEKPointData>, at_start: bool, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        let contour = contour.cubic_mut().unwrap();
+
+        if !at_start {
+            contour.push(Point::from_x_y_type(
+                (mouse_pos.0 as f32, mouse_pos.1 as f32),
+                PointType::Curve,
+            ));
+        } else {
+            let point_type = contour[0].ptype;
+
+            if contour.is_open() {
+                contour[0].ptype = PointType::Curve;
+            }
+
+            contour.insert(
+                0,
+                Point::from_x_y_type((mouse_pos.0 as f32, mouse_pos.1 as f32), point_type),
+            );
+        }
+    }
+
     fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &Canvas, info: HoveredPointInfo) {
-        draw_point::<()>(
-            &i.viewport,
-            &Point::from_x_y_type(info.point, PointType::Curve),
-            None,
-            true,
-            canvas
-        )
+        draw_insertion_point(i, canvas, &info)
     }
     
     fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
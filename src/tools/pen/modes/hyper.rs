@@ -3,7 +3,7 @@ use skia_safe::Canvas;
 
 use crate::{editor::{Editor, util::HoveredPointInfo}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
 
-use super::PenMode;
+use super::{draw_insertion_point, PenMode};
 
 #[derive(Clone, Debug)]
 pub struct HyperMode {
@@ -31,35 +31,21 @@ impl PenMode for HyperMode {
     }
     
     fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo) {
-        let mouse_pos = mouse_info.position;
         let contour_idx = v.contour_idx.unwrap();
         let contour_len = get_contour_len!(v.get_active_layer_ref(), contour_idx);
-        
-        let smooth = !mouse_info.modifiers.alt;
 
         if v.point_idx.unwrap() == contour_len - 1 {
             v.point_idx = {
                 let layer = v.get_active_layer_mut();
                 layer.outline[contour_idx].operation_mut().insert_op(contour_len);
-                let contour = layer.outline[contour_idx].hyper_mut().unwrap();
-                contour.get_points_mut().push(HyperPoint::new(
-                    mouse_pos.0 as f32, 
-                    mouse_pos.1 as f32,
-                    HyperPointType::Curve,
-                    smooth
-                ));
+                self.extend_contour(&mut layer.outline[contour_idx], false, mouse_info);
 
                 Some(get_contour_len!(layer, contour_idx) - 1)
             };
         } else if v.point_idx.unwrap() == 0 {
             {
                 let layer = v.get_active_layer_mut();
-                let contour = layer.outline[contour_idx].hyper_mut().unwrap();
-
-                contour.get_points_mut().insert(
-                    0,
-                    HyperPoint::new(mouse_pos.0 as f32, mouse_pos.1 as f32, HyperPointType::Curve, true),
-                );
+                self.extend_contour(&mut layer.outline[contour_idx], true, mouse_info);
 
                 layer.outline[contour_idx].operation_mut().insert_op( 0);
             };
@@ -68,9 +54,39 @@ impl PenMode for HyperMode {
         v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
     }
 
-    fn draw_nearest_point(&self, _i: &crate::user_interface::Interface, _canvas: &Canvas, _info: HoveredPointInfo) {
+    fn extend_contour(&self, contour: &mut MFEKContour<MFEKPointData>, at_start: bool, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        let contour = contour.hyper_mut().unwrap();
+
+        if !at_start {
+            let smooth = !mouse_info.modifiers.alt;
+            contour.get_points_mut().push(HyperPoint::new(
+                mouse_pos.0 as f32, 
+                mouse_pos.1 as f32,
+                HyperPointType::Curve,
+                smooth
+            ));
+        } else {
+            contour.get_points_mut().insert(
+                0,
+                HyperPoint::new(mouse_pos.0 as f32, mouse_pos.1 as f32, HyperPointType::Curve, true),
+            );
+        }
+    }
+
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &Canvas, info: HoveredPointInfo) {
+        draw_insertion_point(i, canvas, &info)
     }
-    
-    fn subdivide_curve(&self, _v: &mut Editor, _info: HoveredPointInfo) {
+
+    // Hyperbeziers have no handles to split, so a smooth point is put on the curve at info.t and
+    // the spline is solved through it.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        let layer = v.get_active_layer_mut();
+        let contour = layer.outline[info.contour_idx].hyper_mut().unwrap();
+        contour.get_points_mut().insert(
+            info.seg_idx + 1,
+            HyperPoint::new(info.point.0, info.point.1, HyperPointType::Curve, true),
+        );
+        layer.outline[info.contour_idx].operation_mut().insert_op(info.seg_idx + 1);
     }
 }
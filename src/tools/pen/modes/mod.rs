@@ -1,20 +1,58 @@
 pub mod cubic;
 pub mod quad;
 pub mod hyper;
+pub mod spiro;
 
 use MFEKmath::skia_safe::Canvas;
 use dyn_clone::DynClone;
+use glifparser::{glif::{inner::MFEKContourInnerType, MFEKContour}, MFEKPointData, Point, PointType};
+use glifrenderer::points::draw_point;
 use crate::{editor::{Editor, util::HoveredPointInfo}, user_interface::{Interface, MouseInfo}};
 
+/// The kind of contour the pen draws. Spiro contours are cubic as far as glifparser is concerned, so this isn't
+/// just an MFEKContourInnerType.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PenModeType {
+    Cubic,
+    Quad,
+    Hyper,
+    Spiro,
+}
+
+impl From<MFEKContourInnerType> for PenModeType {
+    fn from(kind: MFEKContourInnerType) -> Self {
+        match kind {
+            MFEKContourInnerType::Cubic => PenModeType::Cubic,
+            MFEKContourInnerType::Quad => PenModeType::Quad,
+            MFEKContourInnerType::Hyper => PenModeType::Hyper,
+        }
+    }
+}
+
 pub trait PenMode: DynClone + std::fmt::Debug {
     // No selection starting to draw a new contour.
     fn new_contour(&self, v: &mut Editor, mouse_info: MouseInfo);
 
     // The start or end of a contour is selected and we're adding to it.
     fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo);
+
+    // Adds a point at mouse_info.position to the start or end of contour. add_point adds points with this, and the
+    // pen previews the next segment with it, so the preview is what clicking would make.
+    fn extend_contour(&self, contour: &mut MFEKContour<MFEKPointData>, at_start: bool, mouse_info: MouseInfo);
     
     // If a contour type does not support subdivision the way that cubic or quadratic does then you can safely
     // stub these functions out.
     fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo);
     fn draw_nearest_point(&self, i: &Interface, canvas: &Canvas, info: HoveredPointInfo);
-}
\ No newline at end of file
+}
+
+/// Marks the point on a curve where clicking would insert a new point.
+pub fn draw_insertion_point(i: &Interface, canvas: &Canvas, info: &HoveredPointInfo) {
+    draw_point::<()>(
+        &i.viewport,
+        &Point::from_x_y_type(info.point, PointType::Curve),
+        None,
+        true,
+        canvas
+    )
+}
@@ -2,7 +2,7 @@ use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourO
 use skia_safe::Canvas;
 
 use crate::{editor::{Editor, util::{HoveredPointInfo}}, user_interface::MouseInfo, get_contour_len, tool_behaviors::move_handle::MoveHandle};
-use super::PenMode;
+use super::{draw_insertion_point, PenMode};
 
 #[derive(Clone, Debug)]
 pub struct QuadMode {
@@ -45,7 +45,6 @@ impl PenMode for QuadMode {
     }
     
     fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo) {
-        let mouse_pos = mouse_info.position;
         let contour_idx = v.contour_idx.unwrap();
         let contour_len = get_contour_len!(v.get_active_layer_ref(), contour_idx);
 
@@ -53,28 +52,14 @@ impl PenMode for QuadMode {
             v.point_idx = {
                 let layer = v.get_active_layer_mut();
                 layer.outline[contour_idx].operation_mut().insert_op(contour_len);
-                let contour = layer.outline[contour_idx].quad_mut().unwrap();
-                contour.push(QPoint::from_x_y_type(
-                    (mouse_pos.0 as f32, mouse_pos.1 as f32),
-                    PointType::Curve,
-                ));
+                self.extend_contour(&mut layer.outline[contour_idx], false, mouse_info);
 
                 Some(get_contour_len!(layer, contour_idx) - 1)
             };
         } else if v.point_idx.unwrap() == 0 {
             {
                 let layer = v.get_active_layer_mut();
-                let contour = layer.outline[contour_idx].quad_mut().unwrap();
-                let point_type = contour[0].ptype;
-
-                if contour.is_open() {
-                    contour[0].ptype = PointType::Curve;
-                }
-
-                contour.insert(
-                    0,
-                    QPoint::from_x_y_type((mouse_pos.0 as f32, mouse_pos.1 as f32), point_type),
-                );
+                self.extend_contour(&mut layer.outline[contour_idx], true, mouse_info);
 
                 layer.outline[contour_idx].operation_mut().insert_op( 0);
             };
@@ -83,8 +68,61 @@ impl PenMode for QuadMode {
         v.push_behavior(Box::new(MoveHandle::new(WhichHandle::A, mouse_info, true)));
     }
 
-    // TODO: Implement these for quadratic! Would take a bit more work in math.rlib
-    // These functions are safe to be stubbed for now
-    fn draw_nearest_point(&self, _i: &crate::user_interface::Interface, _canvas: &Canvas, _info: HoveredPointInfo) {}
-    fn subdivide_curve(&self, _v: &mut Editor, _info: HoveredPointInfo) {}
+    fn extend_contour(&self, contour: &mut MFEKContour<MFEKPointData>, at_start: bool, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        let contour = contour.quad_mut().unwrap();
+
+        if !at_start {
+            contour.push(QPoint::from_x_y_type(
+                (mouse_pos.0 as f32, mouse_pos.1 as f32),
+                PointType::Curve,
+            ));
+        } else {
+            let point_type = contour[0].ptype;
+
+            if contour.is_open() {
+                contour[0].ptype = PointType::Curve;
+            }
+
+            contour.insert(
+                0,
+                QPoint::from_x_y_type((mouse_pos.0 as f32, mouse_pos.1 as f32), point_type),
+            );
+        }
+    }
+
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &Canvas, info: HoveredPointInfo) {
+        draw_insertion_point(i, canvas, &info)
+    }
+
+    // Splits the quadratic at info.t with de Casteljau, so it becomes two quadratics tracing the
+    // same curve. Straight segments stay straight.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        let layer = v.get_active_layer_mut();
+        let contour = layer.outline[info.contour_idx].quad_mut().unwrap();
+        let (start, end) = (info.seg_idx, (info.seg_idx + 1) % contour.len());
+
+        let lerp = |a: (f32, f32), b: (f32, f32), t: f32| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        let t = info.t as f32;
+        let (p0, p2) = ((contour[start].x, contour[start].y), (contour[end].x, contour[end].y));
+        let new_point = match contour[start].a {
+            Handle::At(cx, cy) => {
+                let (c0, c1) = (lerp(p0, (cx, cy), t), lerp((cx, cy), p2, t));
+                let at = lerp(c0, c1, t);
+                contour[start].a = Handle::At(c0.0, c0.1);
+                QPoint {
+                    x: at.0,
+                    y: at.1,
+                    a: Handle::At(c1.0, c1.1),
+                    name: None,
+                    ptype: PointType::Curve,
+                    smooth: true,
+                    data: None,
+                }
+            }
+            Handle::Colocated => QPoint::from_x_y_type(lerp(p0, p2, t), PointType::Curve),
+        };
+        contour.insert(start + 1, new_point);
+        layer.outline[info.contour_idx].operation_mut().insert_op(start + 1);
+    }
 }
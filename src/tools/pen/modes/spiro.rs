@@ -0,0 +1,82 @@
+use glifparser::{glif::{contour::MFEKContourCommon, contour_operations::ContourOperation, MFEKContour}, Contour, MFEKPointData, PointType};
+use skia_safe::Canvas;
+
+use crate::{editor::{Editor, spiro::{knot, SpiroPointType}, util::HoveredPointInfo}, user_interface::MouseInfo, get_contour_len};
+use super::{draw_insertion_point, PenMode};
+
+/// Spiro contours are cubic contours of knots tagged with their Spiro point types (see `editor::spiro`).
+/// Only knots are placed; their handles come from solving the spline.
+#[derive(Clone, Debug)]
+pub struct SpiroMode {
+    // The type given to new knots, chosen in the pen's mode window.
+    pub point_type: SpiroPointType,
+}
+
+impl PenMode for SpiroMode {
+    fn new_contour(&self, v: &mut Editor, mouse_info: MouseInfo) {
+        let mouse_pos = mouse_info.position;
+        let contour_idx = {
+            let layer = v.get_active_layer_mut();
+            let new_contour: Contour<MFEKPointData> = vec![knot(
+                (mouse_pos.0 as f32, mouse_pos.1 as f32),
+                PointType::Move,
+                self.point_type,
+            )];
+
+            layer.outline.push(new_contour.into());
+            layer.outline.len() - 1
+        };
+        v.contour_idx = Some(contour_idx);
+        v.point_idx = Some(0);
+    }
+
+    fn add_point(&self, v: &mut Editor, mouse_info: MouseInfo) {
+        let contour_idx = v.contour_idx.unwrap();
+        let contour_len = get_contour_len!(v.get_active_layer_ref(), contour_idx);
+
+        if v.point_idx.unwrap() == contour_len - 1 {
+            {
+                let layer = v.get_active_layer_mut();
+                layer.outline[contour_idx].operation_mut().insert_op(contour_len);
+                self.extend_contour(&mut layer.outline[contour_idx], false, mouse_info);
+            }
+            v.point_idx = Some(contour_len);
+        } else if v.point_idx.unwrap() == 0 {
+            {
+                let layer = v.get_active_layer_mut();
+                self.extend_contour(&mut layer.outline[contour_idx], true, mouse_info);
+
+                layer.outline[contour_idx].operation_mut().insert_op(0);
+            }
+        }
+    }
+
+    fn extend_contour(&self, contour: &mut MFEKContour<MFEKPointData>, at_start: bool, mouse_info: MouseInfo) {
+        let mouse_pos = (mouse_info.position.0 as f32, mouse_info.position.1 as f32);
+        let contour = contour.cubic_mut().unwrap();
+
+        if !at_start {
+            contour.push(knot(mouse_pos, PointType::Curve, self.point_type));
+        } else {
+            let point_type = contour[0].ptype;
+            contour[0].ptype = PointType::Curve;
+            contour.insert(0, knot(mouse_pos, point_type, self.point_type));
+        }
+    }
+
+    fn draw_nearest_point(&self, i: &crate::user_interface::Interface, canvas: &Canvas, info: HoveredPointInfo) {
+        draw_insertion_point(i, canvas, &info)
+    }
+
+    // The spline is solved again through a new knot at info.point, so the curve moves a little
+    // rather than being split exactly.
+    fn subdivide_curve(&self, v: &mut Editor, info: HoveredPointInfo) {
+        let layer = v.get_active_layer_mut();
+        layer.outline[info.contour_idx].operation_mut().insert_op(info.seg_idx + 1);
+        let contour = layer.outline[info.contour_idx].cubic_mut().unwrap();
+        contour.insert(
+            info.seg_idx + 1,
+            knot(info.point, PointType::Curve, self.point_type),
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use super::Pencil;
+
+impl Pencil {
+    pub fn tool_dialog(&mut self, ui: &mut egui::Ui) {
+        ui.label("Tolerance");
+        ui.add(egui::Slider::new(&mut self.tolerance, 0.5..=50.).logarithmic(true));
+
+        ui.label("Smoothing");
+        ui.add(egui::Slider::new(&mut self.smoothing, 0..=10));
+
+        ui.label("Close within (px)");
+        ui.add(egui::Slider::new(&mut self.close_distance, 0.0..=50.));
+
+        ui.separator();
+
+        let mut brush = self.brush_width.is_some();
+        ui.checkbox(&mut brush, "Brush stroke (VWS)");
+        match (brush, self.brush_width) {
+            (true, None) => self.brush_width = Some(20.),
+            (false, Some(_)) => self.brush_width = None,
+            _ => (),
+        }
+        if let Some(width) = self.brush_width.as_mut() {
+            ui.label("Width");
+            ui.add(egui::Slider::new(width, 1.0..=200.));
+        }
+    }
+}
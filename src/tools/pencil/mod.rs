@@ -0,0 +1,212 @@
+// Freehand drawing. The mouse path is recorded while dragging, smoothed with a moving average, and
+// fit with cubics by flo_curves, the same fitter `Editor::simplify_cubic_selection` uses.
+
+mod dialog;
+
+use flo_curves::bezier::fit_curve_cubic;
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::contour_operations::vws::{InterpolationType, VWSHandle};
+use glifparser::glif::contour_operations::ContourOperations;
+use glifparser::glif::MFEKContour;
+use glifparser::{CapType, JoinType, MFEKPointData, VWSContour};
+use skia_safe::Path;
+use MFEKmath::{vec2, Bezier, Piecewise, Vector};
+
+use super::prelude::*;
+use crate::tool_behaviors::zoom_scroll::ZoomScroll;
+
+// Mouse samples closer together than this many screen pixels are dropped.
+const MIN_SAMPLE_DISTANCE: f32 = 2.;
+
+#[derive(Clone, Debug)]
+pub struct Pencil {
+    // The path being drawn, in glyph space.
+    points: Vec<(f32, f32)>,
+    /// How far, in font units, the fitted curves may stray from the smoothed path.
+    tolerance: f64,
+    /// How many samples on either side are averaged into each point.
+    smoothing: usize,
+    /// Close the contour when the stroke ends this near its start, in screen pixels. Zero never
+    /// closes.
+    close_distance: f32,
+    /// Attach a variable width stroke of this width to new contours, making them brush strokes.
+    brush_width: Option<f64>,
+}
+
+impl Tool for Pencil {
+    #[rustfmt::skip]
+    fn event(&mut self, v: &mut Editor, i: &mut Interface, event: EditorEvent) {
+        match event {
+            EditorEvent::MouseEvent { mouse_info, event_type } => match event_type {
+                MouseEventType::Pressed => self.mouse_pressed(mouse_info),
+                MouseEventType::Moved => self.mouse_moved(i, mouse_info),
+                MouseEventType::Released => self.mouse_released(v, i),
+                _ => (),
+            }
+            EditorEvent::ScrollEvent { .. } => ZoomScroll::default().event(v, i, event),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, _v: &Editor, i: &Interface, canvas: &Canvas) {
+        if self.points.len() < 2 {
+            return;
+        }
+        let mut path = Path::new();
+        path.move_to(self.points[0]);
+        for point in self.points[1..].iter() {
+            path.line_to(*point);
+        }
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_color(OUTLINE_STROKE);
+        paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * (1. / i.viewport.factor));
+        canvas.draw_path(&path, &paint);
+    }
+
+    fn dialog(&mut self, _v: &mut Editor, _i: &mut Interface, ui: &mut Ui) -> bool {
+        self.tool_dialog(ui);
+        true
+    }
+}
+
+impl Pencil {
+    pub fn new() -> Self {
+        Self {
+            points: vec![],
+            tolerance: 4.,
+            smoothing: 2,
+            close_distance: 12.,
+            brush_width: None,
+        }
+    }
+
+    fn mouse_pressed(&mut self, mouse_info: MouseInfo) {
+        self.points = vec![mouse_info.position];
+    }
+
+    fn mouse_moved(&mut self, i: &Interface, mouse_info: MouseInfo) {
+        let last = match self.points.last() {
+            Some(last) => *last,
+            None => return,
+        };
+        let (x, y) = mouse_info.position;
+        if (x - last.0).hypot(y - last.1) * i.viewport.factor >= MIN_SAMPLE_DISTANCE {
+            self.points.push(mouse_info.position);
+        }
+    }
+
+    fn mouse_released(&mut self, v: &mut Editor, i: &Interface) {
+        let points = std::mem::take(&mut self.points);
+        if points.len() < 2 {
+            return;
+        }
+
+        let (first, last) = (points[0], points[points.len() - 1]);
+        let closed = points.len() > 3
+            && (first.0 - last.0).hypot(first.1 - last.1) * i.viewport.factor < self.close_distance;
+
+        let beziers = fit_stroke(&points, closed, self.smoothing, self.tolerance);
+        if beziers.is_empty() {
+            return;
+        }
+        let mut contour: MFEKContour<MFEKPointData> =
+            Piecewise::new(beziers, None).to_contour().into();
+        if closed {
+            contour.set_closed();
+        } else {
+            contour.set_open();
+        }
+        if let Some(width) = self.brush_width {
+            contour.set_operation(Some(ContourOperations::VariableWidthStroke {
+                data: brush_stroke(contour.len(), width),
+            }));
+        }
+
+        v.begin_modification("Draw with pencil.", false);
+        let layer = v.get_active_layer_mut();
+        let ci = layer.outline.len();
+        layer.outline.push(contour);
+        v.contour_idx = Some(ci);
+        v.point_idx = Some(0);
+        v.selected.clear();
+        v.end_modification();
+    }
+}
+
+fn brush_stroke(points: usize, width: f64) -> VWSContour {
+    VWSContour {
+        handles: (0..points + 1)
+            .map(|_| VWSHandle {
+                left_offset: width / 2.,
+                right_offset: width / 2.,
+                interpolation: InterpolationType::Linear,
+                tangent_offset: 0.,
+            })
+            .collect(),
+        cap_start_type: CapType::Round,
+        cap_end_type: CapType::Round,
+        join_type: JoinType::Round,
+        remove_internal: false,
+        remove_external: false,
+    }
+}
+
+/// Smooths the sampled mouse path and fits it with cubics. A closed path is averaged around its
+/// seam and starts and ends on the same tangent.
+fn fit_stroke(
+    points: &[(f32, f32)],
+    closed: bool,
+    smoothing: usize,
+    tolerance: f64,
+) -> Vec<Bezier> {
+    let mut raw: Vec<Vector> = points
+        .iter()
+        .map(|(x, y)| vec2!(*x as f64, *y as f64))
+        .collect();
+    raw.dedup_by(|a, b| a.distance(*b) < f64::EPSILON);
+    let n = raw.len();
+    if n < 2 {
+        return vec![];
+    }
+
+    let smoothed: Vec<Vector> = (0..n)
+        .map(|i| {
+            // The ends of an open stroke stay where they were drawn.
+            if !closed && (i == 0 || i == n - 1) {
+                return raw[i];
+            }
+            let (mut sum, mut count) = (vec2!(0., 0.), 0.);
+            for j in (i as isize - smoothing as isize)..=(i + smoothing) as isize {
+                let j = if closed {
+                    j.rem_euclid(n as isize) as usize
+                } else if j < 0 || j >= n as isize {
+                    continue;
+                } else {
+                    j as usize
+                };
+                sum = sum + raw[j];
+                count += 1.;
+            }
+            sum * (1. / count)
+        })
+        .collect();
+
+    let mut run = smoothed;
+    if closed {
+        run.push(run[0]);
+    }
+    let n = run.len();
+    let reach = 3.min(n - 1);
+    let (start_tangent, end_tangent) = if closed {
+        let tangent = (run[1] - run[n - 2]).normalize();
+        (tangent, -tangent)
+    } else {
+        (
+            (run[reach] - run[0]).normalize(),
+            (run[n - 1 - reach] - run[n - 1]).normalize(),
+        )
+    };
+    fit_curve_cubic(&run, &start_tangent, &end_tangent, tolerance)
+}
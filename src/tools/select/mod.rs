@@ -13,7 +13,8 @@ use glifparser::glif::mfek::contour::MFEKContourCommon;
 use MFEKmath::Vector;
 
 use crate::tool_behaviors::{
-    draw_pivot::DrawPivot, move_handle::MoveHandle, move_point::MovePoint, pan::PanBehavior,
+    draw_pivot::DrawPivot, move_component::MoveComponent, move_handle::MoveHandle,
+    move_point::MovePoint, pan::PanBehavior,
     selection_box::SelectionBox, zoom_scroll::ZoomScroll,
 };
 
@@ -71,6 +72,7 @@ impl Tool for Select {
     }
 
     fn draw(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        self.draw_selected_component(v, i, canvas);
         self.draw_tunni_line(v, i, canvas);
         self.draw_pivot.draw(v, i, canvas);
     }
@@ -91,6 +93,17 @@ impl Select {
         v.selected = points;
     }
 
+    fn draw_selected_component(&self, v: &Editor, i: &Interface, canvas: &Canvas) {
+        if let Some(rect) = v.component_idx.and_then(|idx| v.component_rect(idx)) {
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_color(SELECTED_FILL);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(OUTLINE_STROKE_THICKNESS * 2. * (1. / i.viewport.factor));
+            canvas.draw_rect(rect, &paint);
+        }
+    }
+
     fn draw_tunni_line(&mut self, v: &Editor, i: &Interface, canvas: &Canvas) {
         let mut paint = Paint::default();
     
@@ -208,6 +221,7 @@ impl Select {
             }
 
             // Set the editor's selected point to the most recently clicked one.
+            v.component_idx = None;
             v.contour_idx = Some(ci);
             v.point_idx = Some(pi);
 
@@ -240,6 +254,18 @@ impl Select {
         }
 
 
+        // the user clicked inside a component, so we select it and start dragging it
+        if mouse_info.button == MouseButton::Left {
+            if let Some(idx) = v.component_at(mouse_info.position) {
+                v.selected = HashSet::new();
+                v.contour_idx = None;
+                v.point_idx = None;
+                v.component_idx = Some(idx);
+                v.set_behavior(Box::new(MoveComponent::new(v, idx, mouse_info)));
+                return
+            }
+        }
+
         // the user clicked an empty location
         // if the user isn't holding shift we clear the current selection and the currently selected
         // point
@@ -247,6 +273,7 @@ impl Select {
             v.selected = HashSet::new();
             v.contour_idx = None;
             v.point_idx = None;
+            v.component_idx = None;
         }
 
         // if they clicked right mouse we set the pivot point that will be used by rotate_points behavior.
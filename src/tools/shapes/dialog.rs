@@ -1,14 +1,20 @@
+use crate::editor::Editor;
 use crate::user_interface::{Interface, gui::windows::egui_parsed_textfield};
 
 use super::{ShapeType, Shapes};
 
 impl Shapes {
-    pub fn shape_settings(&mut self, _i: &mut Interface, ui: &mut egui::Ui) {
+    pub fn shape_settings(&mut self, v: &mut Editor, _i: &mut Interface, ui: &mut egui::Ui) {
         ui.radio_value(&mut self.stype, ShapeType::Circle, "Circle");
         ui.radio_value(&mut self.stype, ShapeType::Oval, "Oval");
+        ui.radio_value(&mut self.stype, ShapeType::Superellipse, "Superellipse");
         ui.radio_value(&mut self.stype, ShapeType::Rectangle, "Rectangle");
         ui.radio_value(&mut self.stype, ShapeType::RoundedRectangle, "RoundedRectangle");
         ui.radio_value(&mut self.stype, ShapeType::Polygon, "Polygon");
+        ui.radio_value(&mut self.stype, ShapeType::RoundedPolygon, "RoundedPolygon");
+        ui.radio_value(&mut self.stype, ShapeType::Star, "Star");
+        ui.radio_value(&mut self.stype, ShapeType::RingSector, "RingSector");
+        ui.radio_value(&mut self.stype, ShapeType::Spiral, "Spiral");
 
         ui.separator();
 
@@ -27,7 +33,68 @@ impl Shapes {
                 ui.label("Angle");
                 self.sdata.polygon_angle = egui_parsed_textfield(ui, "angle", self.sdata.polygon_angle, &mut self.edit_buf)
             }
+            ShapeType::RoundedPolygon => {
+                ui.label("Sides");
+                ui.add(egui::Slider::new(&mut self.sdata.polygon_sides, 3u16..=50u16));
+                let last = self.sdata.corner_radii.last().copied().unwrap_or(0.);
+                self.sdata.corner_radii.resize(self.sdata.polygon_sides as usize, last);
+
+                ui.label("All corners");
+                let mut all = self.sdata.corner_radii[0];
+                if ui.add(egui::Slider::new(&mut all, 0f32..=500f32)).changed() {
+                    self.sdata.corner_radii.iter_mut().for_each(|r| *r = all);
+                }
+                ui.collapsing("Corners", |ui| {
+                    for (idx, radius) in self.sdata.corner_radii.iter_mut().enumerate() {
+                        ui.add(egui::Slider::new(radius, 0f32..=500f32).text(format!("{}", idx + 1)));
+                    }
+                });
+
+                ui.label("Angle");
+                self.sdata.polygon_angle = egui_parsed_textfield(ui, "angle", self.sdata.polygon_angle, &mut self.edit_buf)
+            }
+            ShapeType::Superellipse => {
+                ui.label("Exponent");
+                ui.add(egui::Slider::new(&mut self.sdata.superellipse_exponent, 0.5f32..=10f32));
+            }
+            ShapeType::RingSector => {
+                ui.label("Inner radius");
+                ui.add(egui::Slider::new(&mut self.sdata.ring_inner, 0f32..=0.99f32));
+
+                ui.label("Sweep (°)");
+                ui.add(egui::Slider::new(&mut self.sdata.ring_sweep, 1f32..=360f32));
+            }
+            ShapeType::Spiral => {
+                ui.label("Turns");
+                ui.add(egui::Slider::new(&mut self.sdata.spiral_turns, 0.25f32..=20f32));
+            }
             _ => (),
         }
+
+        ui.separator();
+
+        if self.stype.fits_in_rect() {
+            ui.label("Corner");
+        } else {
+            ui.label("Center");
+        }
+        self.placement.x = egui_parsed_textfield(ui, "px", self.placement.x, &mut self.edit_buf);
+        self.placement.y = egui_parsed_textfield(ui, "py", self.placement.y, &mut self.edit_buf);
+        if self.stype.fits_in_rect() {
+            ui.label("Size");
+            self.placement.width = egui_parsed_textfield(ui, "pw", self.placement.width, &mut self.edit_buf);
+            self.placement.height = egui_parsed_textfield(ui, "ph", self.placement.height, &mut self.edit_buf);
+        } else {
+            ui.label("Radius");
+            self.placement.width = egui_parsed_textfield(ui, "pw", self.placement.width, &mut self.edit_buf);
+        }
+        ui.label("Rotation");
+        self.placement.rotation = egui_parsed_textfield(ui, "protation", self.placement.rotation, &mut self.edit_buf);
+        if ui.button("Add shape").clicked() {
+            self.place(v);
+        }
+
+        ui.separator();
+        ui.label("Hold Alt when releasing a dragged shape to unite it with the outline under it, or Alt and the Windows key to cut it out.");
     }
-}
\ No newline at end of file
+}
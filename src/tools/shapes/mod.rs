@@ -1,4 +1,5 @@
 mod dialog;
+mod paths;
 
 use std::collections::HashMap;
 use std::f32::consts::PI;
@@ -9,20 +10,26 @@ use crate::tool_behaviors::selection_box::SelectionBox;
 use crate::user_interface::Interface;
 
 use float_cmp::ApproxEq;
+use glifparser::glif::contour::MFEKContourCommon as _;
 use glifparser::MFEKPointData;
-use glifparser::outline::{skia::FromSkiaPath as _, FromKurbo as _, Reverse};
+use glifparser::outline::{skia::{FromSkiaPath as _, ToSkiaPaths as _}, FromKurbo as _, Reverse};
 use glifparser::{glif::MFEKContour, Outline};
 use kurbo;
-use kurbo::Shape as _;
+use kurbo::{Affine, Shape as _};
 use num;
 use num_derive::FromPrimitive;
-use skia_safe::{Matrix, Path, PathDirection, PathEffect, StrokeRec};
+use skia_safe::{Matrix, Path, PathDirection, PathEffect, PathOp, StrokeRec};
+
+use crate::editor::components::transform_point;
+use crate::editor::nib::{nib_of, nibs_in};
+use crate::editor::spiro::is_spiro;
 
 
 #[derive(Clone, Debug)]
 pub struct Shapes {
     pressed_pos: Option<(f32, f32)>,
-    dropped_shape: bool,
+    // How many contours of the shape being dragged are at the end of the outline.
+    dropped_contours: usize,
     // Because of imgui, we can't have associated types on the ShapeType enum. Thus, this
     // compromise.
     stype: ShapeType,
@@ -30,6 +37,7 @@ pub struct Shapes {
     corners: Option<((f32, f32), (f32, f32))>,
     draw_pivot: DrawPivot,
     locked_angle: bool,
+    placement: Placement,
 
     //ui
     edit_buf: HashMap<String, String>,
@@ -52,8 +60,8 @@ impl Tool for Shapes {
         }
     }
 
-    fn dialog(&mut self, _v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
-        self.shape_settings(i, ui);
+    fn dialog(&mut self, v: &mut Editor, i: &mut Interface, ui: &mut Ui) -> bool {
+        self.shape_settings(v, i, ui);
         true
     }
 
@@ -76,14 +84,22 @@ pub enum ShapeType {
     Circle,
     #[display(fmt = "oval")]
     Oval,
+    #[display(fmt = "superellipse")]
+    Superellipse,
     #[display(fmt = "rectangle")]
     Rectangle,
     #[display(fmt = "rounded rectangle")]
     RoundedRectangle,
     #[display(fmt = "polygon")]
     Polygon,
+    #[display(fmt = "rounded polygon")]
+    RoundedPolygon,
     #[display(fmt = "star")]
     Star,
+    #[display(fmt = "ring sector")]
+    RingSector,
+    #[display(fmt = "spiral")]
+    Spiral,
 }
 
 // Implement scrolling through options
@@ -92,26 +108,39 @@ impl ShapeType {
     fn prev(&self) -> Self {
         use ShapeType::*;
         match self {
-            Oval | Rectangle | RoundedRectangle | Polygon | Star => {
-                num::FromPrimitive::from_u32(*self as u32 - 1).unwrap()
-            }
-            Circle => Star,
+            Oval | Superellipse | Rectangle | RoundedRectangle | Polygon | RoundedPolygon | Star
+            | RingSector | Spiral => num::FromPrimitive::from_u32(*self as u32 - 1).unwrap(),
+            Circle => Spiral,
         }
     }
 
     fn next(&self) -> Self {
         use ShapeType::*;
         match self {
-            Circle | Oval | Rectangle | RoundedRectangle | Polygon => {
-                num::FromPrimitive::from_u32(*self as u32 + 1).unwrap()
-            }
-            Star => Circle,
+            Circle | Oval | Superellipse | Rectangle | RoundedRectangle | Polygon | RoundedPolygon
+            | Star | RingSector => num::FromPrimitive::from_u32(*self as u32 + 1).unwrap(),
+            Spiral => Circle,
         }
     }
+
+    /// Whether the shape is dragged out from corner to corner, rather than from its centre.
+    fn fits_in_rect(&self) -> bool {
+        matches!(
+            self,
+            ShapeType::Oval
+                | ShapeType::Superellipse
+                | ShapeType::Rectangle
+                | ShapeType::RoundedRectangle
+        )
+    }
+
+    fn has_polygon_angle(&self) -> bool {
+        matches!(self, ShapeType::Polygon | ShapeType::RoundedPolygon | ShapeType::Star)
+    }
 }
 
 
-#[derive(Copy, Clone, Debug, derive_more::Display)]
+#[derive(Clone, Debug, derive_more::Display)]
 #[display(
     fmt = "{}{}{}",
     "self.display_sides()",
@@ -123,6 +152,14 @@ pub struct ShapeData {
     polygon_sides: u16,
     polygon_radius: f32,
     rrect_radius: f32,
+    /// Rounding of each corner of a rounded polygon, from the first.
+    corner_radii: Vec<f32>,
+    superellipse_exponent: f32,
+    /// Inner radius of a ring sector, as a fraction of the outer one.
+    ring_inner: f32,
+    /// Sweep of a ring sector, in degrees.
+    ring_sweep: f32,
+    spiral_turns: f32,
 }
 
 impl ShapeData {
@@ -149,6 +186,11 @@ impl Default for ShapeData {
             polygon_sides: 5,
             polygon_radius: 0.,
             rrect_radius: 50.,
+            corner_radii: vec![20.; 5],
+            superellipse_exponent: 4.,
+            ring_inner: 0.5,
+            ring_sweep: 270.,
+            spiral_turns: 3.,
         }
     }
 }
@@ -157,20 +199,68 @@ impl Shapes {
     pub fn new() -> Self {
         Self {
             pressed_pos: None,
-            dropped_shape: false,
+            dropped_contours: 0,
             stype: ShapeType::Circle,
             sdata: ShapeData::default(),
             corners: None,
             draw_pivot: DrawPivot::default(),
             locked_angle: false,
+            placement: Placement::default(),
             edit_buf: HashMap::new(),
         }
     }
 }
 
+/// A shape typed in by numbers. `x` and `y` are the corner of shapes that fit in a rect, and the
+/// centre of the others, which use `width` as their radius.
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    /// In degrees, about the shape's centre.
+    rotation: f32,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            x: 0.,
+            y: 0.,
+            width: 200.,
+            height: 200.,
+            rotation: 0.,
+        }
+    }
+}
+
+/// What a new shape does to the outline already under it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Combine {
+    Add,
+    Unite,
+    Subtract,
+}
+
+impl From<MouseInfo> for Combine {
+    // Alt unites, and Alt with the Windows key subtracts. Shift and Ctrl are left to the drag, to
+    // square the shape and lock its angle.
+    fn from(mouse_info: MouseInfo) -> Self {
+        match (mouse_info.modifiers.alt, mouse_info.modifiers.meta) {
+            (true, true) => Combine::Subtract,
+            (true, false) => Combine::Unite,
+            _ => Combine::Add,
+        }
+    }
+}
+
 struct ShapeDrawer {
-    mouse_info: MouseInfo,
     from: (f32, f32),
+    // The other corner, or a point on the edge of a shape drawn from its centre.
+    to: (f32, f32),
+    // Make shapes that fit in a rect square.
+    square: bool,
     sdata: ShapeData,
     corners: Option<((f32, f32), (f32, f32))>,
 }
@@ -186,8 +276,8 @@ fn shape_direction(cx: f32) -> PathDirection {
 impl ShapeDrawer {
     fn calculate_radius(&self) -> (f32, f32, f32) {
         let (cx, cy) = (
-            self.from.0 - self.mouse_info.position.0,
-            self.from.1 - self.mouse_info.position.1,
+            self.from.0 - self.to.0,
+            self.from.1 - self.to.1,
         );
         (cx, cy, ((cx).powf(2.) + (cy).powf(2.)).sqrt())
     }
@@ -293,11 +383,11 @@ impl ShapeDrawer {
         let (fx, fy, mx, my) = (
             self.from.0 as f64,
             self.from.1 as f64,
-            self.mouse_info.position.0 as f64,
-            self.mouse_info.position.1 as f64,
+            self.to.0 as f64,
+            self.to.1 as f64,
         );
         let mut rect = kurbo::Rect::new(fx, fy, mx, my);
-        if self.mouse_info.modifiers.shift {
+        if self.square {
             let (dx, dy) = (mx - fx, my - fy);
             let (dx, dy) = (dx.abs(), dy.abs());
             let size = f64::max(dx, dy);
@@ -310,6 +400,11 @@ impl ShapeDrawer {
         ));
         let path = kurbo::BezPath::from_vec(match stype {
             ShapeType::Oval => kurbo::Ellipse::from_rect(rect).path_elements(1.0).collect(),
+            ShapeType::Superellipse => {
+                paths::superellipse(rect.abs(), self.sdata.superellipse_exponent as f64)
+                    .elements()
+                    .to_vec()
+            }
             ShapeType::RoundedRectangle => {
                 kurbo::RoundedRect::from_rect(rect, self.sdata.rrect_radius as f64)
                     .path_elements(1.0)
@@ -329,6 +424,108 @@ impl ShapeDrawer {
     }
 }
 
+impl ShapeDrawer {
+    // The angle from the centre towards `to`, in radians.
+    fn drag_angle(&self) -> f64 {
+        ((self.to.1 - self.from.1) as f64).atan2((self.to.0 - self.from.0) as f64)
+    }
+
+    // Shapes dragged out from their centre. `path` gets the centre, the radius, and whether the drag
+    // went left, which turns spirals clockwise. Closed shapes are turned around instead.
+    fn draw_radial(
+        &self,
+        closed: bool,
+        path: impl FnOnce(kurbo::Point, f64, bool) -> kurbo::BezPath,
+    ) -> Outline<MFEKPointData> {
+        let (cx, _cy, radius) = self.calculate_radius();
+        let center = kurbo::Point::new(self.from.0 as f64, self.from.1 as f64);
+        let path = path(center, radius as f64, cx.is_sign_negative());
+        let mut ret = Outline::from_kurbo(&path);
+        if closed && cx.is_sign_negative() {
+            ret.reverse();
+        }
+        ret
+    }
+
+    fn draw(&mut self, stype: ShapeType) -> Outline<MFEKPointData> {
+        match stype {
+            ShapeType::Circle => self.draw_circle(),
+            ShapeType::Polygon | ShapeType::Star => self.draw_polygon(stype),
+            ShapeType::Oval
+            | ShapeType::Superellipse
+            | ShapeType::Rectangle
+            | ShapeType::RoundedRectangle => self.draw_fits_in_rect(stype),
+            ShapeType::RoundedPolygon => self.draw_radial(true, |center, radius, _| {
+                let radii: Vec<f64> = self.sdata.corner_radii.iter().map(|r| *r as f64).collect();
+                paths::rounded_polygon(
+                    center,
+                    radius,
+                    self.sdata.polygon_sides as usize,
+                    (self.sdata.polygon_angle as f64).to_radians(),
+                    &radii,
+                )
+            }),
+            ShapeType::RingSector => {
+                let start = self.drag_angle();
+                self.draw_radial(true, |center, radius, _| {
+                    paths::ring_sector(
+                        center,
+                        radius,
+                        radius * self.sdata.ring_inner as f64,
+                        start,
+                        (self.sdata.ring_sweep as f64).to_radians(),
+                    )
+                })
+            }
+            ShapeType::Spiral => {
+                let end = self.drag_angle();
+                self.draw_radial(false, |center, radius, clockwise| {
+                    paths::spiral(center, radius, self.sdata.spiral_turns as f64, end, clockwise)
+                })
+            }
+        }
+    }
+}
+
+// Unites the last `count` contours of the active layer, a new shape, with the closed contours it
+// overlaps, or cuts it out of them.
+fn combine(v: &mut Editor, count: usize, combine: Combine) {
+    let op = match combine {
+        Combine::Add => return,
+        Combine::Unite => PathOp::Union,
+        Combine::Subtract => PathOp::Difference,
+    };
+    let outline = v.get_active_layer_ref().outline.clone();
+    let (outline, shape) = outline.split_at(outline.len() - count);
+    if shape.is_empty() {
+        return;
+    }
+    if shape.iter().any(|c| !c.is_closed()) {
+        log::warn!("Only closed shapes can be combined with the outline, so the shape was only added");
+        return;
+    }
+    let shape_path = shape.to_vec().to_skia_paths(None).combined();
+    let bounds = *shape_path.bounds();
+    // Skeletons and Spiro contours are left alone, as they'd lose their nibs and knots.
+    let nibs = v.with_glyph(|glyph| nibs_in(&glyph.lib));
+    let (under, mut rest): (Vec<_>, Vec<_>) = outline.iter().cloned().partition(|c| {
+        if !c.is_closed() || c.operation().is_some() || nib_of(&nibs, c).is_some() || is_spiro(c) {
+            return false;
+        }
+        let b = *vec![c.clone()].to_skia_paths(None).combined().bounds();
+        b.left < bounds.right && bounds.left < b.right && b.top < bounds.bottom && bounds.top < b.bottom
+    });
+
+    let under_path = under.to_skia_paths(None).combined();
+    match under_path.op(&shape_path, op).and_then(|path| path.as_winding()) {
+        Some(result) => {
+            rest.extend(Outline::<MFEKPointData>::from_skia_path(&result).iter().map(|c| c.into()));
+            v.get_active_layer_mut().outline = rest;
+        }
+        None => log::warn!("Failed to combine the shape with the outline, so it was only added"),
+    }
+}
+
 impl Shapes {
     fn describe_history_entry(&self) -> String {
         let shape_type = self.stype;
@@ -351,48 +548,89 @@ impl Shapes {
     fn mouse_moved(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
         if let Some(pos) = self.pressed_pos {
             self.locked_angle = mouse_info.modifiers.ctrl;
-            if self.dropped_shape {
-                let layer = v.get_active_layer_mut();
-                if layer.outline.len() > 0 {
-                    layer.outline.remove(layer.outline.len() - 1);
-                }
-            }
+            self.remove_dropped_shape(v);
 
             let mut sd = ShapeDrawer {
-                mouse_info,
                 from: pos,
-                sdata: self.sdata,
+                to: mouse_info.position,
+                square: mouse_info.modifiers.shift,
+                sdata: self.sdata.clone(),
                 corners: None,
             };
-            let o = match self.stype {
-                ShapeType::Circle => sd.draw_circle(),
-                ShapeType::Oval | ShapeType::Rectangle | ShapeType::RoundedRectangle => {
-                    sd.draw_fits_in_rect(self.stype)
-                }
-                ShapeType::Polygon | ShapeType::Star => {
-                    if !self.locked_angle {
-                        self.sdata.polygon_angle = sd.polygon_angle();
-                    }
-                    sd.draw_polygon(self.stype)
-                }
-            };
+            if self.stype.has_polygon_angle() && !self.locked_angle {
+                self.sdata.polygon_angle = sd.polygon_angle();
+                sd.sdata.polygon_angle = self.sdata.polygon_angle;
+            }
+            let o = sd.draw(self.stype);
             self.corners = sd.corners;
-
-            let mfek_o: Vec<MFEKContour<MFEKPointData>> =
-                o.iter().map(|e| e.into()).collect();
-            v.get_active_layer_mut().outline.extend(mfek_o);
-            self.dropped_shape = true;
+            self.drop_shape(v, o);
         }
     }
 
-    fn mouse_released(&mut self, v: &mut Editor, _mouse_info: MouseInfo) {
+    fn mouse_released(&mut self, v: &mut Editor, mouse_info: MouseInfo) {
+        if self.dropped_contours > 0 {
+            combine(v, self.dropped_contours, Combine::from(mouse_info));
+        }
         v.redescribe_modification(self.describe_history_entry());
         v.end_modification();
         self.pressed_pos = None;
-        self.dropped_shape = false;
+        self.dropped_contours = 0;
         self.corners = None;
     }
 
+    fn drop_shape(&mut self, v: &mut Editor, o: Outline<MFEKPointData>) {
+        let mfek_o: Vec<MFEKContour<MFEKPointData>> =
+            o.iter().map(|e| e.into()).collect();
+        self.dropped_contours = mfek_o.len();
+        v.get_active_layer_mut().outline.extend(mfek_o);
+    }
+
+    fn remove_dropped_shape(&mut self, v: &mut Editor) {
+        if self.dropped_contours > 0 {
+            let layer = v.get_active_layer_mut();
+            let len = layer.outline.len();
+            layer.outline.truncate(len.saturating_sub(self.dropped_contours));
+            self.dropped_contours = 0;
+        }
+    }
+
+    /// Adds the current shape where the numbers in the dialog put it.
+    fn place(&mut self, v: &mut Editor) {
+        if v.is_modifying() {
+            return;
+        }
+        let p = self.placement;
+        let (from, to, pivot) = if self.stype.fits_in_rect() {
+            let to = (p.x + p.width, p.y + p.height);
+            ((p.x, p.y), to, (p.x + p.width / 2., p.y + p.height / 2.))
+        } else {
+            ((p.x, p.y), (p.x + p.width, p.y), (p.x, p.y))
+        };
+        let mut sdata = self.sdata.clone();
+        sdata.polygon_angle = 0.;
+        let mut sd = ShapeDrawer {
+            from,
+            to,
+            square: false,
+            sdata,
+            corners: None,
+        };
+        let mut o = sd.draw(self.stype);
+
+        let (px, py) = (pivot.0 as f64, pivot.1 as f64);
+        let rotation = Affine::translate((px, py))
+            * Affine::rotate((p.rotation as f64).to_radians())
+            * Affine::translate((-px, -py));
+        for point in o.iter_mut().flatten() {
+            transform_point(point, rotation);
+        }
+
+        v.begin_modification(&self.describe_history_entry(), false);
+        self.drop_shape(v, o);
+        self.dropped_contours = 0;
+        v.end_modification();
+    }
+
     fn scroll(&mut self, vertical: i32) {
         let prev = vertical > 0;
         for _ in 0..vertical.abs() {
@@ -0,0 +1,274 @@
+// Paths of the shapes kurbo and skia don't have. Angles are in radians, and arcs are drawn with
+// one cubic per quarter turn or less.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use kurbo::{BezPath, Point, Rect, Vec2};
+
+fn polar(center: Point, radius: f64, angle: f64) -> Point {
+    center + Vec2::from_angle(angle) * radius
+}
+
+/// Adds a circular arc to `path`, starting with a line to its start unless the path is empty.
+fn arc(path: &mut BezPath, center: Point, radius: f64, start: f64, sweep: f64) {
+    let pieces = (sweep.abs() / FRAC_PI_2).ceil().max(1.);
+    let step = sweep / pieces;
+    let k = 4. / 3. * (step / 4.).tan() * radius;
+
+    let p0 = polar(center, radius, start);
+    if path.elements().is_empty() {
+        path.move_to(p0);
+    } else {
+        path.line_to(p0);
+    }
+    for i in 0..pieces as usize {
+        let (a, b) = (start + step * i as f64, start + step * (i + 1) as f64);
+        let (from, to) = (polar(center, radius, a), polar(center, radius, b));
+        path.curve_to(
+            from + Vec2::from_angle(a + FRAC_PI_2) * k,
+            to - Vec2::from_angle(b + FRAC_PI_2) * k,
+            to,
+        );
+    }
+}
+
+/// The superellipse |x/a|ⁿ + |y/b|ⁿ = 1 filling `rect`, one cubic per quadrant. An exponent of 2
+/// is an ellipse, 4 a squircle, and 1 a diamond; below 1 the sides curve inwards.
+pub fn superellipse(rect: Rect, exponent: f64) -> BezPath {
+    let center = rect.center();
+    let (a, b) = (rect.width() / 2., rect.height() / 2.);
+    // The handle length, as a fraction of the radius, which puts the middle of each quadrant's
+    // cubic on the curve where it crosses the diagonal.
+    let k = (2f64.powf(-1. / exponent) - 0.5) / 0.375;
+
+    // The point at parametric angle `t`, and the tangent there scaled to a quarter's handle.
+    let at = |t: f64| {
+        let (sin, cos) = t.sin_cos();
+        (
+            Point::new(center.x + a * cos, center.y + b * sin),
+            Vec2::new(-a * sin, b * cos) * k,
+        )
+    };
+    let mut path = BezPath::new();
+    path.move_to(at(0.).0);
+    for quadrant in 0..4 {
+        let (from, from_tangent) = at(FRAC_PI_2 * quadrant as f64);
+        let (to, to_tangent) = at(FRAC_PI_2 * (quadrant + 1) as f64);
+        path.curve_to(from + from_tangent, to - to_tangent, to);
+    }
+    path.close_path();
+    path
+}
+
+/// A ring sector between radii `inner` and `outer`, from `start` sweeping `sweep` anticlockwise.
+/// With an `inner` of zero it's a pie slice, and with a full sweep it's a ring.
+pub fn ring_sector(center: Point, outer: f64, inner: f64, start: f64, sweep: f64) -> BezPath {
+    let mut path = BezPath::new();
+    if sweep.abs() >= 2. * PI - 1e-6 {
+        arc(&mut path, center, outer, start, 2. * PI);
+        path.close_path();
+        if inner > 0. {
+            let mut hole = BezPath::new();
+            arc(&mut hole, center, inner, start, -2. * PI);
+            hole.close_path();
+            for el in hole.elements() {
+                path.push(*el);
+            }
+        }
+        return path;
+    }
+
+    arc(&mut path, center, outer, start, sweep);
+    if inner > 0. {
+        arc(&mut path, center, inner, start + sweep, -sweep);
+    } else {
+        path.line_to(center);
+    }
+    path.close_path();
+    path
+}
+
+/// An open Archimedean spiral from `center` out to `radius` in `turns` turns, ending at angle
+/// `end`, winding anticlockwise outwards or, if `clockwise`, clockwise.
+pub fn spiral(center: Point, radius: f64, turns: f64, end: f64, clockwise: bool) -> BezPath {
+    let total = 2. * PI * turns.max(0.05);
+    let sign = if clockwise { -1. } else { 1. };
+    let pieces = (total / (PI / 4.)).ceil();
+    let step = total / pieces;
+    let growth = radius / total;
+
+    // Position and derivative by θ, the angle turned from the centre.
+    let at = |theta: f64| {
+        let phi = end + sign * (theta - total);
+        let r = growth * theta;
+        let (sin, cos) = phi.sin_cos();
+        (
+            Point::new(center.x + r * cos, center.y + r * sin),
+            Vec2::new(growth * cos - sign * r * sin, growth * sin + sign * r * cos),
+        )
+    };
+
+    let mut path = BezPath::new();
+    path.move_to(center);
+    for i in 0..pieces as usize {
+        let (p0, d0) = at(step * i as f64);
+        let (p1, d1) = at(step * (i + 1) as f64);
+        path.curve_to(p0 + d0 * (step / 3.), p1 - d1 * (step / 3.), p1);
+    }
+    path
+}
+
+/// A regular polygon whose corners are rounded each with their own radius. The first corner is at
+/// angle `rotation`; corners past the end of `radii` are sharp. Empty if `radius` isn't positive.
+pub fn rounded_polygon(
+    center: Point,
+    radius: f64,
+    sides: usize,
+    rotation: f64,
+    radii: &[f64],
+) -> BezPath {
+    if !(radius > 0. && radius.is_finite()) {
+        return BezPath::new();
+    }
+    let sides = sides.max(3);
+    let turn = 2. * PI / sides as f64;
+    let vertices: Vec<Point> = (0..sides)
+        .map(|i| polar(center, radius, rotation + turn * i as f64))
+        .collect();
+    let side = vertices[0].distance(vertices[1]);
+    // Half the corner's inside angle.
+    let half_angle = (PI - turn) / 2.;
+
+    let mut path = BezPath::new();
+    for (i, vertex) in vertices.iter().enumerate() {
+        let prev = vertices[(i + sides - 1) % sides];
+        let next = vertices[(i + 1) % sides];
+        // How far the rounding reaches along each side, at most halfway.
+        let reach = (radii.get(i).copied().unwrap_or(0.).max(0.) / half_angle.tan()).min(side / 2.);
+        let enter = *vertex + (prev - *vertex).normalize() * reach;
+        let leave = *vertex + (next - *vertex).normalize() * reach;
+
+        if i == 0 {
+            path.move_to(enter);
+        } else {
+            path.line_to(enter);
+        }
+        if reach > 0. {
+            let corner_radius = reach * half_angle.tan();
+            let handle = 4. / 3. * (turn / 4.).tan() * corner_radius / reach;
+            path.curve_to(
+                enter + (*vertex - enter) * handle,
+                leave + (*vertex - leave) * handle,
+                leave,
+            );
+        }
+    }
+    path.close_path();
+    path
+}
+
+#[cfg(test)]
+fn all_finite(path: &BezPath) -> bool {
+    use kurbo::PathEl;
+    path.elements().iter().all(|el| match el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => p.is_finite(),
+        PathEl::QuadTo(a, b) => a.is_finite() && b.is_finite(),
+        PathEl::CurveTo(a, b, c) => a.is_finite() && b.is_finite() && c.is_finite(),
+        PathEl::ClosePath => true,
+    })
+}
+
+#[test]
+fn superellipse_crosses_diagonal_on_curve() {
+    use kurbo::{ParamCurve as _, PathSeg};
+    let rect = Rect::new(0., 0., 200., 100.);
+    for exponent in [1., 2., 4.] {
+        let path = superellipse(rect, exponent);
+        let cubics = path
+            .segments()
+            .filter(|seg| matches!(seg, PathSeg::Cubic(_)));
+        assert_eq!(cubics.count(), 4);
+        let first = match path.segments().next() {
+            Some(PathSeg::Cubic(cubic)) => cubic,
+            _ => panic!("expected a cubic"),
+        };
+        assert_eq!(first.p0, Point::new(200., 50.));
+        let on_curve = 2f64.powf(-1. / exponent);
+        let mid = first.eval(0.5);
+        assert!((mid.x - (100. + 100. * on_curve)).abs() < 1e-9);
+        assert!((mid.y - (50. + 50. * on_curve)).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn ring_sector_areas() {
+    use kurbo::Shape as _;
+    let center = Point::new(10., 20.);
+    let ring = ring_sector(center, 100., 50., 0., 2. * PI);
+    assert_eq!(
+        ring.elements()
+            .iter()
+            .filter(|el| matches!(el, kurbo::PathEl::MoveTo(_)))
+            .count(),
+        2
+    );
+    let expected = PI * (100f64.powi(2) - 50f64.powi(2));
+    assert!((ring.area().abs() - expected).abs() < expected * 1e-3);
+
+    let pie = ring_sector(center, 100., 0., 0., FRAC_PI_2);
+    let expected = PI * 100f64.powi(2) / 4.;
+    assert!((pie.area().abs() - expected).abs() < expected * 1e-3);
+}
+
+#[test]
+fn spiral_ends() {
+    use kurbo::ParamCurve as _;
+    let center = Point::new(0., 0.);
+    for clockwise in [false, true] {
+        let path = spiral(center, 100., 2., FRAC_PI_2, clockwise);
+        let elements = path.elements();
+        assert_eq!(elements[0], kurbo::PathEl::MoveTo(center));
+        assert!(!matches!(elements.last(), Some(kurbo::PathEl::ClosePath)));
+        // Two turns, in eighths.
+        assert_eq!(elements.len(), 1 + 16);
+        let end = path.segments().last().unwrap().end();
+        assert!((end - Point::new(0., 100.)).hypot() < 1e-9);
+    }
+}
+
+#[test]
+fn rounded_polygon_corners() {
+    use kurbo::ParamCurve as _;
+    let center = Point::new(0., 0.);
+    assert!(rounded_polygon(center, 0., 5, 0., &[10.])
+        .elements()
+        .is_empty());
+    assert!(rounded_polygon(center, f64::NAN, 5, 0., &[])
+        .elements()
+        .is_empty());
+
+    // Without radii it's the plain polygon.
+    let square = rounded_polygon(center, 100., 4, 0., &[]);
+    assert!(all_finite(&square));
+    assert_eq!(square.segments().count(), 4);
+    assert_eq!(
+        square.elements()[0],
+        kurbo::PathEl::MoveTo(Point::new(100., 0.))
+    );
+
+    // Rounded, every corner is a curve inside the polygon, and overlarge radii stop halfway along
+    // the sides.
+    for r in [10., 1e6] {
+        let path = rounded_polygon(center, 100., 4, 0., &[r; 4]);
+        assert!(all_finite(&path));
+        assert_eq!(
+            path.segments()
+                .filter(|seg| matches!(seg, kurbo::PathSeg::Cubic(_)))
+                .count(),
+            4
+        );
+        assert!(path
+            .segments()
+            .all(|seg| seg.end().distance(center) <= 100. + 1e-9));
+    }
+}
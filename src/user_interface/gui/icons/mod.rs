@@ -7,7 +7,7 @@ pub const KNIFE: &str = "\u{F000}";
 pub const MEASURE: &str = "\u{F001}";
 pub const PAN: &str = "\u{F002}";
 pub const PEN: &str = "\u{F003}";
-pub const _PENCIL: &str = "\u{F004}";
+pub const PENCIL: &str = "\u{F004}";
 pub const SELECT: &str = "\u{F005}";
 pub const SHAPES: &str = "\u{F006}";
 pub const _TEXT: &str = "\u{F007}";
@@ -35,7 +35,9 @@ pub const GUIDELINES: &str = "\u{F030}";
 pub const HYPERBEZIER: &str = "\u{F031}";
 pub const QUADRATIC: &str = "\u{F032}";
 //pub const BSPLINE: &str = "\u{F033}";
-//pub const SPIRO: &str = "\u{F034}";
+pub const SPIRO: &str = "\u{F034}";
 pub const DASH: &str = "\u{F035}";
+// There's no nib glyph in the icon font yet, so the button is labeled with text.
+pub const NIB: &str = "Nib";
 pub const _GLOBE: &str = "\u{F036}";
 pub const _UFO: &str = "\u{F037}";
@@ -39,6 +39,23 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 if ui.button("Redo").clicked() {
                     v.redo();
                 }
+                ui.separator();
+                if ui.button("Harmonize").clicked() {
+                    v.harmonize_selection();
+                }
+                if ui.button("Offset Path…").clicked() {
+                    wm.offset.set_open(true);
+                }
+                ui.separator();
+                if ui.button("Add Component…").clicked() {
+                    wm.components.set_open(true);
+                }
+                if ui.button("Decompose All Components").clicked() {
+                    v.decompose_all_components();
+                }
+                if ui.button("Build Composite…").clicked() {
+                    v.build_composite_prompt(i);
+                }
             });
 
             //
@@ -69,8 +86,28 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 });
 
                 ui.checkbox(&mut i.grid.show, "Grid");
-                ui.checkbox(&mut i.curvature_vis, "Curvature Visualization");
-                ui.checkbox(&mut i.measure.enabled, "Show Measure");
+                ui.menu_button("Snapping", |ui| {
+                    ui.checkbox(&mut i.snap.enabled, "Enabled");
+                    ui.separator();
+                    ui.checkbox(&mut i.snap.grid, "Grid");
+                    ui.checkbox(&mut i.snap.guidelines, "Guidelines");
+                    ui.checkbox(&mut i.snap.metrics, "Metrics");
+                    ui.checkbox(&mut i.snap.points, "Points");
+                    ui.checkbox(&mut i.snap.extrema, "Extrema");
+                    ui.separator();
+                    ui.add(egui::Slider::new(&mut i.snap.angle_step, 5.0..=90.).text("Shift angle (°)"));
+                    ui.add(egui::Slider::new(&mut i.snap.pen_angle_step, 5.0..=90.).text("Pen Shift angle (°)"));
+                });
+                ui.checkbox(&mut i.curvature_vis.enabled, "Curvature Visualization");
+                ui.menu_button("Measure", |ui| {
+                    ui.checkbox(&mut i.measure.enabled, "Show Measure");
+                    ui.checkbox(&mut i.measure.pin, "Pin New Lines");
+                    let pinned = !i.measure.pinned.is_empty();
+                    if ui.add_enabled(pinned, egui::Button::new("Clear Pinned Lines")).clicked() {
+                        i.measure.pinned.clear();
+                    }
+                });
+                ui.checkbox(&mut i.text_preview.enabled, "Text Preview");
             });
 
             //
@@ -84,6 +121,34 @@ pub fn menu_bar(ctx: &Context, v: &mut Editor, i: &mut Interface, wm: &mut Windo
                 let mut grid_open = wm.grid.open();
                 ui.checkbox(&mut grid_open, "Grid");
                 wm.grid.set_open(grid_open);
+
+                let mut curvature_open = wm.curvature.open();
+                ui.checkbox(&mut curvature_open, "Curvature");
+                wm.curvature.set_open(curvature_open);
+
+                let mut offset_open = wm.offset.open();
+                ui.checkbox(&mut offset_open, "Offset Path");
+                wm.offset.set_open(offset_open);
+
+                let mut components_open = wm.components.open();
+                ui.checkbox(&mut components_open, "Components");
+                wm.components.set_open(components_open);
+
+                let mut anchors_open = wm.anchors.open();
+                ui.checkbox(&mut anchors_open, "Anchors");
+                wm.anchors.set_open(anchors_open);
+
+                let mut metrics_open = wm.metrics.open();
+                ui.checkbox(&mut metrics_open, "Metrics Bar");
+                wm.metrics.set_open(metrics_open);
+
+                let mut text_preview_open = wm.text_preview.open();
+                ui.checkbox(&mut text_preview_open, "Text Preview");
+                wm.text_preview.set_open(text_preview_open);
+
+                let mut background_open = wm.background.open();
+                ui.checkbox(&mut background_open, "Background Glyph");
+                wm.background.set_open(background_open);
             })
         })
     });
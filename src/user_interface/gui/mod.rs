@@ -35,6 +35,13 @@ pub fn build_ui(
         wm.layer_list.build(ctx, v, i);
         wm.inspector.build(ctx, v, i);
         wm.grid.build(ctx, v, i);
+        wm.curvature.build(ctx, v, i);
+        wm.offset.build(ctx, v, i);
+        wm.components.build(ctx, v, i);
+        wm.anchors.build(ctx, v, i);
+        wm.metrics.build(ctx, v, i);
+        wm.text_preview.build(ctx, v, i);
+        wm.background.build(ctx, v, i);
         wm.tool.build(ctx, v, i);
 
         if i.active_prompts() {
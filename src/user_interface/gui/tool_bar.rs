@@ -50,6 +50,7 @@ pub fn tool_bar(ctx: &Context, v: &mut Editor, _i: &mut Interface) {
                 build_button(v, ui, icons::PAN, ToolEnum::Pan);
                 build_button(v, ui, icons::SELECT, ToolEnum::Select);
                 build_button(v, ui, icons::PEN, ToolEnum::Pen);
+                build_button(v, ui, icons::PENCIL, ToolEnum::Pencil);
                 build_button(v, ui, icons::KNIFE, ToolEnum::Cut);
                 ui.separator();
                 build_button(v, ui, icons::ZOOM, ToolEnum::Zoom);
@@ -58,6 +59,7 @@ pub fn tool_bar(ctx: &Context, v: &mut Editor, _i: &mut Interface) {
                 build_button(v, ui, icons::VWS, ToolEnum::VWS);
                 build_button(v, ui, icons::PAP, ToolEnum::PAP);
                 build_button(v, ui, icons::DASH, ToolEnum::Dash);
+                build_button(v, ui, icons::NIB, ToolEnum::Nib);
                 ui.separator();
                 build_button(v, ui, icons::ANCHOR, ToolEnum::Anchors);
                 build_button(v, ui, icons::SHAPES, ToolEnum::Shapes);
@@ -3,12 +3,26 @@ use egui::Context;
 use crate::user_interface::gui::windows::inspection_window::InspectionWindow;
 use crate::{editor::Editor, user_interface::Interface};
 
+use super::windows::anchor_window::AnchorWindow;
+use super::windows::background_window::BackgroundWindow;
+use super::windows::components_window::ComponentsWindow;
+use super::windows::curvature_window::CurvatureWindow;
 use super::windows::grid_window::GridWindow;
 use super::windows::layer_list::LayerList;
+use super::windows::metrics_bar::MetricsBar;
+use super::windows::offset_window::OffsetWindow;
+use super::windows::text_preview_window::TextPreviewWindow;
 use super::windows::tool_window::ToolWindow;
 pub struct WindowManager {
     pub inspector: InspectionWindow,
     pub grid: GridWindow,
+    pub curvature: CurvatureWindow,
+    pub offset: OffsetWindow,
+    pub components: ComponentsWindow,
+    pub anchors: AnchorWindow,
+    pub metrics: MetricsBar,
+    pub text_preview: TextPreviewWindow,
+    pub background: BackgroundWindow,
     pub tool: ToolWindow,
     pub layer_list: LayerList,
 }
@@ -18,6 +32,13 @@ impl WindowManager {
         Self {
             inspector: InspectionWindow::new(),
             grid: GridWindow::new(),
+            curvature: CurvatureWindow::new(),
+            offset: OffsetWindow::new(),
+            components: ComponentsWindow::new(),
+            anchors: AnchorWindow::new(),
+            metrics: MetricsBar::new(),
+            text_preview: TextPreviewWindow::new(),
+            background: BackgroundWindow::new(),
             tool: ToolWindow::new(),
             layer_list: LayerList::new(),
         }
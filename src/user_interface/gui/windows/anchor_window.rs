@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::{
+        anchors::{anchor_warnings, ANCHOR_PRESETS},
+        ufo_index::UfoIndex,
+        Editor,
+    },
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::{Color32, Context};
+
+pub struct AnchorWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+    // Base anchor names used by the UFO's other glyphs, found again whenever its index is read again.
+    ufo_base_names: Option<(Rc<UfoIndex>, HashSet<String>)>,
+}
+
+impl AnchorWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+            ufo_base_names: None,
+        }
+    }
+
+    fn base_names(v: &Editor, index: &UfoIndex) -> HashSet<String> {
+        let current = v.with_glyph(|g| g.name.clone());
+        index
+            .others(&current)
+            .flat_map(|glif| glif.anchors.iter())
+            .filter_map(|a| a.class.clone())
+            .filter(|n| !n.starts_with('_'))
+            .collect()
+    }
+}
+
+impl GlifWindow for AnchorWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, _i: &mut Interface) {
+        let edit_buf = &mut self.edit_buf;
+        let ufo_base_names = &mut self.ufo_base_names;
+        egui::Window::new("Anchors")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(100.)
+            .show(ctx, |ui| {
+                let anchors = v.with_glyph(|glif| glif.anchors.clone());
+
+                egui::Grid::new("anchor_list").show(ui, |ui| {
+                    ui.label("Name");
+                    ui.label("X");
+                    ui.label("Y");
+                    ui.end_row();
+
+                    for (idx, anchor) in anchors.iter().enumerate() {
+                        let mut edited = anchor.clone();
+
+                        let mut name = anchor.class.clone().unwrap_or_default();
+                        ui.text_edit_singleline(&mut name);
+                        edited.class = if name.is_empty() { None } else { Some(name) };
+                        edited.x =
+                            egui_parsed_textfield(ui, format!("x{}", idx), anchor.x, edit_buf);
+                        edited.y =
+                            egui_parsed_textfield(ui, format!("y{}", idx), anchor.y, edit_buf);
+
+                        let bounds = v.outline_bounds();
+                        ui.add_enabled_ui(bounds.is_some(), |ui| {
+                            let bounds = bounds.unwrap_or_default();
+                            let center = ui.button("Center");
+                            if center.on_hover_text("Center on the outline's bounds").clicked() {
+                                edited.x = bounds.center_x();
+                            }
+                            // Skia rects are y-down, so `bottom` is the top of the glyph.
+                            let top = ui.button("Top");
+                            if top.on_hover_text("Move to the top of the outline").clicked() {
+                                edited.y = bounds.bottom;
+                            }
+                        });
+
+                        if edited != *anchor {
+                            v.set_anchor(idx, edited);
+                        }
+                        if ui.button("✖").clicked() {
+                            v.remove_anchor(idx);
+                            edit_buf.clear();
+                            // The rest of the rows are out of date now.
+                            break;
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Add:");
+                    for preset in ANCHOR_PRESETS {
+                        if ui.button(*preset).clicked() {
+                            let position = v.preset_anchor_position(preset);
+                            v.add_anchor(preset, position);
+                        }
+                    }
+                });
+
+                let index = v.ufo_index();
+                match ufo_base_names {
+                    Some((scanned, _)) if Rc::ptr_eq(scanned, &index) => (),
+                    _ => *ufo_base_names = Some((Rc::clone(&index), Self::base_names(v, &index))),
+                }
+                let warnings = anchor_warnings(&anchors, &ufo_base_names.as_ref().unwrap().1);
+                if !warnings.is_empty() {
+                    ui.separator();
+                    for warning in warnings {
+                        ui.colored_label(Color32::YELLOW, format!("⚠ {}", warning));
+                    }
+                }
+
+                if ui.button("Rescan UFO").clicked() {
+                    v.invalidate_ufo_index();
+                }
+            });
+    }
+}
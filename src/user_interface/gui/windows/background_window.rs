@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::{
+        background::{Background, BackgroundSource},
+        Editor,
+    },
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::Context;
+
+pub struct BackgroundWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+    // What's being filled in while the glyph has no background yet.
+    draft: Background,
+}
+
+impl BackgroundWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+            draft: Background::new(BackgroundSource::Ufo(String::new())),
+        }
+    }
+}
+
+fn source_is_empty(source: &BackgroundSource) -> bool {
+    match source {
+        BackgroundSource::Ufo(name) => name.is_empty(),
+        BackgroundSource::System { family, .. } => family.is_empty(),
+    }
+}
+
+impl GlifWindow for BackgroundWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, i: &mut Interface) {
+        let edit_buf = &mut self.edit_buf;
+        let draft = &mut self.draft;
+        egui::Window::new("Background Glyph")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(100.)
+            .show(ctx, |ui| {
+                let current = v.background();
+                let mut edited = current.clone().unwrap_or_else(|| draft.clone());
+
+                let from_ufo = matches!(edited.source, BackgroundSource::Ufo(_));
+                ui.horizontal(|ui| {
+                    if ui.radio(from_ufo, "UFO glyph").clicked() && !from_ufo {
+                        edited.source = BackgroundSource::Ufo(String::new());
+                    }
+                    if ui.radio(!from_ufo, "System font").clicked() && from_ufo {
+                        let character = v
+                            .with_glyph(|g| g.unicode.first().copied())
+                            .unwrap_or('a');
+                        edited.source = BackgroundSource::System {
+                            family: "sans-serif".to_string(),
+                            character,
+                        };
+                    }
+                });
+
+                match &mut edited.source {
+                    BackgroundSource::Ufo(name) => {
+                        ui.horizontal(|ui| {
+                            ui.label("Glyph");
+                            *name = egui_parsed_textfield(ui, "glyph", name.clone(), edit_buf);
+                        });
+                    }
+                    BackgroundSource::System { family, character } => {
+                        ui.horizontal(|ui| {
+                            ui.label("Family");
+                            *family = egui_parsed_textfield(ui, "family", family.clone(), edit_buf);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Character");
+                            let entered = egui_parsed_textfield(
+                                ui,
+                                "character",
+                                character.to_string(),
+                                edit_buf,
+                            );
+                            if let Some(c) = entered.chars().next() {
+                                *character = c;
+                            }
+                        });
+                    }
+                }
+
+                ui.add(egui::Slider::new(&mut edited.opacity, 0.0..=1.0).text("Opacity"));
+                ui.horizontal(|ui| {
+                    ui.label("Offset");
+                    edited.offset.0 =
+                        egui_parsed_textfield(ui, "offset_x", edited.offset.0, edit_buf);
+                    edited.offset.1 =
+                        egui_parsed_textfield(ui, "offset_y", edited.offset.1, edit_buf);
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if current.is_some() {
+                        if ui.button("Remove").clicked() {
+                            *draft = edited.clone();
+                            v.set_background(None);
+                        } else if Some(&edited) != current.as_ref() {
+                            v.set_background(Some(edited));
+                        }
+                        if ui.button("Reload").clicked() {
+                            i.background.invalidate();
+                        }
+                    } else {
+                        let ready = !source_is_empty(&edited.source);
+                        if ui.add_enabled(ready, egui::Button::new("Show")).clicked() {
+                            v.set_background(Some(edited.clone()));
+                        }
+                        *draft = edited;
+                    }
+                });
+            });
+    }
+}
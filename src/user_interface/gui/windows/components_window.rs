@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::{
+        components::{compose_matrix, decompose_matrix},
+        Editor,
+    },
+    user_interface::{gui::window::GlifWindow, InputPrompt, Interface},
+};
+use egui::Context;
+
+pub struct ComponentsWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+    new_base: String,
+}
+
+impl ComponentsWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+            new_base: String::new(),
+        }
+    }
+}
+
+/// Opens the glyph component `idx` refers to, asking first if that would lose changes.
+pub fn open_base_glyph(v: &mut Editor, i: &mut Interface, idx: usize) {
+    let base = v.with_glyph(|g| g.components.vec[idx].base.clone());
+    let filename = match v.glyph_filename(&base) {
+        Some(f) => f,
+        None => {
+            log::error!("Can't open base glyph: no glyph named {} beside this one", base);
+            return;
+        }
+    };
+
+    if !v.has_unsaved_changes() {
+        v.load_glif(i, &filename);
+        return;
+    }
+
+    i.push_prompt(InputPrompt::YesNo {
+        question: format!("Open {}? Unsaved changes to this glyph will be lost.\n ", base),
+        afterword: "".to_string(),
+        func: Rc::new(move |v, i, open| {
+            if open {
+                v.load_glif(i, &filename);
+            }
+        }),
+    });
+}
+
+impl GlifWindow for ComponentsWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, i: &mut Interface) {
+        let edit_buf = &mut self.edit_buf;
+        let new_base = &mut self.new_base;
+        egui::Window::new("Components")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(100.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(new_base);
+                    if ui.button("Add").clicked() && !new_base.is_empty() {
+                        if v.add_component(new_base) {
+                            new_base.clear();
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                let bases: Vec<String> =
+                    v.with_glyph(|g| g.components.vec.iter().map(|c| c.base.clone()).collect());
+                for (idx, base) in bases.iter().enumerate() {
+                    if ui
+                        .selectable_label(v.component_idx == Some(idx), base)
+                        .clicked()
+                    {
+                        v.component_idx = Some(idx);
+                        // Cached fields belong to the previously selected component.
+                        edit_buf.clear();
+                    }
+                }
+
+                let idx = match v.component_idx {
+                    Some(idx) if idx < bases.len() => idx,
+                    _ => return,
+                };
+
+                ui.separator();
+
+                let matrix = v.component_matrix(idx);
+                let decomposed = decompose_matrix(matrix);
+                let (mut offset, mut scale, mut rotation, skew) = decomposed;
+
+                egui::Grid::new("component_transform").show(ui, |ui| {
+                    ui.label("Offset");
+                    offset.0 = egui_parsed_textfield(ui, "ox", offset.0, edit_buf);
+                    offset.1 = egui_parsed_textfield(ui, "oy", offset.1, edit_buf);
+                    ui.end_row();
+
+                    ui.label("Scale");
+                    scale.0 = egui_parsed_textfield(ui, "sx", scale.0, edit_buf);
+                    scale.1 = egui_parsed_textfield(ui, "sy", scale.1, edit_buf);
+                    ui.end_row();
+
+                    ui.label("Rotation");
+                    rotation = egui_parsed_textfield(ui, "rot", rotation, edit_buf);
+                    ui.end_row();
+                });
+
+                // Only recompose on an edit, the round trip isn't bit-exact.
+                if (offset, scale, rotation, skew) != decomposed {
+                    v.set_component_matrix(idx, compose_matrix(offset, scale, rotation, skew));
+                }
+
+                ui.collapsing("Matrix", |ui| {
+                    let mut coeffs = matrix.as_coeffs();
+                    egui::Grid::new("component_matrix").show(ui, |ui| {
+                        for (n, coeff) in coeffs.iter_mut().enumerate() {
+                            *coeff = egui_parsed_textfield(ui, &format!("m{}", n), *coeff, edit_buf);
+                            if n % 2 == 1 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+                    if coeffs != matrix.as_coeffs() {
+                        v.set_component_matrix(idx, kurbo::Affine::new(coeffs));
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Open Base Glyph").clicked() {
+                        open_base_glyph(v, i, idx);
+                    }
+                    if ui.button("Decompose").clicked() {
+                        v.decompose_components(&[idx]);
+                    }
+                    if ui.button("Delete").clicked() {
+                        v.delete_component(idx);
+                    }
+                });
+                if ui.button("Decompose All").clicked() {
+                    v.decompose_all_components();
+                }
+            });
+    }
+}
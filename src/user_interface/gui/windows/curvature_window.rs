@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::Editor,
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::Context;
+
+pub struct CurvatureWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+}
+
+impl CurvatureWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+        }
+    }
+}
+
+impl GlifWindow for CurvatureWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, i: &mut Interface) {
+        egui::Window::new("Curvature")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(100.)
+            .show(ctx, |ui| {
+                let cv = &mut i.curvature_vis;
+                ui.checkbox(&mut cv.enabled, "Active");
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Scale");
+                    cv.scale = egui_parsed_textfield(ui, "scale", cv.scale, &mut self.edit_buf);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Density");
+                    cv.density =
+                        egui_parsed_textfield(ui, "density", cv.density, &mut self.edit_buf);
+                });
+
+                ui.checkbox(&mut cv.all_contours, "All contours");
+                ui.checkbox(&mut cv.show_inflections, "Inflections");
+                ui.checkbox(&mut cv.show_discontinuities, "Discontinuities");
+            });
+    }
+}
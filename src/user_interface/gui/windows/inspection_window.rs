@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     contour_operations::ContourOperationBuild as _,
-    editor::Editor,
+    editor::{quadratic::DEFAULT_QUADRATIC_TOLERANCE, Editor},
     user_interface::{gui::window::GlifWindow, Interface},
 };
 use egui::Context;
@@ -19,6 +19,7 @@ pub struct InspectionWindow {
     // is this window open?
     open: bool,
     edit_buf: HashMap<String, String>,
+    quad_tolerance: f64,
 }
 
 impl InspectionWindow {
@@ -26,6 +27,7 @@ impl InspectionWindow {
         InspectionWindow {
             open: false,
             edit_buf: HashMap::new(),
+            quad_tolerance: DEFAULT_QUADRATIC_TOLERANCE,
         }
     }
 }
@@ -56,8 +58,10 @@ impl GlifWindow for InspectionWindow {
                         .expect("Editor should have valid selection!");
                     // do contour stuff
 
+                    // Set when the contour's points were replaced, making `point` stale.
+                    let mut restructured = false;
                     ui.collapsing("Contour", |ui| {
-                        let mut contour = v.get_active_layer_ref().outline[ci].clone();
+                        let contour = v.get_active_layer_ref().outline[ci].clone();
                         let ci = v.contour_idx.expect("Expected a selected contour w/o one");
 
                         ui.label(format!("Type: {:?}", contour.get_type()));
@@ -70,6 +74,13 @@ impl GlifWindow for InspectionWindow {
                             }
                         }
 
+                        if contour.get_type() != MFEKContourInnerType::Quad {
+                            if ui.button("Convert to Quadratic").clicked() {
+                                v.convert_contour_to_quad(ci, self.quad_tolerance);
+                                restructured = true;
+                            }
+                        }
+
                         if contour.operation().is_some() {
                             if ui.button("Apply Contour Operation").clicked() {
                                 let outline = contour.operation().build(&contour);
@@ -99,16 +110,27 @@ impl GlifWindow for InspectionWindow {
 
                         if open != contour.is_open() {
                             if open {
-                                contour.set_open();
+                                v.open_contour_at(ci, pi);
                             } else {
-                                contour.set_closed();
+                                v.close_contour(ci);
                             }
-
-                            v.begin_modification("Modified contour with inspector.", true);
-                            v.get_active_layer_mut().outline[ci] = contour;
-                            v.end_modification();
+                            restructured = true;
                         }
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Set Start Point").clicked() {
+                                v.set_start_point(ci, pi);
+                                restructured = true;
+                            }
+                            if contour.is_closed() && ui.button("Split Here").clicked() {
+                                v.split_contour_at(ci, pi);
+                                restructured = true;
+                            }
+                        });
                     });
+                    if restructured {
+                        return;
+                    }
                     ui.collapsing("Point", |ui| {
                         ui.label("Position:");
                         point.set_position(
@@ -260,6 +282,21 @@ impl GlifWindow for InspectionWindow {
                 } else {
                     ui.label("No point selected to inspect!");
                 }
+
+                ui.collapsing("Layer", |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Quadratic tolerance");
+                        self.quad_tolerance = egui_parsed_textfield(
+                            ui,
+                            "quad_tolerance",
+                            self.quad_tolerance,
+                            &mut self.edit_buf,
+                        );
+                    });
+                    if ui.button("Convert Layer to Quadratic").clicked() {
+                        v.convert_layer_to_quad(self.quad_tolerance);
+                    }
+                });
             });
     }
 }
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use super::egui_parsed_textfield;
+use crate::{
+    constants::FONT_SCALE_FACTOR,
+    editor::{metrics::Metric, Editor},
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::{Align2, Color32, Context};
+
+/// Numeric LSB, advance width and RSB fields along the bottom of the canvas. Each takes a number
+/// or a metrics expression, see `editor::metrics`.
+pub struct MetricsBar {
+    open: bool,
+    edit_buf: HashMap<String, String>,
+    error: Option<String>,
+}
+
+impl MetricsBar {
+    pub fn new() -> Self {
+        Self {
+            open: true,
+            edit_buf: HashMap::new(),
+            error: None,
+        }
+    }
+}
+
+fn format_metric(value: Option<f32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl GlifWindow for MetricsBar {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, _i: &mut Interface) {
+        if !self.open {
+            return;
+        }
+
+        let edit_buf = &mut self.edit_buf;
+        let error = &mut self.error;
+        egui::Window::new("Metrics")
+            .anchor(Align2::CENTER_BOTTOM, [0., -8. * FONT_SCALE_FACTOR])
+            .title_bar(false)
+            .resizable(false)
+            .enabled(!v.is_modifying())
+            .show(ctx, |ui| {
+                let metrics = v.metrics();
+                ui.horizontal(|ui| {
+                    for (label, id, metric) in [
+                        ("LSB", "lsb", Metric::LeftSidebearing),
+                        ("Advance", "advance", Metric::Advance),
+                        ("RSB", "rsb", Metric::RightSidebearing),
+                    ] {
+                        ui.label(label);
+                        let current = format_metric(metrics.get(metric));
+                        let entered = egui_parsed_textfield(ui, id, current.clone(), edit_buf);
+                        if entered != current && !entered.is_empty() {
+                            *error = v
+                                .set_metric(metric, &entered)
+                                .err()
+                                .map(|e| format!("{}: {}", label, e));
+                        }
+                    }
+                });
+                if let Some(e) = error {
+                    ui.colored_label(Color32::RED, e.as_str());
+                }
+            });
+    }
+}
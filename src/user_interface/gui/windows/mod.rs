@@ -4,9 +4,16 @@ use egui::Ui;
 
 use super::textedit_buffer::EditBuffer;
 
+pub mod anchor_window;
+pub mod background_window;
+pub mod components_window;
+pub mod curvature_window;
 pub mod grid_window;
 pub mod inspection_window;
 pub mod layer_list;
+pub mod metrics_bar;
+pub mod offset_window;
+pub mod text_preview_window;
 pub mod tool_window;
 
 pub fn egui_parsed_textfield<D>(
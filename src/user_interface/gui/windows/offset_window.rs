@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use super::egui_parsed_textfield;
+use crate::{
+    editor::{
+        offset::{OffsetMode, OffsetSettings},
+        Editor,
+    },
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::Context;
+use glifparser::{CapType, JoinType};
+
+pub struct OffsetWindow {
+    // is this window open?
+    open: bool,
+    edit_buf: HashMap<String, String>,
+    settings: OffsetSettings,
+}
+
+impl OffsetWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            edit_buf: HashMap::new(),
+            settings: OffsetSettings::default(),
+        }
+    }
+}
+
+impl GlifWindow for OffsetWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, _i: &mut Interface) {
+        let settings = &mut self.settings;
+        let edit_buf = &mut self.edit_buf;
+        egui::Window::new("Offset Path")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .enabled(!v.is_modifying())
+            .constrain(true)
+            .default_width(100.)
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label("Mode")
+                    .selected_text(format!("{:?}", settings.mode))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut settings.mode, OffsetMode::Outset, "Outset");
+                        ui.selectable_value(&mut settings.mode, OffsetMode::Inset, "Inset");
+                        ui.selectable_value(&mut settings.mode, OffsetMode::Expand, "Expand");
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("Distance");
+                    settings.distance =
+                        egui_parsed_textfield(ui, "distance", settings.distance, edit_buf);
+                });
+
+                egui::ComboBox::from_label("Join Type")
+                    .selected_text(format!("{:?}", settings.join_type))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut settings.join_type, JoinType::Round, "Round");
+                        ui.selectable_value(&mut settings.join_type, JoinType::Circle, "Circle");
+                        ui.selectable_value(&mut settings.join_type, JoinType::Miter, "Miter");
+                        ui.selectable_value(&mut settings.join_type, JoinType::Bevel, "Bevel");
+                    });
+
+                ui.add_enabled_ui(settings.mode == OffsetMode::Expand, |ui| {
+                    egui::ComboBox::from_label("Cap Type")
+                        .selected_text(format!("{:?}", settings.cap_type))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut settings.cap_type, CapType::Round, "Round");
+                            ui.selectable_value(&mut settings.cap_type, CapType::Circle, "Circle");
+                            ui.selectable_value(&mut settings.cap_type, CapType::Square, "Squared");
+                        });
+                });
+
+                ui.separator();
+
+                if ui.button("Apply").clicked() {
+                    v.offset_selection(settings);
+                }
+            });
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    editor::Editor,
+    user_interface::{gui::window::GlifWindow, Interface},
+};
+use egui::Context;
+
+/// Edits the text of the preview strip, see `render::text_preview`.
+pub struct TextPreviewWindow {
+    // is this window open?
+    open: bool,
+}
+
+impl TextPreviewWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+}
+
+impl GlifWindow for TextPreviewWindow {
+    fn open(&self) -> bool {
+        self.open
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    fn build(&mut self, ctx: &Context, v: &mut Editor, i: &mut Interface) {
+        egui::Window::new("Text Preview")
+            .resizable(true)
+            .collapsible(true)
+            .open(&mut self.open)
+            .constrain(true)
+            .default_width(200.)
+            .show(ctx, |ui| {
+                let tp = &mut i.text_preview;
+                ui.checkbox(&mut tp.enabled, "Active");
+                ui.text_edit_singleline(&mut tp.text)
+                    .on_hover_text("Characters, /glyphname, and /current for this glyph");
+                if v.font_dir().is_some() {
+                    ui.label("Click the dot under a pair to kern it.");
+                }
+                if ui.button("Reload").clicked() {
+                    v.invalidate_ufo_index();
+                    tp.invalidate();
+                }
+            });
+    }
+}
@@ -4,6 +4,7 @@ pub mod gui;
 pub mod mouse_input;
 pub mod popout;
 pub mod sdl;
+pub mod snap;
 
 use std::rc::Rc;
 
@@ -22,8 +23,12 @@ use skia_safe::ColorType;
 use skia_safe::RCHandle;
 
 use crate::editor::Editor;
+pub use crate::render::background::BackgroundRenderer;
 pub use crate::render::measure::Measure;
+pub use crate::render::speed_visualization::CurvatureVis;
+pub use crate::render::text_preview::TextPreview;
 pub use crate::user_interface::mouse_input::MouseInfo;
+pub use crate::user_interface::snap::Snap;
 
 use sdl2::{video::Window as SdlWindow, Sdl};
 
@@ -43,7 +48,10 @@ pub struct Interface {
     pub context: Option<(f32, f32)>,
     pub grid: Grid,
     pub measure: Measure,
-    pub curvature_vis: bool,
+    pub curvature_vis: CurvatureVis,
+    pub text_preview: TextPreview,
+    pub background: BackgroundRenderer,
+    pub snap: Snap,
     pub mouse_info: MouseInfo,
     pub viewport: Viewport,
 
@@ -91,8 +99,13 @@ impl Interface {
                 start_point: None,
                 end_point: None,
                 enabled: true,
+                pin: false,
+                pinned: vec![],
             },
-            curvature_vis: true,
+            curvature_vis: CurvatureVis::default(),
+            text_preview: TextPreview::default(),
+            background: BackgroundRenderer::default(),
+            snap: Snap::default(),
             mouse_info: MouseInfo::default(),
             viewport: Viewport::default(),
 
@@ -58,7 +58,7 @@ impl MouseInfo {
         let raw_absolute_mposition = absolute_mposition;
         let raw_mposition = mposition;
 
-        if i.grid.show {
+        if i.grid.show && i.snap.enabled && i.snap.grid && !command_mod.meta {
             let mpos = (mposition.0, mposition.1);
 
             let mut candidates = vec![];
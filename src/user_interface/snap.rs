@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+
+use glifparser::glif::contour::MFEKContourCommon as _;
+use glifparser::glif::point::MFEKPointCommon as _;
+use glifrenderer::viewport::Viewport;
+use skia_safe::{Canvas, Color, Paint, PaintStyle, Path};
+use MFEKmath::{mfek::ResolveCubic, Bezier, Evaluate, Piecewise, Vector};
+
+use super::{Interface, MouseInfo};
+use crate::editor::Editor;
+
+/// What the last call to [`Interface::snap_position`] snapped to, so it can be drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SnapIndicator {
+    Point((f32, f32)),
+    /// An infinite line through `at` along the unit vector `dir`.
+    Line { at: (f32, f32), dir: (f32, f32) },
+    Angle { origin: (f32, f32), to: (f32, f32) },
+}
+
+/// Snapping settings. Holding the Meta key while dragging skips snapping for that drag.
+#[derive(Clone, Debug)]
+pub struct Snap {
+    pub enabled: bool,
+    pub grid: bool,
+    pub guidelines: bool,
+    pub metrics: bool,
+    pub points: bool,
+    pub extrema: bool,
+    /// Step of the Shift angle constraint, in degrees.
+    pub angle_step: f32,
+    /// Step of the pen's Shift angle constraint, in degrees.
+    pub pen_angle_step: f32,
+    /// How close, in screen pixels, the cursor must be to a target to snap to it.
+    pub distance: f32,
+    pub indicators: Vec<SnapIndicator>,
+}
+
+impl Default for Snap {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grid: true,
+            guidelines: true,
+            metrics: true,
+            points: true,
+            extrema: true,
+            angle_step: 15.,
+            pen_angle_step: 45.,
+            distance: 8.,
+            indicators: vec![],
+        }
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+// Foot of the perpendicular from `p` to the line through `at` along unit vector `dir`.
+fn project(p: (f32, f32), at: (f32, f32), dir: (f32, f32)) -> (f32, f32) {
+    let t = (p.0 - at.0) * dir.0 + (p.1 - at.1) * dir.1;
+    (at.0 + dir.0 * t, at.1 + dir.1 * t)
+}
+
+fn intersect(a: ((f32, f32), (f32, f32)), b: ((f32, f32), (f32, f32))) -> Option<(f32, f32)> {
+    let ((p, r), (q, s)) = (a, b);
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() < 1e-6 {
+        return None;
+    }
+    let t = ((q.0 - p.0) * s.1 - (q.1 - p.1) * s.0) / denom;
+    Some((p.0 + r.0 * t, p.1 + r.1 * t))
+}
+
+// Parameters in (0, 1) where one coordinate of the cubic has a local extremum.
+fn extrema_t(bez: &Bezier) -> Vec<f64> {
+    let mut ret = vec![];
+    let axes: [fn(Vector) -> f64; 2] = [|v| v.x, |v| v.y];
+    for axis in axes {
+        let (p0, p1, p2, p3) = (axis(bez.w1), axis(bez.w2), axis(bez.w3), axis(bez.w4));
+        // Derivative divided by 3: a·t² + b·t + c
+        let a = -p0 + 3. * p1 - 3. * p2 + p3;
+        let b = 2. * (p0 - 2. * p1 + p2);
+        let c = p1 - p0;
+        if a.abs() < 1e-9 {
+            if b.abs() > 1e-9 {
+                ret.push(-c / b);
+            }
+            continue;
+        }
+        let disc = b * b - 4. * a * c;
+        if disc < 0. {
+            continue;
+        }
+        ret.push((-b + disc.sqrt()) / (2. * a));
+        ret.push((-b - disc.sqrt()) / (2. * a));
+    }
+    ret.retain(|t| *t > 1e-6 && *t < 1. - 1e-6);
+    ret
+}
+
+impl Snap {
+    fn point_targets(&self, v: &Editor, exclude: &HashSet<(usize, usize)>) -> Vec<(f32, f32)> {
+        let mut ret = vec![];
+        let layer = v.get_active_layer_ref();
+        for (ci, contour) in layer.outline.iter().enumerate() {
+            let moving = exclude.iter().any(|(eci, _)| *eci == ci);
+            if self.points {
+                for pi in 0..contour.len() {
+                    if exclude.contains(&(ci, pi)) {
+                        continue;
+                    }
+                    ret.push(contour.get_point(pi).unwrap().get_position());
+                }
+            }
+            // Extrema of a contour being dragged would chase the cursor.
+            if self.extrema && !moving {
+                let pw: Piecewise<Bezier> = contour.to_cubic().into();
+                for bez in pw.segs.iter() {
+                    for t in extrema_t(bez) {
+                        let at = bez.at(t);
+                        ret.push((at.x as f32, at.y as f32));
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    fn line_targets(&self, v: &Editor) -> Vec<((f32, f32), (f32, f32))> {
+        let mut ret = vec![];
+        if self.metrics {
+            // baseline
+            ret.push(((0., 0.), (1., 0.)));
+        }
+        let local_guidelines = v.with_glyph(|glyph| glyph.guidelines.clone());
+        for guideline in v.guidelines.iter().chain(local_guidelines.iter()) {
+            // Width and UFO-global metrics guidelines are "format" guidelines.
+            let is_metric = guideline.data.as_guideline().format;
+            if (is_metric && !self.metrics) || (!is_metric && !self.guidelines) {
+                continue;
+            }
+            let angle = f32::from(-guideline.angle).to_radians();
+            ret.push(((guideline.at.x, guideline.at.y), (angle.cos(), angle.sin())));
+        }
+        ret
+    }
+}
+
+impl Interface {
+    /// Returns where something dragged to `mouse_info` should go, and records what it snapped to
+    /// for drawing. `origin` is where the drag started, used by the Shift angle constraint, and
+    /// `exclude` lists the points being moved so they aren't snapped to themselves.
+    pub fn snap_position(
+        &mut self,
+        v: &Editor,
+        mouse_info: MouseInfo,
+        origin: Option<(f32, f32)>,
+        exclude: &HashSet<(usize, usize)>,
+    ) -> (f32, f32) {
+        let angle_step = self.snap.angle_step;
+        self.snap_position_by(v, mouse_info, origin, exclude, angle_step)
+    }
+
+    /// As [`Interface::snap_position`], for a new pen point continuing from `origin`, which the
+    /// Shift angle constraint holds to the pen's own, coarser, step.
+    pub fn snap_pen_position(
+        &mut self,
+        v: &Editor,
+        mouse_info: MouseInfo,
+        origin: Option<(f32, f32)>,
+    ) -> (f32, f32) {
+        let angle_step = self.snap.pen_angle_step;
+        self.snap_position_by(v, mouse_info, origin, &HashSet::new(), angle_step)
+    }
+
+    fn snap_position_by(
+        &mut self,
+        v: &Editor,
+        mouse_info: MouseInfo,
+        origin: Option<(f32, f32)>,
+        exclude: &HashSet<(usize, usize)>,
+        angle_step: f32,
+    ) -> (f32, f32) {
+        self.snap.indicators.clear();
+        let raw = mouse_info.raw_position;
+
+        if let (true, Some(origin)) = (mouse_info.modifiers.shift, origin) {
+            let (dx, dy) = (raw.0 - origin.0, raw.1 - origin.1);
+            let step = angle_step.to_radians();
+            let angle = (dy.atan2(dx) / step).round() * step;
+            let (dir_x, dir_y) = (angle.cos(), angle.sin());
+            let length = dx * dir_x + dy * dir_y;
+            let to = (origin.0 + dir_x * length, origin.1 + dir_y * length);
+            self.snap.indicators.push(SnapIndicator::Angle { origin, to });
+            return to;
+        }
+
+        if !self.snap.enabled || mouse_info.modifiers.meta {
+            return raw;
+        }
+
+        let threshold = self.snap.distance / self.viewport.factor;
+
+        let nearest_point = self
+            .snap
+            .point_targets(v, exclude)
+            .into_iter()
+            .map(|p| (distance(raw, p), p))
+            .filter(|(d, _)| *d < threshold)
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        if let Some((_, p)) = nearest_point {
+            self.snap.indicators.push(SnapIndicator::Point(p));
+            return p;
+        }
+
+        let mut lines: Vec<_> = self
+            .snap
+            .line_targets(v)
+            .into_iter()
+            .map(|(at, dir)| (distance(raw, project(raw, at, dir)), at, dir))
+            .filter(|(d, _, _)| *d < threshold)
+            .collect();
+        lines.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        if let Some(&(_, at, dir)) = lines.first() {
+            // Near two lines at once, prefer where they cross.
+            for &(_, at2, dir2) in lines.iter().skip(1) {
+                if let Some(p) = intersect((at, dir), (at2, dir2)) {
+                    if distance(raw, p) < threshold {
+                        self.snap.indicators.push(SnapIndicator::Line { at, dir });
+                        self.snap
+                            .indicators
+                            .push(SnapIndicator::Line { at: at2, dir: dir2 });
+                        self.snap.indicators.push(SnapIndicator::Point(p));
+                        return p;
+                    }
+                }
+            }
+            self.snap.indicators.push(SnapIndicator::Line { at, dir });
+            return project(raw, at, dir);
+        }
+
+        // MouseInfo::new already put `position` on the grid if the grid is active.
+        if self.grid.show && self.snap.grid {
+            return mouse_info.position;
+        }
+
+        raw
+    }
+}
+
+impl Snap {
+    pub fn draw(&self, viewport: &Viewport, canvas: &Canvas) {
+        let factor = viewport.factor;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(1. / factor);
+        paint.set_color(Color::from_argb(200, 255, 0, 255));
+
+        for indicator in &self.indicators {
+            let mut path = Path::new();
+            match *indicator {
+                SnapIndicator::Point((x, y)) => {
+                    let size = 6. / factor;
+                    path.move_to((x - size, y - size));
+                    path.line_to((x + size, y + size));
+                    path.move_to((x + size, y - size));
+                    path.line_to((x - size, y + size));
+                }
+                SnapIndicator::Line { at, dir } => {
+                    let far = 100_000.;
+                    path.move_to((at.0 - dir.0 * far, at.1 - dir.1 * far));
+                    path.line_to((at.0 + dir.0 * far, at.1 + dir.1 * far));
+                }
+                SnapIndicator::Angle { origin, to } => {
+                    path.move_to(origin);
+                    path.line_to(to);
+                }
+            }
+            canvas.draw_path(&path, &paint);
+        }
+    }
+}